@@ -0,0 +1,267 @@
+use std::array;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use group::{Curve, Group};
+use halo2::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{create_proof, keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+    poly::commitment::Params,
+    transcript::{Blake2bWrite, Challenge255},
+};
+use halo2_gadgets::{
+    ecc::{
+        chip::{EccChip, EccConfig, GeneratorFixedBase, NUM_WINDOWS, NUM_WINDOWS_SHORT},
+        FixedPoint, NonIdentityPoint,
+    },
+    utilities::{lookup_range_check::LookupRangeCheckConfig, UtilitiesInstructions},
+};
+use lazy_static::lazy_static;
+use pasta_curves::{arithmetic::FieldExt, pallas, vesta};
+#[cfg(unix)]
+use pprof::criterion::{Output, PProfProfiler};
+use rand::rngs::OsRng;
+
+/// Every single-operation circuit fits in `2^K` rows, including the 10-bit
+/// lookup table.
+const K: u32 = 11;
+
+type FixedBase = GeneratorFixedBase<pallas::Affine>;
+
+lazy_static! {
+    static ref FULL_WIDTH: FixedBase =
+        GeneratorFixedBase::new(pallas::Point::generator().to_affine(), NUM_WINDOWS);
+    static ref SHORT: FixedBase =
+        GeneratorFixedBase::new(pallas::Point::generator().to_affine(), NUM_WINDOWS_SHORT);
+}
+
+/// A single ECC operation, laid out on its own in [`OpCircuit`].
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Add,
+    AddIncomplete,
+    Double,
+    Mul,
+    MulFixed,
+    MulFixedShort,
+    MulFixedBaseFieldElem,
+}
+
+impl Op {
+    fn name(&self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::AddIncomplete => "add_incomplete",
+            Op::Double => "double",
+            Op::Mul => "mul",
+            Op::MulFixed => "mul_fixed",
+            Op::MulFixedShort => "mul_fixed_short",
+            Op::MulFixedBaseFieldElem => "mul_fixed_base_field_elem",
+        }
+    }
+}
+
+/// A circuit that witnesses its inputs and performs a single [`Op`].
+#[derive(Clone, Debug)]
+struct OpCircuit {
+    op: Op,
+    p: Option<pallas::Affine>,
+    q: Option<pallas::Affine>,
+    scalar: Option<pallas::Scalar>,
+    base_field_elem: Option<pallas::Base>,
+    magnitude: Option<pallas::Base>,
+    sign: Option<pallas::Base>,
+}
+
+impl OpCircuit {
+    /// Builds a circuit performing `op` on random inputs.
+    fn random(op: Op) -> Self {
+        let rng = OsRng;
+        OpCircuit {
+            op,
+            p: Some(pallas::Point::random(rng).to_affine()),
+            q: Some(pallas::Point::random(rng).to_affine()),
+            scalar: Some(pallas::Scalar::random(rng)),
+            base_field_elem: Some(pallas::Base::random(rng)),
+            magnitude: Some(pallas::Base::from_u64(0xdead_beef_f00d)),
+            sign: Some(-pallas::Base::one()),
+        }
+    }
+}
+
+impl Circuit<pallas::Base> for OpCircuit {
+    type Config = EccConfig<pallas::Affine>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        OpCircuit {
+            op: self.op,
+            p: None,
+            q: None,
+            scalar: None,
+            base_field_elem: None,
+            magnitude: None,
+            sign: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let advices = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let lookup_table = meta.lookup_table_column();
+        let lagrange_coeffs = [
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+        ];
+        // Shared fixed column for loading constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let range_check = LookupRangeCheckConfig::configure(meta, advices[9], lookup_table);
+        EccChip::<pallas::Affine, FixedBase>::configure(meta, advices, lagrange_coeffs, range_check)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+        config.lookup_config.load(&mut layouter)?;
+
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), self.p)?;
+
+        match self.op {
+            Op::Add => {
+                let q = NonIdentityPoint::new(chip, layouter.namespace(|| "Q"), self.q)?;
+                p.add(layouter.namespace(|| "P + Q"), &q)?;
+            }
+            Op::AddIncomplete => {
+                let q = NonIdentityPoint::new(chip, layouter.namespace(|| "Q"), self.q)?;
+                p.add_incomplete(layouter.namespace(|| "P + Q"), &q)?;
+            }
+            Op::Double => {
+                p.double(layouter.namespace(|| "[2] P"))?;
+            }
+            Op::Mul => {
+                let scalar = chip.load_private(
+                    layouter.namespace(|| "scalar"),
+                    config.advices[0],
+                    self.base_field_elem,
+                )?;
+                p.mul(layouter.namespace(|| "[scalar] P"), &scalar)?;
+            }
+            Op::MulFixed => {
+                let base = FixedPoint::from_inner(chip, FULL_WIDTH.clone());
+                base.mul(layouter.namespace(|| "[scalar] B"), self.scalar)?;
+            }
+            Op::MulFixedShort => {
+                let magnitude = chip.load_private(
+                    layouter.namespace(|| "magnitude"),
+                    config.advices[0],
+                    self.magnitude,
+                )?;
+                let sign =
+                    chip.load_private(layouter.namespace(|| "sign"), config.advices[0], self.sign)?;
+                let base = FixedPoint::from_inner(chip, SHORT.clone());
+                base.mul_short(layouter.namespace(|| "[scalar] B"), (magnitude, sign))?;
+            }
+            Op::MulFixedBaseFieldElem => {
+                let scalar = chip.load_private(
+                    layouter.namespace(|| "scalar"),
+                    config.advices[0],
+                    self.base_field_elem,
+                )?;
+                let base = FixedPoint::from_inner(chip, FULL_WIDTH.clone());
+                base.mul_base_field(layouter.namespace(|| "[scalar] B"), scalar)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn bench_ecc(c: &mut Criterion) {
+    let ops = [
+        Op::Add,
+        Op::AddIncomplete,
+        Op::Double,
+        Op::Mul,
+        Op::MulFixed,
+        Op::MulFixedShort,
+        Op::MulFixedBaseFieldElem,
+    ];
+
+    {
+        let mut group = c.benchmark_group("ecc-mock-prover");
+
+        for op in array::IntoIter::new(ops) {
+            let circuit = OpCircuit::random(op);
+
+            group.bench_function(BenchmarkId::from_parameter(op.name()), |b| {
+                b.iter(|| {
+                    let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+                    assert_eq!(prover.verify(), Ok(()));
+                })
+            });
+        }
+    }
+
+    {
+        let mut group = c.benchmark_group("ecc-prover");
+        // Proving is slow, so take fewer samples.
+        group.sample_size(10);
+
+        let params: Params<vesta::Affine> = Params::new(K);
+
+        for op in array::IntoIter::new(ops) {
+            let circuit = OpCircuit::random(op);
+
+            let empty_circuit = circuit.without_witnesses();
+            let vk = keygen_vk(&params, &empty_circuit).unwrap();
+            let pk = keygen_pk(&params, vk, &empty_circuit).unwrap();
+
+            group.bench_function(BenchmarkId::from_parameter(op.name()), |b| {
+                b.iter(|| {
+                    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+                    create_proof(
+                        &params,
+                        &pk,
+                        std::slice::from_ref(&circuit),
+                        &[&[]],
+                        &mut transcript,
+                    )
+                    .unwrap();
+                    transcript.finalize()
+                })
+            });
+        }
+    }
+}
+
+#[cfg(unix)]
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_ecc
+}
+#[cfg(not(unix))]
+criterion_group!(benches, bench_ecc);
+criterion_main!(benches);
@@ -1,16 +1,80 @@
 //! Gadgets for elliptic curve operations.
 
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 
+use ff::Field;
+use group::prime::PrimeCurveAffine;
 use halo2::{
-    arithmetic::CurveAffine,
+    arithmetic::{CurveAffine, FieldExt},
     circuit::{Chip, Layouter},
-    plonk::Error,
+    plonk::{Column, Error, Instance},
 };
 
-use crate::utilities::UtilitiesInstructions;
+use crate::utilities::{SecretScalar, UtilitiesInstructions, Var};
 
 pub mod chip;
+pub mod pedersen;
+
+/// An error returned by the ECC gadgets.
+///
+/// Where the witnessed values are known, the gadgets check for the cases that
+/// the circuit cannot satisfy before assigning anything, and report them as a
+/// specific variant. Every other failure is a [`EccError::Synthesis`].
+///
+/// `Circuit::synthesize` returns a [`halo2::plonk::Error`], which an `EccError`
+/// converts into with `?`.
+#[derive(Debug)]
+pub enum EccError {
+    /// A point that must not be the identity is the identity.
+    IdentityPoint,
+    /// The inputs to an incomplete addition are an exceptional case, i.e. they
+    /// have the same x-coordinate.
+    ExceptionalAddition,
+    /// A short scalar has a magnitude that does not fit in 64 bits, or a sign
+    /// that is not 1 or -1.
+    OutOfRangeScalar,
+    /// An error returned by the underlying chip.
+    Synthesis(Error),
+}
+
+impl fmt::Display for EccError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EccError::IdentityPoint => write!(f, "point is the identity"),
+            EccError::ExceptionalAddition => {
+                write!(f, "exceptional case in incomplete addition")
+            }
+            EccError::OutOfRangeScalar => write!(f, "short scalar is out of range"),
+            EccError::Synthesis(e) => write!(f, "synthesis error: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for EccError {}
+
+impl From<Error> for EccError {
+    fn from(e: Error) -> Self {
+        EccError::Synthesis(e)
+    }
+}
+
+impl From<EccError> for Error {
+    fn from(e: EccError) -> Self {
+        match e {
+            EccError::Synthesis(e) => e,
+            _ => Error::SynthesisError,
+        }
+    }
+}
+
+/// Returns `true` if `a` and `b` are known to be an exceptional case for
+/// incomplete addition.
+fn is_exceptional_addition<C: CurveAffine>(a: Option<C>, b: Option<C>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a == b || a == -b,
+        _ => false,
+    }
+}
 
 /// Window size for fixed-base scalar multiplication
 pub const FIXED_BASE_WINDOW_SIZE: usize = 3;
@@ -60,6 +124,35 @@ pub trait EccInstructions<C: CurveAffine>:
         b: &Self::Point,
     ) -> Result<(), Error>;
 
+    /// Constrains the coordinates of `point` to the `x_row` and `y_row` cells
+    /// of the `instance` column.
+    fn constrain_point_to_instance(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+        instance: Column<Instance>,
+        x_row: usize,
+        y_row: usize,
+    ) -> Result<(), Error>;
+
+    /// Constrains the windows of the full-width scalar `a` to be equal to the
+    /// windows of the full-width scalar `b`.
+    fn constrain_scalar_fixed_equal(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::ScalarFixed,
+        b: &Self::ScalarFixed,
+    ) -> Result<(), Error>;
+
+    /// Constrains the windows of the full-width scalar `scalar` to recompose
+    /// to its value, returning the cell holding that value reduced modulo the
+    /// base field modulus.
+    fn bind_scalar_fixed(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+    ) -> Result<Self::Var, Error>;
+
     /// Witnesses the given point as a private input to the circuit.
     /// This allows the point to be the identity, mapped to (0, 0) in
     /// affine coordinates.
@@ -69,6 +162,38 @@ pub trait EccInstructions<C: CurveAffine>:
         value: Option<C>,
     ) -> Result<Self::Point, Error>;
 
+    /// Witnesses the given points as private inputs to the circuit, within a
+    /// single region. Each point may be the identity.
+    ///
+    /// The returned points are in the same order as `values`.
+    fn witness_points(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        values: &[Option<C>],
+    ) -> Result<Vec<Self::Point>, Error>;
+
+    /// Witnesses the given point as a private input to the circuit, along
+    /// with a boolean flag that is constrained to be 1 iff the point is the
+    /// identity.
+    fn witness_point_with_flag(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        value: Option<C>,
+    ) -> Result<(Self::Point, Self::Var), Error>;
+
+    /// Witnesses a point from previously assigned affine coordinates,
+    /// constraining them to be either (0, 0) (the identity) or a valid
+    /// curve point.
+    fn witness_point_from_coords(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        x: Self::Var,
+        y: Self::Var,
+    ) -> Result<Self::Point, Error>;
+
+    /// Loads the identity point as a constant, using its `(0, 0)` encoding.
+    fn identity(&self, layouter: &mut impl Layouter<C::Base>) -> Result<Self::Point, Error>;
+
     /// Witnesses the given point as a private input to the circuit.
     /// This returns an error if the point is the identity.
     fn witness_point_non_id(
@@ -80,6 +205,17 @@ pub trait EccInstructions<C: CurveAffine>:
     /// Extracts the x-coordinate of a point.
     fn extract_p<Point: Into<Self::Point> + Clone>(point: &Point) -> Self::X;
 
+    /// Extracts the y-coordinate of a non-identity point.
+    ///
+    /// This is only defined for non-identity points, since the identity has no
+    /// meaningful y-coordinate.
+    fn extract_y(point: &Self::NonIdentityPoint) -> Self::Var;
+
+    /// Returns the value of a point, if known.
+    ///
+    /// The identity is returned for a point with coordinates `(0, 0)`.
+    fn point_value(point: &Self::Point) -> Option<C>;
+
     /// Performs incomplete point addition, returning `a + b`.
     ///
     /// This returns an error in exceptional cases.
@@ -90,6 +226,95 @@ pub trait EccInstructions<C: CurveAffine>:
         b: &Self::NonIdentityPoint,
     ) -> Result<Self::NonIdentityPoint, Error>;
 
+    /// Performs incomplete point addition, returning `a + b` along with a
+    /// boolean flag that is 1 iff `a` and `b` are an exceptional case (that is,
+    /// `a = b` or `a = -b`).
+    ///
+    /// Exceptional cases set the flag instead of returning an error. The
+    /// returned point is only meaningful when the flag is 0.
+    fn try_add_incomplete(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::NonIdentityPoint,
+        b: &Self::NonIdentityPoint,
+    ) -> Result<(Self::NonIdentityPoint, Self::Var), Error>;
+
+    /// Performs incomplete point addition of a fixed constant point, returning
+    /// `a + constant`.
+    ///
+    /// This returns an error if `constant` is the identity, or in exceptional
+    /// cases.
+    fn add_incomplete_constant(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::NonIdentityPoint,
+        constant: C,
+    ) -> Result<Self::NonIdentityPoint, Error>;
+
+    /// Negates a non-identity point, returning `-point`.
+    fn negate(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::NonIdentityPoint,
+    ) -> Result<Self::NonIdentityPoint, Error>;
+
+    /// Doubles a non-identity point, returning `[2] point`.
+    ///
+    /// This returns an error if the point has y-coordinate zero.
+    fn double(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::NonIdentityPoint,
+    ) -> Result<Self::NonIdentityPoint, Error>;
+
+    /// Constrains a point that may be the identity to be a valid non-identity
+    /// curve point, returning it as a non-identity point.
+    ///
+    /// This returns an error if the point is known to be the identity.
+    fn assert_non_identity(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+    ) -> Result<Self::NonIdentityPoint, Error>;
+
+    /// Performs incomplete point addition, returning `a + b`, where `a` may be
+    /// the identity.
+    ///
+    /// `a_is_identity` is part of the circuit structure, and must not depend on
+    /// the witness (e.g. it is set for the initial value of an accumulator). If
+    /// it is set, `a` is constrained to be the identity and `b` is returned.
+    /// Otherwise, both operands are constrained to be non-identity points, and
+    /// this has the same exceptional cases as [`EccInstructions::add_incomplete`].
+    fn add_incomplete_or_copy(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::Point,
+        b: &Self::Point,
+        a_is_identity: bool,
+    ) -> Result<Self::Point, Error>;
+
+    /// Returns a boolean flag that is constrained to be 1 if `a` and `b` are
+    /// equal, and 0 otherwise.
+    fn points_equal(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::Point,
+        b: &Self::Point,
+    ) -> Result<Self::Var, Error>;
+
+    /// Constrains the given point to be on the curve and to have no
+    /// small-order component, i.e. $[h] P \neq \mathcal{O}$ where $h$ is the
+    /// cofactor of the curve. The identity is always rejected.
+    ///
+    /// This does not prove membership in the prime-order subgroup, which would
+    /// require $[q] P = \mathcal{O}$. On a prime-order curve ($h = 1$) the two
+    /// coincide for non-identity points.
+    fn assert_not_small_order(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+    ) -> Result<(), Error>;
+
     /// Performs complete point addition, returning `a + b`.
     fn add<A: Into<Self::Point> + Clone, B: Into<Self::Point> + Clone>(
         &self,
@@ -106,6 +331,31 @@ pub trait EccInstructions<C: CurveAffine>:
         base: &Self::NonIdentityPoint,
     ) -> Result<(Self::Point, Self::ScalarVar), Error>;
 
+    /// Performs variable-base scalar multiplication of the sum of two points,
+    /// returning `[scalar] (a + b)`.
+    ///
+    /// This is equivalent to adding `a` and `b`, asserting that the sum is not
+    /// the identity, and then multiplying it by `scalar`.
+    fn add_then_mul(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::Var,
+        a: &Self::NonIdentityPoint,
+        b: &Self::NonIdentityPoint,
+    ) -> Result<(Self::Point, Self::ScalarVar), Error>;
+
+    /// Decomposes a scalar output by variable-base scalar multiplication into
+    /// its canonical little-endian bits, returning a boolean cell for each bit.
+    ///
+    /// The multiplication does not assign per-bit cells of the scalar (its
+    /// running sum is over `scalar + t_q`), so this is a fresh decomposition
+    /// of the scalar cell, with its own rows and canonicity check.
+    fn scalar_var_bits(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarVar,
+    ) -> Result<Vec<Self::Var>, Error>;
+
     /// Performs fixed-base scalar multiplication using a full-width scalar, returning `[scalar] base`.
     fn mul_fixed(
         &self,
@@ -114,6 +364,46 @@ pub trait EccInstructions<C: CurveAffine>:
         base: &Self::FixedPoints,
     ) -> Result<(Self::Point, Self::ScalarFixed), Error>;
 
+    /// Returns `[scalar] base` for a constant scalar.
+    ///
+    /// The product is computed outside the circuit and loaded as a constant
+    /// point, instead of being laid out as a windowed multiplication.
+    fn mul_fixed_const(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: C::Scalar,
+        base: &Self::FixedPoints,
+    ) -> Result<Self::Point, Error>;
+
+    /// Performs fixed-base scalar multiplication using an already-witnessed
+    /// full-width scalar, returning `[scalar] base`.
+    ///
+    /// The windows of `scalar` are copied into the multiplication and
+    /// constrained to be equal to the original cells.
+    fn mul_fixed_with_scalar(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+        base: &Self::FixedPoints,
+    ) -> Result<Self::Point, Error>;
+
+    /// Performs fixed-base scalar multiplication by the negation of a full-width
+    /// scalar, returning `[-scalar] base` along with the negated scalar.
+    ///
+    /// A full-width scalar is witnessed as windows within the multiplication
+    /// that uses it, so the windows of `-scalar mod q` are witnessed here as
+    /// part of computing `[-scalar] base`. `product` must be `[scalar] base`,
+    /// as returned by the multiplication that witnessed `scalar`; the result is
+    /// constrained to equal `-product`. Since `base` has prime order, this
+    /// binds the negated scalar to `-scalar mod q`.
+    fn mul_fixed_negated(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+        base: &Self::FixedPoints,
+        product: &Self::Point,
+    ) -> Result<(Self::Point, Self::ScalarFixed), Error>;
+
     /// Performs fixed-base scalar multiplication using a short signed scalar, returning
     /// `[magnitude * sign] base`.
     fn mul_fixed_short(
@@ -140,6 +430,41 @@ pub trait FixedPoints<C: CurveAffine>: Debug + Eq + Clone {
     fn u(&self) -> Vec<[[u8; 32]; H]>;
     fn z(&self) -> Vec<u64>;
     fn lagrange_coeffs(&self) -> Vec<[C::Base; H]>;
+
+    /// Checks that the fixed-base tables are consistent with each other:
+    /// `u`, `z` and `lagrange_coeffs` must have one entry per window, and
+    /// every `z` must be nonzero.
+    fn validate(&self) -> Result<(), Error> {
+        let num_windows = self.lagrange_coeffs().len();
+        if self.u().len() != num_windows || self.z().len() != num_windows {
+            return Err(Error::SynthesisError);
+        }
+        if self.z().iter().any(|z| *z == 0) {
+            return Err(Error::SynthesisError);
+        }
+        Ok(())
+    }
+
+    /// Checks that the tables have `num_windows` windows, and that for every
+    /// window and every window value `k`, they describe the multiple of the
+    /// generator used by fixed-base scalar multiplication.
+    ///
+    /// This is [`FixedPoints::validate`] followed by [`chip::check_tables`],
+    /// with a description of the first inconsistency as the error. It does not
+    /// need a circuit, so it is useful for debugging custom fixed bases.
+    fn self_check(&self, num_windows: usize) -> Result<(), String> {
+        let found = self.lagrange_coeffs().len();
+        if found != num_windows {
+            return Err(format!("expected {} windows, found {}", num_windows, found));
+        }
+        if self.validate().is_err() {
+            return Err(format!(
+                "tables must have {} u and z entries, and every z must be nonzero",
+                num_windows
+            ));
+        }
+        chip::check_tables(self).map_err(|e| e.to_string())
+    }
 }
 
 /// An element of the given elliptic curve's base field, that is used as a scalar
@@ -159,6 +484,22 @@ pub struct ScalarVar<C: CurveAffine, EccChip: EccInstructions<C>> {
     inner: EccChip::ScalarVar,
 }
 
+impl<C: CurveAffine, EccChip: EccInstructions<C>> ScalarVar<C, EccChip> {
+    /// Returns the canonical little-endian bits of this scalar, each as a
+    /// boolean cell. The bits are constrained to recompose to this scalar.
+    ///
+    /// This decomposes the scalar afresh; it does not reuse any cells from the
+    /// multiplication that produced it. See [`EccInstructions::scalar_var_bits`].
+    pub fn bits(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+    ) -> Result<Vec<EccChip::Var>, EccError> {
+        self.chip
+            .scalar_var_bits(&mut layouter, &self.inner)
+            .map_err(EccError::from)
+    }
+}
+
 /// A full-width element of the given elliptic curve's scalar field, to be used for fixed-base scalar mul.
 #[derive(Debug)]
 pub struct ScalarFixed<C: CurveAffine, EccChip: EccInstructions<C>> {
@@ -166,6 +507,77 @@ pub struct ScalarFixed<C: CurveAffine, EccChip: EccInstructions<C>> {
     inner: EccChip::ScalarFixed,
 }
 
+impl<C: CurveAffine, EccChip: EccInstructions<C>> ScalarFixed<C, EccChip> {
+    /// Returns the inner scalar.
+    pub fn inner(&self) -> &EccChip::ScalarFixed {
+        &self.inner
+    }
+
+    /// Constrains this scalar to have the same window decomposition as `other`.
+    ///
+    /// The windows are copy-constrained pairwise, without recombining either
+    /// scalar into a field element.
+    pub fn constrain_equal(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Self,
+    ) -> Result<(), EccError> {
+        assert_eq!(self.chip, other.chip);
+        self.chip
+            .constrain_scalar_fixed_equal(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+    }
+
+    /// Returns a cell holding the value of this scalar, constrained to be the
+    /// recombination of its windows.
+    ///
+    /// The windows of a full-width scalar are otherwise not bound to its value.
+    /// The cell holds the integer representation of the scalar reduced modulo
+    /// the base field modulus, so it can only be compared against the value of
+    /// a scalar below that modulus.
+    pub fn bind_value(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+    ) -> Result<EccChip::Var, EccError> {
+        self.chip
+            .bind_scalar_fixed(&mut layouter, &self.inner)
+            .map_err(EccError::from)
+    }
+
+    /// Returns `-self`, along with `[-self] base`.
+    ///
+    /// The windows of the negated scalar are witnessed by the fixed-base
+    /// multiplication that uses them, so negation requires a base, and
+    /// `product` must be the point `[self] base` returned alongside `self`.
+    /// The returned point is constrained to equal `-product`, so that
+    /// `[self] base + [-self] base = 𝒪` holds in the circuit.
+    #[allow(clippy::type_complexity)]
+    pub fn negate(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        base: &FixedPoint<C, EccChip>,
+        product: &Point<C, EccChip>,
+    ) -> Result<(Self, Point<C, EccChip>), EccError> {
+        assert_eq!(self.chip, base.chip);
+        assert_eq!(self.chip, product.chip);
+        self.chip
+            .mul_fixed_negated(&mut layouter, &self.inner, &base.inner, &product.inner)
+            .map_err(EccError::from)
+            .map(|(point, scalar)| {
+                (
+                    ScalarFixed {
+                        chip: self.chip.clone(),
+                        inner: scalar,
+                    },
+                    Point {
+                        chip: self.chip.clone(),
+                        inner: point,
+                    },
+                )
+            })
+    }
+}
+
 /// A signed short element of the given elliptic curve's scalar field, to be used for fixed-base scalar mul.
 #[derive(Debug)]
 pub struct ScalarFixedShort<C: CurveAffine, EccChip: EccInstructions<C>> {
@@ -186,9 +598,14 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
         chip: EccChip,
         mut layouter: impl Layouter<C::Base>,
         value: Option<C>,
-    ) -> Result<Self, Error> {
+    ) -> Result<Self, EccError> {
+        if value == Some(C::identity()) {
+            return Err(EccError::IdentityPoint);
+        }
         let point = chip.witness_point_non_id(&mut layouter, value);
-        point.map(|inner| NonIdentityPoint { chip, inner })
+        point
+            .map(|inner| NonIdentityPoint { chip, inner })
+            .map_err(EccError::from)
     }
 
     /// Constrains this point to be equal in value to another point.
@@ -196,13 +613,15 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
         &self,
         mut layouter: impl Layouter<C::Base>,
         other: &Other,
-    ) -> Result<(), Error> {
+    ) -> Result<(), EccError> {
         let other: Point<C, EccChip> = (other.clone()).into();
-        self.chip.constrain_equal(
-            &mut layouter,
-            &Point::<C, EccChip>::from(self.clone()).inner,
-            &other.inner,
-        )
+        self.chip
+            .constrain_equal(
+                &mut layouter,
+                &Point::<C, EccChip>::from(self.clone()).inner,
+                &other.inner,
+            )
+            .map_err(EccError::from)
     }
 
     /// Returns the inner point.
@@ -210,11 +629,32 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
         &self.inner
     }
 
+    /// Returns the witnessed value of this point, if known.
+    pub fn value(&self) -> Option<C> {
+        Point::<C, EccChip>::from(self.clone()).value()
+    }
+
+    /// Constrains this point to be a valid non-identity curve point.
+    ///
+    /// This is useful for points obtained from other chips, which may not
+    /// have been constrained on-curve by the ECC chip. It is
+    /// [`Point::assert_non_identity`], discarding the returned point.
+    pub fn constrain_non_identity(&self, layouter: impl Layouter<C::Base>) -> Result<(), EccError> {
+        Point::from(self.clone())
+            .assert_non_identity(layouter)
+            .map(|_| ())
+    }
+
     /// Extracts the x-coordinate of a point.
     pub fn extract_p(&self) -> X<C, EccChip> {
         X::from_inner(self.chip.clone(), EccChip::extract_p(&self.inner))
     }
 
+    /// Extracts the y-coordinate of this point.
+    pub fn extract_y(&self) -> EccChip::Var {
+        EccChip::extract_y(&self.inner)
+    }
+
     /// Wraps the given point (obtained directly from an instruction) in a gadget.
     pub fn from_inner(chip: EccChip, inner: EccChip::NonIdentityPoint) -> Self {
         NonIdentityPoint { chip, inner }
@@ -225,12 +665,13 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
         &self,
         mut layouter: impl Layouter<C::Base>,
         other: &Other,
-    ) -> Result<Point<C, EccChip>, Error> {
+    ) -> Result<Point<C, EccChip>, EccError> {
         let other: Point<C, EccChip> = (other.clone()).into();
 
         assert_eq!(self.chip, other.chip);
         self.chip
             .add(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
             .map(|inner| Point {
                 chip: self.chip.clone(),
                 inner,
@@ -239,16 +680,91 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
 
     /// Returns `self + other` using incomplete addition.
     /// The arguments are type-constrained not to be the identity point,
-    /// and since exceptional cases return an Error, the result also cannot
-    /// be the identity point.
+    /// and since exceptional cases return [`EccError::ExceptionalAddition`],
+    /// the result also cannot be the identity point.
     pub fn add_incomplete(
         &self,
         mut layouter: impl Layouter<C::Base>,
         other: &Self,
-    ) -> Result<Self, Error> {
+    ) -> Result<Self, EccError> {
         assert_eq!(self.chip, other.chip);
+        if is_exceptional_addition(self.value(), other.value()) {
+            return Err(EccError::ExceptionalAddition);
+        }
         self.chip
             .add_incomplete(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+            .map(|inner| NonIdentityPoint {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
+    /// Returns `self + other` using incomplete addition, along with a boolean
+    /// flag that is 1 iff `self` and `other` are an exceptional case, i.e.
+    /// `self = other` or `self = -other`.
+    ///
+    /// The returned point is only meaningful when the flag is 0.
+    pub fn try_add_incomplete(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Self,
+    ) -> Result<(Self, EccChip::Var), EccError> {
+        assert_eq!(self.chip, other.chip);
+        self.chip
+            .try_add_incomplete(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+            .map(|(inner, exceptional)| {
+                (
+                    NonIdentityPoint {
+                        chip: self.chip.clone(),
+                        inner,
+                    },
+                    exceptional,
+                )
+            })
+    }
+
+    /// Returns `self + constant` using incomplete addition, where `constant`
+    /// is a fixed point that is loaded from the circuit's constants column
+    /// rather than witnessed.
+    ///
+    /// `constant` must not be the identity, and the same exceptional cases as
+    /// [`NonIdentityPoint::add_incomplete`] apply.
+    pub fn add_incomplete_constant(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        constant: C,
+    ) -> Result<Self, EccError> {
+        if is_exceptional_addition(self.value(), Some(constant)) {
+            return Err(EccError::ExceptionalAddition);
+        }
+        self.chip
+            .add_incomplete_constant(&mut layouter, &self.inner, constant)
+            .map_err(EccError::from)
+            .map(|inner| NonIdentityPoint {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
+    /// Returns `-self`.
+    pub fn negate(&self, mut layouter: impl Layouter<C::Base>) -> Result<Self, EccError> {
+        self.chip
+            .negate(&mut layouter, &self.inner)
+            .map_err(EccError::from)
+            .map(|inner| NonIdentityPoint {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
+    /// Returns `[2] self`.
+    /// This returns an Error if `self` has y-coordinate zero.
+    pub fn double(&self, mut layouter: impl Layouter<C::Base>) -> Result<Self, EccError> {
+        self.chip
+            .double(&mut layouter, &self.inner)
+            .map_err(EccError::from)
             .map(|inner| NonIdentityPoint {
                 chip: self.chip.clone(),
                 inner,
@@ -261,9 +777,45 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> NonIdentityPoint<C, EccChip> {
         &self,
         mut layouter: impl Layouter<C::Base>,
         by: &EccChip::Var,
-    ) -> Result<(Point<C, EccChip>, ScalarVar<C, EccChip>), Error> {
+    ) -> Result<(Point<C, EccChip>, ScalarVar<C, EccChip>), EccError> {
         self.chip
             .mul(&mut layouter, by, &self.inner.clone())
+            .map_err(EccError::from)
+            .map(|(point, scalar)| {
+                (
+                    Point {
+                        chip: self.chip.clone(),
+                        inner: point,
+                    },
+                    ScalarVar {
+                        chip: self.chip.clone(),
+                        inner: scalar,
+                    },
+                )
+            })
+    }
+
+    /// Returns `[by] (self + other)`.
+    ///
+    /// This has the same result as [`NonIdentityPoint::add`] followed by
+    /// [`Point::assert_non_identity`] and [`NonIdentityPoint::mul`], but lays
+    /// out the addition in the same region as the multiplication.
+    #[allow(clippy::type_complexity)]
+    pub fn add_then_mul(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Self,
+        by: &EccChip::Var,
+    ) -> Result<(Point<C, EccChip>, ScalarVar<C, EccChip>), EccError> {
+        assert_eq!(self.chip, other.chip);
+        if let (Some(a), Some(b)) = (self.value(), other.value()) {
+            if a == -b {
+                return Err(EccError::IdentityPoint);
+            }
+        }
+        self.chip
+            .add_then_mul(&mut layouter, by, &self.inner, &other.inner)
+            .map_err(EccError::from)
             .map(|(point, scalar)| {
                 (
                     Point {
@@ -303,32 +855,144 @@ impl<C: CurveAffine, EccChip: EccInstructions<C> + Clone + Debug + Eq> Point<C,
         chip: EccChip,
         mut layouter: impl Layouter<C::Base>,
         value: Option<C>,
-    ) -> Result<Self, Error> {
+    ) -> Result<Self, EccError> {
         let point = chip.witness_point(&mut layouter, value);
-        point.map(|inner| Point { chip, inner })
+        point
+            .map(|inner| Point { chip, inner })
+            .map_err(EccError::from)
     }
 
-    /// Constrains this point to be equal in value to another point.
-    pub fn constrain_equal<Other: Into<Point<C, EccChip>> + Clone>(
-        &self,
+    /// Constructs new points with the given values, in a single region.
+    pub fn new_batch(
+        chip: EccChip,
         mut layouter: impl Layouter<C::Base>,
-        other: &Other,
-    ) -> Result<(), Error> {
-        let other: Point<C, EccChip> = (other.clone()).into();
-        self.chip
-            .constrain_equal(&mut layouter, &self.inner, &other.inner)
-    }
+        values: &[Option<C>],
+    ) -> Result<Vec<Self>, EccError> {
+        let points = chip.witness_points(&mut layouter, values)?;
+        Ok(points
+            .into_iter()
+            .map(|inner| Point {
+                chip: chip.clone(),
+                inner,
+            })
+            .collect())
+    }
+
+    /// Constructs a new point with the given value, along with a boolean flag
+    /// that is 1 iff the point is the identity.
+    pub fn new_with_flag(
+        chip: EccChip,
+        mut layouter: impl Layouter<C::Base>,
+        value: Option<C>,
+    ) -> Result<(Self, EccChip::Var), EccError> {
+        let point = chip.witness_point_with_flag(&mut layouter, value);
+        point
+            .map(|(inner, is_identity)| (Point { chip, inner }, is_identity))
+            .map_err(EccError::from)
+    }
+
+    /// Constructs the identity point, loaded as a constant. This is useful as
+    /// the initial value of an accumulator.
+    pub fn identity(chip: EccChip, mut layouter: impl Layouter<C::Base>) -> Result<Self, EccError> {
+        let point = chip.identity(&mut layouter);
+        point
+            .map(|inner| Point { chip, inner })
+            .map_err(EccError::from)
+    }
+
+    /// Constructs a point from previously assigned affine coordinates.
+    /// `(0, 0)` is interpreted as the identity.
+    pub fn from_coords(
+        chip: EccChip,
+        mut layouter: impl Layouter<C::Base>,
+        x: EccChip::Var,
+        y: EccChip::Var,
+    ) -> Result<Self, EccError> {
+        let point = chip.witness_point_from_coords(&mut layouter, x, y);
+        point
+            .map(|inner| Point { chip, inner })
+            .map_err(EccError::from)
+    }
+
+    /// Constrains this point to be equal in value to another point.
+    pub fn constrain_equal<Other: Into<Point<C, EccChip>> + Clone>(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Other,
+    ) -> Result<(), EccError> {
+        let other: Point<C, EccChip> = (other.clone()).into();
+        self.chip
+            .constrain_equal(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+    }
 
     /// Returns the inner point.
     pub fn inner(&self) -> &EccChip::Point {
         &self.inner
     }
 
+    /// Constrains the coordinates of this point to the `x_row` and `y_row`
+    /// cells of the `instance` column, exposing the point as a public input.
+    ///
+    /// The identity is exposed as `(0, 0)`. `instance` must have been
+    /// equality-enabled with [`ConstraintSystem::enable_equality`].
+    ///
+    /// [`ConstraintSystem::enable_equality`]: halo2::plonk::ConstraintSystem::enable_equality
+    pub fn constrain_to_instance(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        instance: Column<Instance>,
+        x_row: usize,
+        y_row: usize,
+    ) -> Result<(), EccError> {
+        self.chip
+            .constrain_point_to_instance(&mut layouter, &self.inner, instance, x_row, y_row)
+            .map_err(EccError::from)
+    }
+
+    /// Constrains this point to not be the identity, returning it as a
+    /// [`NonIdentityPoint`].
+    ///
+    /// This returns an error if the point is known to be the identity.
+    pub fn assert_non_identity(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+    ) -> Result<NonIdentityPoint<C, EccChip>, EccError> {
+        if self.value() == Some(C::identity()) {
+            return Err(EccError::IdentityPoint);
+        }
+        self.chip
+            .assert_non_identity(&mut layouter, &self.inner)
+            .map_err(EccError::from)
+            .map(|inner| NonIdentityPoint::from_inner(self.chip.clone(), inner))
+    }
+
+    /// Converts this point into a [`NonIdentityPoint`], constraining it to not
+    /// be the identity. This is the reverse of the conversion from
+    /// [`NonIdentityPoint`] to [`Point`].
+    ///
+    /// This is [`Point::assert_non_identity`] taking `self` by value, and
+    /// returns an error if the point is known to be the identity.
+    pub fn try_into_non_identity(
+        self,
+        layouter: impl Layouter<C::Base>,
+    ) -> Result<NonIdentityPoint<C, EccChip>, EccError> {
+        self.assert_non_identity(layouter)
+    }
+
     /// Extracts the x-coordinate of a point.
     pub fn extract_p(&self) -> X<C, EccChip> {
         X::from_inner(self.chip.clone(), EccChip::extract_p(&self.inner))
     }
 
+    /// Returns the witnessed value of this point, if known.
+    ///
+    /// This reads the assigned coordinates back out of the circuit, and adds no
+    /// constraints.
+    pub fn value(&self) -> Option<C> {
+        EccChip::point_value(&self.inner)
+    }
+
     /// Wraps the given point (obtained directly from an instruction) in a gadget.
     pub fn from_inner(chip: EccChip, inner: EccChip::Point) -> Self {
         Point { chip, inner }
@@ -339,12 +1003,69 @@ impl<C: CurveAffine, EccChip: EccInstructions<C> + Clone + Debug + Eq> Point<C,
         &self,
         mut layouter: impl Layouter<C::Base>,
         other: &Other,
-    ) -> Result<Point<C, EccChip>, Error> {
+    ) -> Result<Point<C, EccChip>, EccError> {
         let other: Point<C, EccChip> = (other.clone()).into();
 
         assert_eq!(self.chip, other.chip);
         self.chip
             .add(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+            .map(|inner| Point {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
+    /// Returns a boolean flag that is 1 if this point is equal in value to
+    /// another point, and 0 otherwise.
+    ///
+    /// Unlike [`Point::constrain_equal`], this does not fail if the points
+    /// differ.
+    pub fn equals<Other: Into<Point<C, EccChip>> + Clone>(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Other,
+    ) -> Result<EccChip::Var, EccError> {
+        let other: Point<C, EccChip> = (other.clone()).into();
+
+        assert_eq!(self.chip, other.chip);
+        self.chip
+            .points_equal(&mut layouter, &self.inner, &other.inner)
+            .map_err(EccError::from)
+    }
+
+    /// Constrains this point to be on the curve and not the identity after
+    /// clearing the cofactor. See [`EccInstructions::assert_not_small_order`].
+    pub fn assert_not_small_order(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+    ) -> Result<(), EccError> {
+        self.chip
+            .assert_not_small_order(&mut layouter, &self.inner)
+            .map_err(EccError::from)
+    }
+
+    /// Returns `self + other` using incomplete addition, where `self` may be
+    /// the identity.
+    ///
+    /// `self_is_identity` must be fixed by the circuit, since it determines the
+    /// layout. If it is set, `self` is constrained to be the identity and
+    /// `other` is returned; otherwise both points are constrained to be
+    /// non-identity.
+    pub fn add_incomplete_or_copy(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        other: &Self,
+        self_is_identity: bool,
+    ) -> Result<Self, EccError> {
+        assert_eq!(self.chip, other.chip);
+        let is_identity = |point: &Self| point.value() == Some(C::identity());
+        if !self_is_identity && (is_identity(self) || is_identity(other)) {
+            return Err(EccError::IdentityPoint);
+        }
+        self.chip
+            .add_incomplete_or_copy(&mut layouter, &self.inner, &other.inner, self_is_identity)
+            .map_err(EccError::from)
             .map(|inner| Point {
                 chip: self.chip.clone(),
                 inner,
@@ -389,9 +1110,10 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> FixedPoint<C, EccChip> {
         &self,
         mut layouter: impl Layouter<C::Base>,
         by: Option<C::Scalar>,
-    ) -> Result<(Point<C, EccChip>, ScalarFixed<C, EccChip>), Error> {
+    ) -> Result<(Point<C, EccChip>, ScalarFixed<C, EccChip>), EccError> {
         self.chip
             .mul_fixed(&mut layouter, by, &self.inner)
+            .map_err(EccError::from)
             .map(|(point, scalar)| {
                 (
                     Point {
@@ -406,15 +1128,65 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> FixedPoint<C, EccChip> {
             })
     }
 
+    /// Returns `[by] self` for a secret scalar `by`, such as key material.
+    ///
+    /// This lays out the same circuit as [`FixedPoint::mul`]. `by` is zeroized
+    /// when the caller drops it.
+    #[allow(clippy::type_complexity)]
+    pub fn mul_secret(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        by: &SecretScalar<C::Scalar>,
+    ) -> Result<(Point<C, EccChip>, ScalarFixed<C, EccChip>), EccError> {
+        self.mul(layouter, by.value())
+    }
+
+    /// Returns `[by] self` for a constant scalar `by`.
+    ///
+    /// The product is fixed in the circuit, so no windows are witnessed and no
+    /// scalar is returned.
+    pub fn mul_const(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        by: C::Scalar,
+    ) -> Result<Point<C, EccChip>, EccError> {
+        self.chip
+            .mul_fixed_const(&mut layouter, by, &self.inner)
+            .map_err(EccError::from)
+            .map(|inner| Point {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
+    /// Returns `[by] self`, reusing the windows of an already-witnessed scalar.
+    ///
+    /// This allows a single scalar to be multiplied by several fixed bases.
+    pub fn mul_with_scalar(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        by: &ScalarFixed<C, EccChip>,
+    ) -> Result<Point<C, EccChip>, EccError> {
+        assert_eq!(self.chip, by.chip);
+        self.chip
+            .mul_fixed_with_scalar(&mut layouter, &by.inner, &self.inner)
+            .map_err(EccError::from)
+            .map(|inner| Point {
+                chip: self.chip.clone(),
+                inner,
+            })
+    }
+
     #[allow(clippy::type_complexity)]
     /// Returns `[by] self`.
     pub fn mul_base_field(
         &self,
         mut layouter: impl Layouter<C::Base>,
         by: EccChip::Var,
-    ) -> Result<Point<C, EccChip>, Error> {
+    ) -> Result<Point<C, EccChip>, EccError> {
         self.chip
             .mul_fixed_base_field_elem(&mut layouter, by, &self.inner)
+            .map_err(EccError::from)
             .map(|inner| Point {
                 chip: self.chip.clone(),
                 inner,
@@ -423,13 +1195,28 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> FixedPoint<C, EccChip> {
 
     #[allow(clippy::type_complexity)]
     /// Returns `[by] self`.
+    ///
+    /// This returns [`EccError::OutOfRangeScalar`] if the magnitude is known
+    /// not to fit in 64 bits, or the sign is known not to be 1 or -1.
     pub fn mul_short(
         &self,
         mut layouter: impl Layouter<C::Base>,
         magnitude_sign: (EccChip::Var, EccChip::Var),
-    ) -> Result<(Point<C, EccChip>, ScalarFixedShort<C, EccChip>), Error> {
+    ) -> Result<(Point<C, EccChip>, ScalarFixedShort<C, EccChip>), EccError> {
+        let (magnitude, sign) = &magnitude_sign;
+        if let Some(magnitude) = magnitude.value() {
+            if magnitude.to_bytes()[8..].iter().any(|byte| *byte != 0) {
+                return Err(EccError::OutOfRangeScalar);
+            }
+        }
+        if let Some(sign) = sign.value() {
+            if sign != C::Base::one() && sign != -C::Base::one() {
+                return Err(EccError::OutOfRangeScalar);
+            }
+        }
         self.chip
             .mul_fixed_short(&mut layouter, magnitude_sign, &self.inner)
+            .map_err(EccError::from)
             .map(|(point, scalar)| {
                 (
                     Point {
@@ -444,6 +1231,28 @@ impl<C: CurveAffine, EccChip: EccInstructions<C>> FixedPoint<C, EccChip> {
             })
     }
 
+    /// Returns `[magnitude * sign] self` for a constant short scalar.
+    ///
+    /// As with [`FixedPoint::mul_const`], the product is fixed in the circuit,
+    /// so the magnitude is neither witnessed nor range-checked, and no scalar
+    /// is returned.
+    ///
+    /// This returns [`EccError::OutOfRangeScalar`] if `sign` is not 1 or -1.
+    pub fn mul_short_const(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        magnitude: u64,
+        sign: i8,
+    ) -> Result<Point<C, EccChip>, EccError> {
+        let magnitude = C::Scalar::from_u64(magnitude);
+        let scalar = match sign {
+            1 => magnitude,
+            -1 => -magnitude,
+            _ => return Err(EccError::OutOfRangeScalar),
+        };
+        self.mul_const(layouter, scalar)
+    }
+
     /// Wraps the given fixed base (obtained directly from an instruction) in a gadget.
     pub fn from_inner(chip: EccChip, inner: EccChip::FixedPoints) -> Self {
         FixedPoint { chip, inner }
@@ -459,8 +1268,8 @@ pub mod tests {
     use crate::ecc::{
         self,
         chip::{
-            compute_lagrange_coeffs, find_zs_and_us, EccChip, EccConfig, NUM_WINDOWS,
-            NUM_WINDOWS_SHORT,
+            compute_lagrange_coeffs, find_zs_and_us, EccChip, EccConfig, EccCurve,
+            GeneratorFixedBase, NUM_WINDOWS, NUM_WINDOWS_SHORT,
         },
         FixedPoints, H,
     };
@@ -468,15 +1277,24 @@ pub mod tests {
 
     use halo2::{
         circuit::{Layouter, SimpleFloorPlanner},
-        plonk::{Circuit, ConstraintSystem, Error},
+        plonk::{
+            Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+            FloorPlanner, Instance, Selector,
+        },
     };
-    use pasta_curves::pallas;
+    use pasta_curves::{arithmetic::FieldExt, pallas, vesta};
 
-    use std::marker::PhantomData;
+    use std::{
+        cmp,
+        collections::{HashMap, HashSet},
+        marker::PhantomData,
+    };
 
     #[derive(Debug, Eq, PartialEq, Clone)]
-    enum FixedBase {
+    pub enum FixedBase {
         FullWidth,
+        // A second full-width base, distinct from the generator.
+        FullWidthAlt,
         Short,
     }
 
@@ -490,16 +1308,25 @@ pub mod tests {
             compute_lagrange_coeffs(*BASE, NUM_WINDOWS);
         static ref LAGRANGE_COEFFS_SHORT: Vec<[pallas::Base; H]> =
             compute_lagrange_coeffs(*BASE, NUM_WINDOWS_SHORT);
+        static ref BASE_ALT: pallas::Affine = pallas::Point::generator().double().to_affine();
+        static ref ZS_AND_US_ALT: Vec<(u64, [[u8; 32]; H])> =
+            find_zs_and_us(*BASE_ALT, NUM_WINDOWS).unwrap();
+        static ref LAGRANGE_COEFFS_ALT: Vec<[pallas::Base; H]> =
+            compute_lagrange_coeffs(*BASE_ALT, NUM_WINDOWS);
     }
 
     impl FixedPoints<pallas::Affine> for FixedBase {
         fn generator(&self) -> pallas::Affine {
-            *BASE
+            match self {
+                FixedBase::FullWidthAlt => *BASE_ALT,
+                _ => *BASE,
+            }
         }
 
         fn u(&self) -> Vec<[[u8; 32]; H]> {
             match self {
                 FixedBase::FullWidth => ZS_AND_US.iter().map(|(_, us)| *us).collect(),
+                FixedBase::FullWidthAlt => ZS_AND_US_ALT.iter().map(|(_, us)| *us).collect(),
                 FixedBase::Short => ZS_AND_US_SHORT.iter().map(|(_, us)| *us).collect(),
             }
         }
@@ -507,6 +1334,7 @@ pub mod tests {
         fn z(&self) -> Vec<u64> {
             match self {
                 FixedBase::FullWidth => ZS_AND_US.iter().map(|(z, _)| *z).collect(),
+                FixedBase::FullWidthAlt => ZS_AND_US_ALT.iter().map(|(z, _)| *z).collect(),
                 FixedBase::Short => ZS_AND_US_SHORT.iter().map(|(z, _)| *z).collect(),
             }
         }
@@ -514,16 +1342,237 @@ pub mod tests {
         fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
             match self {
                 FixedBase::FullWidth => LAGRANGE_COEFFS.to_vec(),
+                FixedBase::FullWidthAlt => LAGRANGE_COEFFS_ALT.to_vec(),
                 FixedBase::Short => LAGRANGE_COEFFS_SHORT.to_vec(),
             }
         }
     }
 
+    /// Configures an [`EccChip`] over `C` with the columns used by the tests:
+    /// ten advice columns, eight fixed columns for the Lagrange coefficients,
+    /// and a fixed column for constants.
+    pub fn configure<C: EccCurve, F: FixedPoints<C>>(
+        meta: &mut ConstraintSystem<C::Base>,
+    ) -> EccConfig<C> {
+        let advices = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let lookup_table = meta.lookup_table_column();
+        let lagrange_coeffs = [
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+        ];
+        // Shared fixed column for loading constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let range_check = LookupRangeCheckConfig::configure(meta, advices[9], lookup_table);
+        EccChip::<C, F>::configure(meta, advices, lagrange_coeffs, range_check)
+    }
+
+    /// A region recorded by [`RegionRecorder`].
+    #[derive(Debug, Default)]
+    pub struct RecordedRegion {
+        /// The namespaces the region was assigned in, outermost first.
+        pub namespace: Vec<String>,
+        /// The first and last rows on which the region assigns a cell or
+        /// enables a selector.
+        pub rows: Option<(usize, usize)>,
+        /// The rows on which each selector is enabled.
+        pub selectors: HashMap<Selector, HashSet<usize>>,
+    }
+
+    impl RecordedRegion {
+        fn update_rows(&mut self, row: usize) {
+            self.rows = Some(match self.rows {
+                Some((start, end)) => (cmp::min(start, row), cmp::max(end, row)),
+                None => (row, row),
+            });
+        }
+
+        /// Returns the number of rows spanned by the region.
+        pub fn height(&self) -> usize {
+            self.rows.map_or(0, |(start, end)| end - start + 1)
+        }
+    }
+
+    /// Records the regions assigned by a circuit, as `MockProver` sees them
+    /// during synthesis, along with the namespaces they were assigned in.
+    #[derive(Debug, Default)]
+    pub struct RegionRecorder {
+        namespace: Vec<String>,
+        current_region: Option<RecordedRegion>,
+        pub regions: Vec<RecordedRegion>,
+    }
+
+    impl RegionRecorder {
+        /// Configures and synthesizes `circuit`, returning the recorded
+        /// regions and the circuit's config.
+        pub fn run<F: ff::Field, C: Circuit<F>>(circuit: &C) -> Result<(Self, C::Config), Error>
+        where
+            C::Config: Clone,
+        {
+            let mut meta = ConstraintSystem::default();
+            let config = C::configure(&mut meta);
+            // Constants are assigned outside of any region, so it does not matter
+            // which column they are placed in.
+            let constants = meta.fixed_column();
+
+            let mut recorder = RegionRecorder::default();
+            C::FloorPlanner::synthesize(&mut recorder, circuit, config.clone(), vec![constants])?;
+            Ok((recorder, config))
+        }
+
+        /// Returns the number of rows on which `selector` is enabled.
+        pub fn selector_usage(&self, selector: &Selector) -> usize {
+            self.regions
+                .iter()
+                .filter_map(|region| region.selectors.get(selector))
+                .map(HashSet::len)
+                .sum()
+        }
+
+        /// Returns the total height of the regions assigned in the top-level
+        /// namespace `name`.
+        pub fn height(&self, name: &str) -> usize {
+            self.regions
+                .iter()
+                .filter(|region| region.namespace.first().map(String::as_str) == Some(name))
+                .map(RecordedRegion::height)
+                .sum()
+        }
+
+        fn update_rows(&mut self, row: usize) {
+            if let Some(region) = self.current_region.as_mut() {
+                region.update_rows(row);
+            }
+        }
+    }
+
+    impl<F: ff::Field> Assignment<F> for RegionRecorder {
+        fn enter_region<NR, N>(&mut self, _: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+            assert!(self.current_region.is_none());
+            self.current_region = Some(RecordedRegion {
+                namespace: self.namespace.clone(),
+                ..Default::default()
+            });
+        }
+
+        fn exit_region(&mut self) {
+            self.regions.push(self.current_region.take().unwrap());
+        }
+
+        fn enable_selector<A, AR>(
+            &mut self,
+            _: A,
+            selector: &Selector,
+            row: usize,
+        ) -> Result<(), Error>
+        where
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            // As in `MockProver`, selectors may only be enabled inside a region.
+            let region = self.current_region.as_mut().unwrap();
+            region.update_rows(row);
+            region.selectors.entry(*selector).or_default().insert(row);
+            Ok(())
+        }
+
+        fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Option<F>, Error> {
+            Ok(None)
+        }
+
+        fn assign_advice<V, VR, A, AR>(
+            &mut self,
+            _: A,
+            _: Column<Advice>,
+            row: usize,
+            to: V,
+        ) -> Result<(), Error>
+        where
+            V: FnOnce() -> Result<VR, Error>,
+            VR: Into<Assigned<F>>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            self.update_rows(row);
+            to().map(|_| ())
+        }
+
+        fn assign_fixed<V, VR, A, AR>(
+            &mut self,
+            _: A,
+            _: Column<Fixed>,
+            row: usize,
+            to: V,
+        ) -> Result<(), Error>
+        where
+            V: FnOnce() -> Result<VR, Error>,
+            VR: Into<Assigned<F>>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            self.update_rows(row);
+            to().map(|_| ())
+        }
+
+        fn copy(
+            &mut self,
+            _: Column<Any>,
+            _: usize,
+            _: Column<Any>,
+            _: usize,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn fill_from_row(
+            &mut self,
+            _: Column<Fixed>,
+            _: usize,
+            _: Option<Assigned<F>>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn push_namespace<NR, N>(&mut self, name_fn: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+            self.namespace.push(name_fn().into());
+        }
+
+        fn pop_namespace(&mut self, _: Option<String>) {
+            self.namespace.pop();
+        }
+    }
+
     pub struct MyCircuit<F: FixedPoints<pallas::Affine>>(pub PhantomData<F>);
 
     #[allow(non_snake_case)]
     impl<F: FixedPoints<pallas::Affine>> Circuit<pallas::Base> for MyCircuit<F> {
-        type Config = EccConfig;
+        type Config = EccConfig<pallas::Affine>;
         type FloorPlanner = SimpleFloorPlanner;
 
         fn without_witnesses(&self) -> Self {
@@ -531,35 +1580,7 @@ pub mod tests {
         }
 
         fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
-            let advices = [
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-                meta.advice_column(),
-            ];
-            let lookup_table = meta.lookup_table_column();
-            let lagrange_coeffs = [
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-                meta.fixed_column(),
-            ];
-            // Shared fixed column for loading constants
-            let constants = meta.fixed_column();
-            meta.enable_constant(constants);
-
-            let range_check = LookupRangeCheckConfig::configure(meta, advices[9], lookup_table);
-            EccChip::<F>::configure(meta, advices, lagrange_coeffs, range_check)
+            configure::<pallas::Affine, F>(meta)
         }
 
         fn synthesize(
@@ -578,36 +1599,151 @@ pub mod tests {
                 layouter.namespace(|| "witness non-identity point"),
             )?;
 
+            ecc::chip::witness_point::tests::test_witness_points(
+                chip.clone(),
+                layouter.namespace(|| "witness points"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_witness_with_flag(
+                chip.clone(),
+                layouter.namespace(|| "witness point with identity flag"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_point_value(
+                chip.clone(),
+                layouter.namespace(|| "read back point value"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_assert_non_identity(
+                chip.clone(),
+                layouter.namespace(|| "assert non-identity"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_extract_y(
+                chip.clone(),
+                layouter.namespace(|| "extract y-coordinate"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_witness_point_strict(
+                chip.clone(),
+                layouter.namespace(|| "witness point strict"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_witness_point_from_x_and_sign(
+                chip.clone(),
+                layouter.namespace(|| "witness point from x and sign"),
+            )?;
+
+            ecc::chip::witness_point::tests::test_identity(
+                chip.clone(),
+                layouter.namespace(|| "identity point"),
+            )?;
+
             ecc::chip::add::tests::test_add(chip.clone(), layouter.namespace(|| "addition"))?;
 
+            ecc::chip::add::tests::test_add_with_slope(
+                chip.clone(),
+                layouter.namespace(|| "complete addition with slope"),
+            )?;
+
             ecc::chip::add_incomplete::tests::test_add_incomplete(
                 chip.clone(),
                 layouter.namespace(|| "incomplete addition"),
             )?;
 
+            ecc::chip::add_incomplete::tests::test_try_add_incomplete(
+                chip.clone(),
+                layouter.namespace(|| "flagged incomplete addition"),
+            )?;
+
+            ecc::chip::add_incomplete::tests::test_add_incomplete_constant(
+                chip.clone(),
+                layouter.namespace(|| "incomplete addition of constant point"),
+            )?;
+
+            ecc::chip::add_incomplete::tests::test_add_incomplete_or_copy(
+                chip.clone(),
+                layouter.namespace(|| "incomplete addition or copy"),
+            )?;
+
+            ecc::chip::negate::tests::test_negate(
+                chip.clone(),
+                layouter.namespace(|| "point negation"),
+            )?;
+
+            ecc::chip::double::tests::test_double(
+                chip.clone(),
+                layouter.namespace(|| "point doubling"),
+            )?;
+
+            ecc::chip::equals::tests::test_points_equal(
+                chip.clone(),
+                layouter.namespace(|| "point equality"),
+            )?;
+
             ecc::chip::mul::tests::test_mul(
                 chip.clone(),
                 layouter.namespace(|| "variable-base scalar multiplication"),
             )?;
 
+            ecc::chip::mul::tests::test_scalar_var_bits(
+                chip.clone(),
+                layouter.namespace(|| "variable-base scalar bits"),
+            )?;
+
+            ecc::chip::mul::tests::test_mul_sum(
+                chip.clone(),
+                layouter.namespace(|| "multi-scalar multiplication"),
+            )?;
+
             ecc::chip::mul_fixed::full_width::tests::test_mul_fixed(
                 FixedBase::FullWidth,
                 chip.clone(),
                 layouter.namespace(|| "fixed-base scalar multiplication with full-width scalar"),
             )?;
 
+            ecc::chip::mul_fixed::full_width::tests::test_mul_with_scalar(
+                [FixedBase::FullWidth, FixedBase::FullWidthAlt],
+                chip.clone(),
+                layouter.namespace(|| "fixed-base scalar multiplication reusing a scalar"),
+            )?;
+
+            ecc::chip::mul_fixed::full_width::tests::test_mul_fixed_bounded(
+                FixedBase::FullWidth,
+                chip.clone(),
+                layouter.namespace(|| "fixed-base scalar multiplication by a bounded scalar"),
+            )?;
+
+            ecc::chip::mul_fixed::full_width::tests::test_mul_fixed_pair(
+                [FixedBase::FullWidth, FixedBase::FullWidthAlt],
+                chip.clone(),
+                layouter.namespace(|| "fixed-base scalar multiplication by a pair of bases"),
+            )?;
+
             ecc::chip::mul_fixed::short::tests::test_mul_fixed_short(
                 FixedBase::Short,
                 chip.clone(),
                 layouter.namespace(|| "fixed-base scalar multiplication with short signed scalar"),
             )?;
 
+            ecc::chip::mul_fixed::short::tests::test_witness_scalar_fixed_short(
+                FixedBase::Short,
+                chip.clone(),
+                layouter.namespace(|| "witness short signed scalar"),
+            )?;
+
             ecc::chip::mul_fixed::base_field_elem::tests::test_mul_fixed_base_field(
                 FixedBase::FullWidth,
-                chip,
+                chip.clone(),
                 layouter.namespace(|| "fixed-base scalar multiplication with base field element"),
             )?;
 
+            ecc::chip::mul_fixed::base_field_elem::tests::test_mul_fixed_from_bits(
+                FixedBase::FullWidth,
+                chip,
+                layouter.namespace(|| "fixed-base scalar multiplication with scalar bits"),
+            )?;
+
             Ok(())
         }
     }
@@ -622,6 +1758,713 @@ pub mod tests {
         assert_eq!(prover.verify(), Ok(()))
     }
 
+    #[cfg(feature = "dev")]
+    #[test]
+    fn selector_usage() {
+        struct UsageCircuit;
+
+        impl Circuit<pallas::Base> for UsageCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                UsageCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                MyCircuit::<FixedBase>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                MyCircuit::<FixedBase>(PhantomData)
+                    .synthesize(config.clone(), layouter.namespace(|| "ECC chip"))?;
+
+                // Instructions that are not covered by `MyCircuit`.
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config);
+                let base = ecc::FixedPoint::from_inner(chip, FixedBase::FullWidth);
+                let point =
+                    base.mul_const(layouter.namespace(|| "[2]B"), pallas::Scalar::from_u64(2))?;
+                point.assert_not_small_order(layouter.namespace(|| "[2]B is not small-order"))?;
+
+                Ok(())
+            }
+        }
+
+        let (recorder, config) = RegionRecorder::run(&UsageCircuit).unwrap();
+        let usage =
+            EccChip::<pallas::Affine, FixedBase>::construct(config.clone()).selector_usage();
+
+        // `q_mul_fixed_running_sum` is also enabled by the running-sum helper,
+        // which does not count its rows.
+        let selectors = [
+            config.q_add_incomplete,
+            config.q_add,
+            config.q_mul_hi.0,
+            config.q_mul_hi.1,
+            config.q_mul_hi.2,
+            config.q_mul_lo.0,
+            config.q_mul_lo.1,
+            config.q_mul_lo.2,
+            config.q_mul_decompose_var,
+            config.q_mul_lsb,
+            config.q_mul_overflow,
+            config.q_mul_fixed_full,
+            config.q_mul_fixed_short,
+            config.q_mul_fixed_base_field,
+            config.q_point,
+            config.q_point_non_id,
+            config.q_point_flag,
+            config.q_negate,
+            config.q_double,
+            config.q_point_equals,
+            config.q_scalar_bits,
+            config.q_scalar_bits_canon,
+            config.q_mul_fixed_full_recompose,
+            config.q_add_incomplete_flag,
+        ];
+        for selector in usage.keys() {
+            assert!(
+                selectors.contains(selector) || *selector == config.q_mul_fixed_running_sum,
+                "{:?} is not an ECC selector",
+                selector
+            );
+        }
+        for selector in selectors.iter() {
+            assert_eq!(
+                usage.get(selector).copied().unwrap_or(0),
+                recorder.selector_usage(selector),
+                "usage of {:?}",
+                selector
+            );
+        }
+    }
+
+    #[test]
+    fn ecc_op_rows() {
+        use crate::{ecc::chip::EccOp, utilities::UtilitiesInstructions};
+        use ecc::EccInstructions;
+        use ff::Field;
+
+        const OPS: [EccOp; 10] = [
+            EccOp::WitnessPoint,
+            EccOp::AddIncomplete,
+            EccOp::Add,
+            EccOp::Negate,
+            EccOp::Double,
+            EccOp::PointsEqual,
+            EccOp::Mul,
+            EccOp::MulFixed,
+            EccOp::MulFixedShort,
+            EccOp::MulFixedBaseFieldElem,
+        ];
+
+        struct RowsCircuit;
+
+        impl Circuit<pallas::Base> for RowsCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                RowsCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                MyCircuit::<FixedBase>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                // Witness the inputs outside of the namespaces of the instructions.
+                let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+                let q_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+                let p = chip.witness_point_non_id(&mut layouter.namespace(|| "P"), Some(p_val))?;
+                let q = chip.witness_point_non_id(&mut layouter.namespace(|| "Q"), Some(q_val))?;
+                let alpha = chip.load_private(
+                    layouter.namespace(|| "alpha"),
+                    config.advices[0],
+                    Some(pallas::Base::rand()),
+                )?;
+                let magnitude = chip.load_private(
+                    layouter.namespace(|| "magnitude"),
+                    config.advices[0],
+                    Some(pallas::Base::from_u64(0xdead_beef)),
+                )?;
+                let sign = chip.load_private(
+                    layouter.namespace(|| "sign"),
+                    config.advices[0],
+                    Some(pallas::Base::one()),
+                )?;
+
+                // Assign each instruction in a namespace named after its `EccOp`.
+                for op in OPS.iter() {
+                    let mut layouter = layouter.namespace(|| format!("{:?}", op));
+                    match op {
+                        EccOp::WitnessPoint => {
+                            chip.witness_point(&mut layouter, Some(p_val))?;
+                        }
+                        EccOp::AddIncomplete => {
+                            chip.add_incomplete(&mut layouter, &p, &q)?;
+                        }
+                        EccOp::Add => {
+                            chip.add(&mut layouter, &p, &q)?;
+                        }
+                        EccOp::Negate => {
+                            chip.negate(&mut layouter, &p)?;
+                        }
+                        EccOp::Double => {
+                            chip.double(&mut layouter, &p)?;
+                        }
+                        EccOp::PointsEqual => {
+                            chip.points_equal(&mut layouter, &p.into(), &q.into())?;
+                        }
+                        EccOp::Mul => {
+                            chip.mul(&mut layouter, &alpha, &p)?;
+                        }
+                        EccOp::MulFixed => {
+                            chip.mul_fixed(
+                                &mut layouter,
+                                Some(pallas::Scalar::rand()),
+                                &FixedBase::FullWidth,
+                            )?;
+                        }
+                        EccOp::MulFixedShort => {
+                            chip.mul_fixed_short(
+                                &mut layouter,
+                                (magnitude, sign),
+                                &FixedBase::Short,
+                            )?;
+                        }
+                        EccOp::MulFixedBaseFieldElem => {
+                            chip.mul_fixed_base_field_elem(
+                                &mut layouter,
+                                alpha,
+                                &FixedBase::FullWidth,
+                            )?;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        let (recorder, _) = RegionRecorder::run(&RowsCircuit).unwrap();
+        for op in OPS.iter() {
+            assert_eq!(
+                recorder.height(&format!("{:?}", op)),
+                ecc::chip::rows(*op),
+                "rows of {:?}",
+                op
+            );
+        }
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn ecc_chip_self_test() {
+        use halo2::dev::MockProver;
+
+        struct SelfTestCircuit;
+
+        impl Circuit<pallas::Base> for SelfTestCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                SelfTestCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                MyCircuit::<FixedBase>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                crate::ecc::chip::ecc_chip_self_test(
+                    chip,
+                    FixedBase::FullWidth,
+                    layouter.namespace(|| "ECC chip self-test"),
+                )
+            }
+        }
+
+        let prover = MockProver::run(11, &SelfTestCircuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn ecc_chip_shared_columns() {
+        use crate::utilities::{
+            cond_swap::{CondSwapChip, CondSwapConfig, CondSwapInstructions},
+            CellValue, UtilitiesInstructions,
+        };
+        use halo2::dev::MockProver;
+
+        struct SharedColumnsCircuit;
+
+        impl Circuit<pallas::Base> for SharedColumnsCircuit {
+            type Config = (EccConfig<pallas::Affine>, CondSwapConfig);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                SharedColumnsCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let advices = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+                let fixed = [
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                ];
+                let lookup_table = meta.lookup_table_column();
+
+                // Shared fixed column for loading constants
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                // Another sub-chip, configured over the first advice columns.
+                let cond_swap_config = CondSwapChip::configure(
+                    meta,
+                    [advices[0], advices[1], advices[2], advices[3], advices[4]],
+                );
+
+                let range_check = LookupRangeCheckConfig::configure(meta, advices[9], lookup_table);
+                let ecc_config = EccChip::<pallas::Affine, FixedBase>::configure_with_columns(
+                    meta,
+                    advices,
+                    [
+                        fixed[0], fixed[1], fixed[2], fixed[3], fixed[4], fixed[5], fixed[6],
+                        fixed[7],
+                    ],
+                    fixed[8],
+                    range_check,
+                );
+
+                (ecc_config, cond_swap_config)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let (ecc_config, cond_swap_config) = config;
+
+                let cond_swap_chip =
+                    CondSwapChip::<pallas::Base>::construct(cond_swap_config.clone());
+                let a: CellValue<pallas::Base> = cond_swap_chip.load_private(
+                    layouter.namespace(|| "a"),
+                    cond_swap_config.a,
+                    Some(pallas::Base::rand()),
+                )?;
+                cond_swap_chip.swap(
+                    layouter.namespace(|| "swap"),
+                    (a, Some(pallas::Base::rand())),
+                    Some(true),
+                )?;
+
+                // Load 10-bit lookup table.
+                ecc_config.lookup_config.load(&mut layouter)?;
+
+                let chip = EccChip::construct(ecc_config);
+                ecc::chip::add::tests::test_add(chip, layouter.namespace(|| "addition"))
+            }
+        }
+
+        let prover = MockProver::run(11, &SharedColumnsCircuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    lazy_static! {
+        static ref VESTA_FIXED_BASE: GeneratorFixedBase<vesta::Affine> =
+            GeneratorFixedBase::vesta();
+    }
+
+    struct VestaCircuit;
+
+    impl Circuit<vesta::Base> for VestaCircuit {
+        type Config = EccConfig<vesta::Affine>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            VestaCircuit
+        }
+
+        fn configure(meta: &mut ConstraintSystem<vesta::Base>) -> Self::Config {
+            configure::<vesta::Affine, GeneratorFixedBase<vesta::Affine>>(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<vesta::Base>,
+        ) -> Result<(), Error> {
+            use crate::utilities::UtilitiesInstructions;
+
+            let chip = EccChip::construct(config.clone());
+            config.lookup_config.load(&mut layouter)?;
+
+            let p_val = (vesta::Point::generator() * vesta::Scalar::rand()).to_affine();
+            let q_val = (vesta::Point::generator() * vesta::Scalar::rand()).to_affine();
+            let p =
+                ecc::NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+            let q =
+                ecc::NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "Q"), Some(q_val))?;
+
+            // P + Q
+            {
+                let result = p.add(layouter.namespace(|| "P + Q"), &q)?;
+                let expected = ecc::Point::new(
+                    chip.clone(),
+                    layouter.namespace(|| "expected P + Q"),
+                    Some((p_val + q_val).to_affine()),
+                )?;
+                result.constrain_equal(layouter.namespace(|| "check P + Q"), &expected)?;
+            }
+
+            // [alpha]P
+            {
+                let alpha = chip.load_private(
+                    layouter.namespace(|| "alpha"),
+                    config.advices[0],
+                    Some(vesta::Base::from_u64(0xdead_beef)),
+                )?;
+                let (result, _) = p.mul(layouter.namespace(|| "[alpha]P"), &alpha)?;
+                let expected = ecc::NonIdentityPoint::new(
+                    chip.clone(),
+                    layouter.namespace(|| "expected [alpha]P"),
+                    Some((p_val * vesta::Scalar::from_u64(0xdead_beef)).to_affine()),
+                )?;
+                result.constrain_equal(layouter.namespace(|| "check [alpha]P"), &expected)?;
+            }
+
+            // [scalar]B
+            {
+                let scalar = vesta::Scalar::rand();
+                let base = ecc::FixedPoint::from_inner(chip.clone(), VESTA_FIXED_BASE.clone());
+                let (result, _) = base.mul(layouter.namespace(|| "[scalar]B"), Some(scalar))?;
+                let expected = ecc::NonIdentityPoint::new(
+                    chip,
+                    layouter.namespace(|| "expected [scalar]B"),
+                    Some((VESTA_FIXED_BASE.generator() * scalar).to_affine()),
+                )?;
+                result.constrain_equal(layouter.namespace(|| "check [scalar]B"), &expected)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fixed_points_validate() {
+        // A fixed base with one Lagrange coefficient row missing.
+        #[derive(Debug, Eq, PartialEq, Clone)]
+        struct TruncatedBase;
+        impl FixedPoints<pallas::Affine> for TruncatedBase {
+            fn generator(&self) -> pallas::Affine {
+                FixedBase::FullWidth.generator()
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                FixedBase::FullWidth.u()
+            }
+
+            fn z(&self) -> Vec<u64> {
+                FixedBase::FullWidth.z()
+            }
+
+            fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
+                let mut lagrange_coeffs = FixedBase::FullWidth.lagrange_coeffs();
+                lagrange_coeffs.pop();
+                lagrange_coeffs
+            }
+        }
+
+        assert!(FixedBase::FullWidth.validate().is_ok());
+        assert!(FixedBase::Short.validate().is_ok());
+        assert!(matches!(
+            TruncatedBase.validate(),
+            Err(Error::SynthesisError)
+        ));
+    }
+
+    #[test]
+    fn fixed_points_self_check() {
+        use ff::Field;
+
+        // A fixed base with one corrupted Lagrange coefficient.
+        #[derive(Debug, Eq, PartialEq, Clone)]
+        struct CorruptedBase;
+        impl FixedPoints<pallas::Affine> for CorruptedBase {
+            fn generator(&self) -> pallas::Affine {
+                FixedBase::FullWidth.generator()
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                FixedBase::FullWidth.u()
+            }
+
+            fn z(&self) -> Vec<u64> {
+                FixedBase::FullWidth.z()
+            }
+
+            fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
+                let mut lagrange_coeffs = FixedBase::FullWidth.lagrange_coeffs();
+                lagrange_coeffs[7][0] += pallas::Base::one();
+                lagrange_coeffs
+            }
+        }
+
+        assert_eq!(FixedBase::FullWidth.self_check(NUM_WINDOWS), Ok(()));
+        assert_eq!(FixedBase::Short.self_check(NUM_WINDOWS_SHORT), Ok(()));
+        assert_eq!(
+            FixedBase::Short.self_check(NUM_WINDOWS),
+            Err(format!(
+                "expected {} windows, found {}",
+                NUM_WINDOWS, NUM_WINDOWS_SHORT
+            ))
+        );
+
+        // The window value 0 evaluates to the constant coefficient.
+        let err = CorruptedBase.self_check(NUM_WINDOWS).unwrap_err();
+        assert!(err.ends_with("at window 7 (k = 0)"), "{}", err);
+    }
+
+    #[test]
+    fn mul_fixed_checks_msb_window() {
+        use halo2::dev::MockProver;
+
+        // A fixed base whose most significant window has the `u`s of window 0.
+        #[derive(Debug, Eq, PartialEq, Clone)]
+        struct CorruptedMsbBase;
+        impl FixedPoints<pallas::Affine> for CorruptedMsbBase {
+            fn generator(&self) -> pallas::Affine {
+                FixedBase::FullWidth.generator()
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                let mut u = FixedBase::FullWidth.u();
+                u[NUM_WINDOWS - 1] = u[0];
+                u
+            }
+
+            fn z(&self) -> Vec<u64> {
+                FixedBase::FullWidth.z()
+            }
+
+            fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
+                FixedBase::FullWidth.lagrange_coeffs()
+            }
+        }
+
+        struct MsbCircuit;
+
+        impl Circuit<pallas::Base> for MsbCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MsbCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                configure::<pallas::Affine, CorruptedMsbBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, CorruptedMsbBase>::construct(config);
+                let base = ecc::FixedPoint::from_inner(chip, CorruptedMsbBase);
+                base.mul(layouter.namespace(|| "[a]B"), Some(pallas::Scalar::rand()))?;
+                Ok(())
+            }
+        }
+
+        // The malformed table is caught when the window is processed, rather
+        // than as an unsatisfied constraint.
+        assert!(MockProver::run(11, &MsbCircuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn constrain_to_instance() {
+        use super::Point;
+        use halo2::{
+            dev::MockProver,
+            plonk::{Column, Instance},
+        };
+        use pasta_curves::arithmetic::CurveAffine;
+
+        struct InstanceCircuit {
+            p: Option<pallas::Affine>,
+        }
+
+        impl Circuit<pallas::Base> for InstanceCircuit {
+            type Config = (EccConfig<pallas::Affine>, Column<Instance>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                InstanceCircuit { p: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let instance = meta.instance_column();
+                meta.enable_equality(instance.into());
+
+                (MyCircuit::<FixedBase>::configure(meta), instance)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let (config, instance) = config;
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                let p = Point::new(chip, layouter.namespace(|| "P"), self.p)?;
+                p.constrain_to_instance(layouter.namespace(|| "expose P"), instance, 0, 1)?;
+
+                Ok(())
+            }
+        }
+
+        let p = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let coords = p.coordinates().unwrap();
+        let circuit = InstanceCircuit { p: Some(p) };
+
+        // The instance holds the coordinates of P.
+        let prover = MockProver::run(11, &circuit, vec![vec![*coords.x(), *coords.y()]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The instance holds the coordinates of -P.
+        let prover = MockProver::run(11, &circuit, vec![vec![*coords.x(), -*coords.y()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn ecc_error() {
+        use super::{EccError, FixedPoint, NonIdentityPoint};
+        use crate::utilities::UtilitiesInstructions;
+        use ff::Field;
+        use group::prime::PrimeCurveAffine;
+        use halo2::dev::MockProver;
+
+        struct ErrorCircuit;
+
+        impl Circuit<pallas::Base> for ErrorCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                ErrorCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                MyCircuit::<FixedBase>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+
+                // Witnessing 𝒪 as a non-identity point.
+                let err = NonIdentityPoint::new(
+                    chip.clone(),
+                    layouter.namespace(|| "𝒪"),
+                    Some(pallas::Affine::identity()),
+                )
+                .unwrap_err();
+                assert!(matches!(err, EccError::IdentityPoint));
+
+                // P + P using incomplete addition.
+                let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+                let p =
+                    NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+                let err = p
+                    .add_incomplete(layouter.namespace(|| "P + P"), &p)
+                    .unwrap_err();
+                assert!(matches!(err, EccError::ExceptionalAddition));
+
+                // A short scalar with magnitude 2^64.
+                let magnitude = chip.load_private(
+                    layouter.namespace(|| "magnitude"),
+                    config.advices[0],
+                    Some(pallas::Base::from_u64(u64::MAX) + pallas::Base::one()),
+                )?;
+                let sign = chip.load_private(
+                    layouter.namespace(|| "sign"),
+                    config.advices[0],
+                    Some(pallas::Base::one()),
+                )?;
+                let base = FixedPoint::from_inner(chip.clone(), FixedBase::Short);
+                let err = base
+                    .mul_short(layouter.namespace(|| "[2^64] B"), (magnitude, sign))
+                    .unwrap_err();
+                assert!(matches!(err, EccError::OutOfRangeScalar));
+
+                // A point with an unknown value cannot be assigned.
+                let err = NonIdentityPoint::new(chip, layouter.namespace(|| "unknown"), None)
+                    .unwrap_err();
+                assert!(matches!(err, EccError::Synthesis(Error::SynthesisError)));
+
+                // The error converts to a `plonk::Error` at the circuit boundary.
+                Err(err.into())
+            }
+        }
+
+        assert!(MockProver::run(11, &ErrorCircuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn ecc_chip_vesta() {
+        use halo2::dev::MockProver;
+
+        let k = 13;
+        let prover = MockProver::run(k, &VestaCircuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_ecc_chip() {
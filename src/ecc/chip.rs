@@ -8,21 +8,43 @@ use crate::{
 };
 use arrayvec::ArrayVec;
 use std::marker::PhantomData;
+#[cfg(feature = "dev")]
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
-use group::prime::PrimeCurveAffine;
+use ff::{Field, PrimeField, PrimeFieldBits};
+use group::{prime::PrimeCurveAffine, Curve};
 use halo2::{
-    circuit::{Chip, Layouter},
-    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+};
+use pasta_curves::{
+    arithmetic::{CurveAffine, FieldExt},
+    pallas, vesta,
 };
-use pasta_curves::{arithmetic::CurveAffine, pallas};
 
 pub(super) mod add;
 pub(super) mod add_incomplete;
+pub(super) mod double;
+pub(super) mod equals;
 pub(super) mod mul;
 pub(super) mod mul_fixed;
+pub(super) mod negate;
+pub(super) mod scalar_bits;
+#[cfg(feature = "test-utils")]
+mod self_test;
 pub(super) mod witness_point;
 
-pub use mul_fixed::{compute_lagrange_coeffs, compute_window_table, find_zs_and_us};
+pub use mul::AddPolicy;
+pub use mul_fixed::{
+    check_tables, compute_lagrange_coeffs, compute_window_table, dump_tables, find_zs_and_us,
+    find_zs_and_us_bounded, load_tables, FixedBaseTable, FixedBaseTables, GeneratorFixedBase,
+    WindowError, MAX_Z_TRIES,
+};
+#[cfg(feature = "test-utils")]
+pub use self_test::ecc_chip_self_test;
 
 /// Number of windows for a full-width scalar
 pub const NUM_WINDOWS: usize =
@@ -50,26 +72,173 @@ pub(crate) const T_Q: u128 = 45560315531506369815346746415080538113;
 /// <https://github.com/zcash/pasta>
 pub(crate) const T_P: u128 = 45560315531419706090280762371685220353;
 
+/// Number of 10-bit words range-checked by the lookup argument when enforcing
+/// that a 130-bit value is canonical.
+const CANONICITY_LOOKUP_WORDS: usize = 13;
+
+/// The operations provided by [`EccChip`], for use with [`rows`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EccOp {
+    /// [`EccInstructions::witness_point`] or
+    /// [`EccInstructions::witness_point_non_id`].
+    WitnessPoint,
+    /// [`EccInstructions::add_incomplete`].
+    AddIncomplete,
+    /// [`EccInstructions::add`].
+    Add,
+    /// [`EccInstructions::negate`].
+    Negate,
+    /// [`EccInstructions::double`].
+    Double,
+    /// [`EccInstructions::points_equal`].
+    PointsEqual,
+    /// [`EccInstructions::mul`].
+    Mul,
+    /// [`EccInstructions::mul_fixed`].
+    MulFixed,
+    /// [`EccInstructions::mul_fixed_short`].
+    MulFixedShort,
+    /// [`EccInstructions::mul_fixed_base_field_elem`].
+    MulFixedBaseFieldElem,
+}
+
+/// Returns the total height of the regions assigned by `op`.
+///
+/// Regions assigned by an instruction may be placed alongside each other by the
+/// floor planner, so this is an upper bound on the rows the instruction adds to
+/// the circuit. It does not include the rows used by the lookup table or by
+/// fixed-base constants, which are shared across instructions.
+///
+/// With $n$ = [`NUM_WINDOWS`] $= \lceil 255 / w \rceil$ and
+/// $n_s$ = [`NUM_WINDOWS_SHORT`] $= \lceil 64 / w \rceil$, where
+/// $w$ = [`FIXED_BASE_WINDOW_SIZE`]:
+///
+/// - `mul_fixed`: $n + 2$ (one row per window, then a complete addition).
+/// - `mul_fixed_short`: $n_s + 3$ (the running sum $z_0, \ldots, z_{n_s}$, then a
+///   complete addition and the sign).
+/// - `mul_fixed_base_field_elem`: $n + 20$ (the running sum $z_0, \ldots, z_n$,
+///   a complete addition, and the canonicity check on $\alpha$: a 13-word lookup
+///   range check plus three rows).
+/// - `mul`: $\lceil 251 / 2 \rceil + 2 \cdot 3 + 5$ for the double-and-add
+///   region, plus 18 rows for the overflow check.
+pub fn rows(op: EccOp) -> usize {
+    // A lookup range check of `num_words` words assigns `z_0, ..., z_{num_words}`.
+    let range_check = CANONICITY_LOOKUP_WORDS + 1;
+
+    match op {
+        EccOp::WitnessPoint | EccOp::Negate | EccOp::Double | EccOp::PointsEqual => 1,
+        EccOp::AddIncomplete | EccOp::Add => 2,
+        EccOp::Mul => {
+            // Initial complete addition (2 rows, sharing its output row with the
+            // first row of incomplete addition), the `lo` half of incomplete
+            // addition with its initialization and final rows, two rows per
+            // complete-addition bit, and two rows for the LSB.
+            let double_and_add =
+                1 + mul::INCOMPLETE_LO_RANGE.len() + 2 + 2 * mul::NUM_COMPLETE_BITS + 2;
+            // s = alpha + k_254 * 2^130, the range check on s, and the final
+            // overflow check region.
+            let overflow = 1 + range_check + 3;
+            double_and_add + overflow
+        }
+        EccOp::MulFixed => NUM_WINDOWS + rows(EccOp::Add),
+        EccOp::MulFixedShort => (NUM_WINDOWS_SHORT + 1) + rows(EccOp::Add),
+        EccOp::MulFixedBaseFieldElem => (NUM_WINDOWS + 1) + rows(EccOp::Add) + range_check + 3,
+    }
+}
+
+/// Asserts that the result witnessed by an instruction matches the same
+/// operation computed outside the circuit, when both are known.
+///
+/// This runs in tests, and in other builds with the `debug-checks` feature
+/// enabled. It catches witness-generation bugs at the point of assignment,
+/// rather than as an opaque constraint failure in `MockProver`.
+#[cfg(any(test, feature = "debug-checks"))]
+pub(crate) fn debug_check_point<C: CurveAffine>(expected: Option<C::Curve>, witnessed: Option<C>) {
+    if let (Some(expected), Some(witnessed)) = (expected, witnessed) {
+        assert_eq!(
+            expected.to_affine(),
+            witnessed,
+            "witnessed result does not match the out-of-circuit computation"
+        );
+    }
+}
+
+/// A curve over which the [`EccChip`] can be instantiated.
+///
+/// The chip's layouts are sized for the Pasta cycle: both fields must be
+/// 255 bits wide, with moduli of the form $2^{254} + t$ for $t < 2^{130}$.
+/// [`EccChip::configure`] checks this against the associated constants below.
+///
+/// In particular, [`EccCurve::NUM_WINDOWS`] must equal the module constant
+/// [`NUM_WINDOWS`]. The fixed-base configs, [`EccScalarFixed`], and the table
+/// helpers ([`dump_tables`], [`load_tables`]) are sized by that constant,
+/// because a const generic argument cannot depend on `C` on stable Rust. A
+/// curve with a different scalar length is rejected when the chip is
+/// configured.
+pub trait EccCurve:
+    CurveAffine<Base = <Self as EccCurve>::BaseField, ScalarExt = <Self as EccCurve>::ScalarField>
+{
+    /// The base field of the curve. This is the field the circuit is defined over.
+    type BaseField: FieldExt + PrimeFieldBits;
+    /// The scalar field of the curve.
+    type ScalarField: FieldExt + PrimeFieldBits;
+
+    /// The scalar field modulus is $q = 2^{254} + \mathsf{t_q}$.
+    const T_Q: u128;
+    /// The base field modulus is $p = 2^{254} + \mathsf{t_p}$.
+    const T_P: u128;
+
+    /// The cofactor $h$ of the curve, such that the curve has order $h \cdot q$.
+    /// The Pasta curves have prime order, so this defaults to 1.
+    const COFACTOR: u64 = 1;
+
+    /// Number of bits in a base field element.
+    const L_BASE: usize = <<Self as EccCurve>::BaseField as PrimeField>::NUM_BITS as usize;
+    /// Number of bits in a scalar field element.
+    const L_SCALAR: usize = <<Self as EccCurve>::ScalarField as PrimeField>::NUM_BITS as usize;
+    /// Number of windows for a full-width scalar. This must equal
+    /// [`NUM_WINDOWS`]; see the trait documentation.
+    const NUM_WINDOWS: usize =
+        (Self::L_SCALAR + FIXED_BASE_WINDOW_SIZE - 1) / FIXED_BASE_WINDOW_SIZE;
+}
+
+impl EccCurve for pallas::Affine {
+    type BaseField = pallas::Base;
+    type ScalarField = pallas::Scalar;
+
+    const T_Q: u128 = T_Q;
+    const T_P: u128 = T_P;
+}
+
+/// The Vesta scalar field is the Pallas base field, and vice versa.
+impl EccCurve for vesta::Affine {
+    type BaseField = vesta::Base;
+    type ScalarField = vesta::Scalar;
+
+    const T_Q: u128 = T_P;
+    const T_P: u128 = T_Q;
+}
+
 /// A curve point represented in affine (x, y) coordinates, or the
 /// identity represented as (0, 0).
 /// Each coordinate is assigned to a cell.
 #[derive(Copy, Clone, Debug)]
-pub struct EccPoint {
+pub struct EccPoint<C: CurveAffine> {
     /// x-coordinate
-    x: CellValue<pallas::Base>,
+    x: CellValue<C::Base>,
     /// y-coordinate
-    y: CellValue<pallas::Base>,
+    y: CellValue<C::Base>,
 }
 
-impl EccPoint {
+impl<C: CurveAffine> EccPoint<C> {
     /// Returns the value of this curve point, if known.
-    pub fn point(&self) -> Option<pallas::Affine> {
+    pub fn point(&self) -> Option<C> {
         match (self.x.value(), self.y.value()) {
             (Some(x), Some(y)) => {
-                if x == pallas::Base::zero() && y == pallas::Base::zero() {
-                    Some(pallas::Affine::identity())
+                if x == C::Base::zero() && y == C::Base::zero() {
+                    Some(C::identity())
                 } else {
-                    Some(pallas::Affine::from_xy(x, y).unwrap())
+                    Some(C::from_xy(x, y).unwrap())
                 }
             }
             _ => None,
@@ -77,65 +246,61 @@ impl EccPoint {
     }
     /// The cell containing the affine short-Weierstrass x-coordinate,
     /// or 0 for the zero point.
-    pub fn x(&self) -> CellValue<pallas::Base> {
+    pub fn x(&self) -> CellValue<C::Base> {
         self.x
     }
     /// The cell containing the affine short-Weierstrass y-coordinate,
     /// or 0 for the zero point.
-    pub fn y(&self) -> CellValue<pallas::Base> {
+    pub fn y(&self) -> CellValue<C::Base> {
         self.y
     }
 
-    #[cfg(test)]
     fn is_identity(&self) -> Option<bool> {
-        self.x.value().map(|x| x == pallas::Base::zero())
+        self.x.value().map(|x| x == C::Base::zero())
     }
 }
 
 /// A non-identity point represented in affine (x, y) coordinates.
 /// Each coordinate is assigned to a cell.
 #[derive(Copy, Clone, Debug)]
-pub struct NonIdentityEccPoint {
+pub struct NonIdentityEccPoint<C: CurveAffine> {
     /// x-coordinate
-    x: CellValue<pallas::Base>,
+    x: CellValue<C::Base>,
     /// y-coordinate
-    y: CellValue<pallas::Base>,
+    y: CellValue<C::Base>,
 }
 
-impl NonIdentityEccPoint {
+impl<C: CurveAffine> NonIdentityEccPoint<C> {
     /// Constructs a point from its coordinates, without checking they are on the curve.
     ///
     /// This is an internal API that we only use where we know we have a valid non-identity
     /// curve point (specifically inside Sinsemilla).
-    pub(crate) fn from_coordinates_unchecked(
-        x: CellValue<pallas::Base>,
-        y: CellValue<pallas::Base>,
-    ) -> Self {
+    pub(crate) fn from_coordinates_unchecked(x: CellValue<C::Base>, y: CellValue<C::Base>) -> Self {
         NonIdentityEccPoint { x, y }
     }
 
     /// Returns the value of this curve point, if known.
-    pub fn point(&self) -> Option<pallas::Affine> {
+    pub fn point(&self) -> Option<C> {
         match (self.x.value(), self.y.value()) {
             (Some(x), Some(y)) => {
-                assert!(x != pallas::Base::zero() && y != pallas::Base::zero());
-                Some(pallas::Affine::from_xy(x, y).unwrap())
+                assert!(x != C::Base::zero() && y != C::Base::zero());
+                Some(C::from_xy(x, y).unwrap())
             }
             _ => None,
         }
     }
     /// The cell containing the affine short-Weierstrass x-coordinate.
-    pub fn x(&self) -> CellValue<pallas::Base> {
+    pub fn x(&self) -> CellValue<C::Base> {
         self.x
     }
     /// The cell containing the affine short-Weierstrass y-coordinate.
-    pub fn y(&self) -> CellValue<pallas::Base> {
+    pub fn y(&self) -> CellValue<C::Base> {
         self.y
     }
 }
 
-impl From<NonIdentityEccPoint> for EccPoint {
-    fn from(non_id_point: NonIdentityEccPoint) -> Self {
+impl<C: CurveAffine> From<NonIdentityEccPoint<C>> for EccPoint<C> {
+    fn from(non_id_point: NonIdentityEccPoint<C>) -> Self {
         Self {
             x: non_id_point.x,
             y: non_id_point.y,
@@ -146,7 +311,7 @@ impl From<NonIdentityEccPoint> for EccPoint {
 /// Configuration for the ECC chip
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[allow(non_snake_case)]
-pub struct EccConfig {
+pub struct EccConfig<C: EccCurve> {
     /// Advice columns needed by instructions in the ECC chip.
     pub advices: [Column<Advice>; 10],
 
@@ -186,22 +351,147 @@ pub struct EccConfig {
     pub q_point: Selector,
     /// Witness non-identity point
     pub q_point_non_id: Selector,
+    /// Witness point with an identity flag
+    pub q_point_flag: Selector,
+
+    /// Point negation
+    pub q_negate: Selector,
+    /// Point doubling
+    pub q_double: Selector,
+    /// Point equality flag
+    pub q_point_equals: Selector,
+    /// Bitwise decomposition of a variable-base scalar
+    pub q_scalar_bits: Selector,
+    /// Canonicity check on the bitwise decomposition of a variable-base scalar
+    pub q_scalar_bits_canon: Selector,
+    /// Recomposition of the windows of a full-width fixed-base scalar
+    pub q_mul_fixed_full_recompose: Selector,
+    /// Incomplete addition that flags exceptional cases instead of failing
+    pub q_add_incomplete_flag: Selector,
 
     /// Lookup range check using 10-bit lookup table
-    pub lookup_config: LookupRangeCheckConfig<pallas::Base, { sinsemilla::K }>,
+    pub lookup_config: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
     /// Running sum decomposition.
-    pub running_sum_config: RunningSumConfig<pallas::Base, { FIXED_BASE_WINDOW_SIZE }>,
+    pub running_sum_config: RunningSumConfig<C::Base, { FIXED_BASE_WINDOW_SIZE }>,
+
+    /// Rows on which the selectors above have been enabled.
+    usage: SelectorUsage,
+}
+
+/// Counts the rows on which each of the [`EccChip`]'s selectors is enabled.
+///
+/// The counts are shared between clones of an [`EccConfig`], and between the
+/// sub-configs built from it. Selectors are only counted with the `dev`
+/// feature enabled; otherwise enabling a selector through this does nothing
+/// more than [`Selector::enable`].
+#[derive(Clone, Debug, Default)]
+struct SelectorUsage {
+    #[cfg(feature = "dev")]
+    counts: Arc<Mutex<UsageCounts>>,
+}
+
+#[cfg(feature = "dev")]
+#[derive(Debug, Default)]
+struct UsageCounts {
+    // Offsets enabled so far by the current run of a region's assignment.
+    region: HashMap<Selector, HashSet<usize>>,
+    // Rows enabled in the regions that have been assigned.
+    total: HashMap<Selector, usize>,
+}
+
+// Selector usage is diagnostic state, and does not affect config equality.
+impl PartialEq for SelectorUsage {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SelectorUsage {}
+
+impl SelectorUsage {
+    /// Enables `selector` at `offset` within `region`, counting the row.
+    ///
+    /// A row on which the selector is enabled more than once is counted once.
+    fn enable<F: FieldExt>(
+        &self,
+        selector: &Selector,
+        region: &mut Region<'_, F>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        selector.enable(region, offset)?;
+        #[cfg(feature = "dev")]
+        {
+            self.counts
+                .lock()
+                .unwrap()
+                .region
+                .entry(*selector)
+                .or_default()
+                .insert(offset);
+        }
+        Ok(())
+    }
+
+    /// Assigns a region whose selectors are enabled through
+    /// [`SelectorUsage::enable`].
+    #[cfg(not(feature = "dev"))]
+    fn assign_region<F, A, AR, N, NR>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: N,
+        assignment: A,
+    ) -> Result<AR, Error>
+    where
+        F: FieldExt,
+        A: FnMut(Region<'_, F>) -> Result<AR, Error>,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        layouter.assign_region(name, assignment)
+    }
+
+    /// Assigns a region whose selectors are enabled through
+    /// [`SelectorUsage::enable`].
+    ///
+    /// The floor planner may run `assignment` more than once, for example to
+    /// measure the region before assigning it, so only the rows enabled by its
+    /// last run are counted.
+    #[cfg(feature = "dev")]
+    fn assign_region<F, A, AR, N, NR>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: N,
+        mut assignment: A,
+    ) -> Result<AR, Error>
+    where
+        F: FieldExt,
+        A: FnMut(Region<'_, F>) -> Result<AR, Error>,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        let result = layouter.assign_region(name, |region| {
+            self.counts.lock().unwrap().region.clear();
+            assignment(region)
+        })?;
+
+        let mut counts = self.counts.lock().unwrap();
+        let region = std::mem::take(&mut counts.region);
+        for (selector, offsets) in region {
+            *counts.total.entry(selector).or_insert(0) += offsets.len();
+        }
+        Ok(result)
+    }
 }
 
 /// A chip implementing EccInstructions
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct EccChip<Fixed: super::FixedPoints<pallas::Affine>> {
-    config: EccConfig,
+pub struct EccChip<C: EccCurve, Fixed: super::FixedPoints<C>> {
+    config: EccConfig<C>,
     _marker: PhantomData<Fixed>,
 }
 
-impl<Fixed: super::FixedPoints<pallas::Affine>> Chip<pallas::Base> for EccChip<Fixed> {
-    type Config = EccConfig;
+impl<C: EccCurve, Fixed: super::FixedPoints<C>> Chip<C::Base> for EccChip<C, Fixed> {
+    type Config = EccConfig<C>;
     type Loaded = ();
 
     fn config(&self) -> &Self::Config {
@@ -213,30 +503,109 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> Chip<pallas::Base> for EccChip<F
     }
 }
 
-impl<Fixed: super::FixedPoints<pallas::Affine>> UtilitiesInstructions<pallas::Base>
-    for EccChip<Fixed>
+impl<C: EccCurve, Fixed: super::FixedPoints<C>> UtilitiesInstructions<C::Base>
+    for EccChip<C, Fixed>
 {
-    type Var = CellValue<pallas::Base>;
+    type Var = CellValue<C::Base>;
 }
 
-impl<FixedPoints: super::FixedPoints<pallas::Affine>> EccChip<FixedPoints> {
-    pub fn construct(config: <Self as Chip<pallas::Base>>::Config) -> Self {
+impl<C: EccCurve, FixedPoints: super::FixedPoints<C>> EccChip<C, FixedPoints> {
+    pub fn construct(config: <Self as Chip<C::Base>>::Config) -> Self {
         Self {
             config,
             _marker: PhantomData,
         }
     }
 
+    /// Returns the number of rows on which each of this chip's selectors has
+    /// been enabled, keyed by the selectors in [`EccConfig`].
+    ///
+    /// The counts are shared by every chip constructed from the same config,
+    /// and accumulate each time the circuit is synthesized. Only the selectors
+    /// of the ECC gates are tracked; the selectors enabled by the range-check
+    /// and running-sum helpers are not. `q_mul_fixed_running_sum` is shared
+    /// with the running-sum helper, so only the rows on which the fixed-base
+    /// gates enable it are counted. Selectors that were never enabled are
+    /// omitted.
+    #[cfg(feature = "dev")]
+    pub fn selector_usage(&self) -> HashMap<Selector, usize> {
+        self.config.usage.counts.lock().unwrap().total.clone()
+    }
+
     /// # Side effects
     ///
     /// All columns in `advices` will be equality-enabled.
-    #[allow(non_snake_case)]
     pub fn configure(
-        meta: &mut ConstraintSystem<pallas::Base>,
+        meta: &mut ConstraintSystem<C::Base>,
         advices: [Column<Advice>; 10],
         lagrange_coeffs: [Column<Fixed>; 8],
-        range_check: LookupRangeCheckConfig<pallas::Base, { sinsemilla::K }>,
-    ) -> <Self as Chip<pallas::Base>>::Config {
+        range_check: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    ) -> <Self as Chip<C::Base>>::Config {
+        let fixed_z = meta.fixed_column();
+        Self::configure_with_columns(meta, advices, lagrange_coeffs, fixed_z, range_check)
+    }
+
+    /// Configures the chip with a [`LookupRangeCheckConfig`] that is shared
+    /// with other chips, such as the Sinsemilla chip.
+    ///
+    /// Configuring a range check does not load its lookup table, so sharing the
+    /// same config between chips does not duplicate any table loads. The caller
+    /// is responsible for loading the table exactly once, either with
+    /// [`LookupRangeCheckConfig::load`] or through a chip that loads it.
+    ///
+    /// # Side effects
+    ///
+    /// All columns in `advices` will be equality-enabled.
+    pub fn configure_shared(
+        meta: &mut ConstraintSystem<C::Base>,
+        advices: [Column<Advice>; 10],
+        lagrange_coeffs: [Column<Fixed>; 8],
+        range_check: &LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    ) -> <Self as Chip<C::Base>>::Config {
+        Self::configure(meta, advices, lagrange_coeffs, range_check.clone())
+    }
+
+    /// Configures the chip over caller-allocated columns, all of which may be
+    /// shared with other chips.
+    ///
+    /// The chip only uses its columns within its own regions, so sharing
+    /// columns with other chips does not affect its constraints. It needs:
+    /// - exactly 10 advice columns in `advices`;
+    /// - 8 fixed columns in `lagrange_coeffs`, and one fixed column `fixed_z`,
+    ///   to hold the fixed-base tables.
+    ///
+    /// The circuit must also have a fixed column passed to
+    /// [`ConstraintSystem::enable_constant`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` does not satisfy the field size requirements documented on
+    /// [`EccCurve`].
+    ///
+    /// # Side effects
+    ///
+    /// All columns in `advices` will be equality-enabled.
+    #[allow(non_snake_case)]
+    pub fn configure_with_columns(
+        meta: &mut ConstraintSystem<C::Base>,
+        advices: [Column<Advice>; 10],
+        lagrange_coeffs: [Column<Fixed>; 8],
+        fixed_z: Column<Fixed>,
+        range_check: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    ) -> <Self as Chip<C::Base>>::Config {
+        // The region layouts are sized for 255-bit fields; see `EccCurve`.
+        assert_eq!(C::L_BASE, L_PALLAS_BASE, "the base field must be 255 bits");
+        assert_eq!(
+            C::L_SCALAR,
+            L_PALLAS_SCALAR,
+            "the scalar field must be 255 bits"
+        );
+        assert_eq!(
+            C::NUM_WINDOWS,
+            NUM_WINDOWS,
+            "full-width scalars must have NUM_WINDOWS windows"
+        );
+
         // The following columns need to be equality-enabled for their use in sub-configs:
         //
         // add::Config and add_incomplete::Config:
@@ -275,7 +644,7 @@ impl<FixedPoints: super::FixedPoints<pallas::Affine>> EccChip<FixedPoints> {
         let config = EccConfig {
             advices,
             lagrange_coeffs,
-            fixed_z: meta.fixed_column(),
+            fixed_z,
             q_add_incomplete: meta.selector(),
             q_add: meta.selector(),
             q_mul_hi: (meta.selector(), meta.selector(), meta.selector()),
@@ -289,31 +658,40 @@ impl<FixedPoints: super::FixedPoints<pallas::Affine>> EccChip<FixedPoints> {
             q_mul_fixed_running_sum,
             q_point: meta.selector(),
             q_point_non_id: meta.selector(),
+            q_negate: meta.selector(),
+            q_double: meta.selector(),
+            q_point_flag: meta.selector(),
+            q_point_equals: meta.selector(),
+            q_scalar_bits: meta.selector(),
+            q_scalar_bits_canon: meta.selector(),
+            q_mul_fixed_full_recompose: meta.selector(),
+            q_add_incomplete_flag: meta.selector(),
             lookup_config: range_check,
             running_sum_config,
+            usage: SelectorUsage::default(),
         };
 
         // Create witness point gate
         {
-            let config: witness_point::Config = (&config).into();
+            let config: witness_point::Config<C> = (&config).into();
             config.create_gate(meta);
         }
 
         // Create incomplete point addition gate
         {
-            let config: add_incomplete::Config = (&config).into();
+            let config: add_incomplete::Config<C> = (&config).into();
             config.create_gate(meta);
         }
 
         // Create complete point addition gate
         {
-            let add_config: add::Config = (&config).into();
+            let add_config: add::Config<C> = (&config).into();
             add_config.create_gate(meta);
         }
 
         // Create variable-base scalar mul gates
         {
-            let mul_config: mul::Config = (&config).into();
+            let mul_config: mul::Config<C> = (&config).into();
             mul_config.create_gate(meta);
         }
 
@@ -321,33 +699,446 @@ impl<FixedPoints: super::FixedPoints<pallas::Affine>> EccChip<FixedPoints> {
         // and fixed-base mul using a base field element.
         {
             // The const generic does not matter when creating gates.
-            let mul_fixed_config: mul_fixed::Config<FixedPoints, { NUM_WINDOWS }> =
+            let mul_fixed_config: mul_fixed::Config<C, FixedPoints, { NUM_WINDOWS }> =
                 (&config).into();
             mul_fixed_config.running_sum_coords_gate(meta);
         }
 
         // Create gate that is only used in full-width fixed-base scalar mul.
         {
-            let mul_fixed_full_config: mul_fixed::full_width::Config<FixedPoints> =
+            let mul_fixed_full_config: mul_fixed::full_width::Config<C, FixedPoints> =
                 (&config).into();
             mul_fixed_full_config.create_gate(meta);
         }
 
         // Create gate that is only used in short fixed-base scalar mul.
         {
-            let short_config: mul_fixed::short::Config<FixedPoints> = (&config).into();
+            let short_config: mul_fixed::short::Config<C, FixedPoints, L_VALUE, NUM_WINDOWS_SHORT> =
+                (&config).into();
             short_config.create_gate(meta);
         }
 
         // Create gate that is only used in fixed-base mul using a base field element.
         {
-            let base_field_config: mul_fixed::base_field_elem::Config<FixedPoints> =
+            let base_field_config: mul_fixed::base_field_elem::Config<C, FixedPoints> =
                 (&config).into();
             base_field_config.create_gate(meta);
         }
 
+        // Create point negation gate
+        {
+            let negate_config: negate::Config<C> = (&config).into();
+            negate_config.create_gate(meta);
+        }
+
+        // Create point doubling gate
+        {
+            let double_config: double::Config<C> = (&config).into();
+            double_config.create_gate(meta);
+        }
+
+        // Create witness point identity flag gate
+        {
+            let config: witness_point::Config<C> = (&config).into();
+            config.create_flag_gate(meta);
+        }
+
+        // Create point equality gate
+        {
+            let equals_config: equals::Config<C> = (&config).into();
+            equals_config.create_gate(meta);
+        }
+
+        // Create scalar bit decomposition gates
+        {
+            let scalar_bits_config: scalar_bits::Config<C> = (&config).into();
+            scalar_bits_config.create_gate(meta);
+        }
+
+        // Create full-width fixed-base scalar recomposition gate
+        {
+            let full_width_config: mul_fixed::full_width::Config<C, FixedPoints> = (&config).into();
+            full_width_config.create_recompose_gate(meta);
+        }
+
+        // Create flagged incomplete point addition gate
+        {
+            let add_incomplete_config: add_incomplete::Config<C> = (&config).into();
+            add_incomplete_config.create_flag_gate(meta);
+        }
+
         config
     }
+
+    /// Performs fixed-base scalar multiplication using a short signed scalar whose
+    /// magnitude is at most `MAGNITUDE_BITS` bits, returning `[magnitude * sign] base`.
+    ///
+    /// `NUM_WINDOWS` must be the number of 3-bit windows needed to hold `MAGNITUDE_BITS`
+    /// bits, and `base` must provide fixed-base tables for that many windows.
+    /// `MAGNITUDE_BITS` must not be congruent to 2 mod 3.
+    #[allow(clippy::type_complexity)]
+    pub fn mul_fixed_short_with_bits<const MAGNITUDE_BITS: usize, const NUM_WINDOWS: usize>(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        magnitude_sign: (CellValue<C::Base>, CellValue<C::Base>),
+        base: &FixedPoints,
+    ) -> Result<(EccPoint<C>, EccScalarFixedShort<C>), Error> {
+        let config: mul_fixed::short::Config<C, FixedPoints, MAGNITUDE_BITS, NUM_WINDOWS> =
+            self.config().into();
+        config.check_magnitude(&magnitude_sign.0)?;
+        config.assign(
+            layouter.namespace(|| format!("short fixed-base mul of {:?}", base)),
+            magnitude_sign,
+            base,
+        )
+    }
+
+    /// Witnesses a signed 64-bit value as the `(magnitude, sign)` pair expected by
+    /// [`EccInstructions::mul_fixed_short`].
+    ///
+    /// The magnitude is range-checked to 64 bits. The sign is witnessed as `±1`;
+    /// it is constrained to be `±1` by the short fixed-base mul gate.
+    pub fn witness_scalar_fixed_short(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        value: Option<i64>,
+    ) -> Result<(CellValue<C::Base>, CellValue<C::Base>), Error> {
+        let magnitude = value.map(|value| C::Base::from_u64(value.unsigned_abs()));
+        let sign = value.map(|value| {
+            if value < 0 {
+                -C::Base::one()
+            } else {
+                C::Base::one()
+            }
+        });
+
+        // Decompose the magnitude into six 10-bit words, leaving a 4-bit
+        // remainder z_6 that is then short range-checked.
+        let zs = self.config().lookup_config.witness_check(
+            layouter.namespace(|| "magnitude"),
+            magnitude,
+            L_VALUE / sinsemilla::K,
+            false,
+        )?;
+        self.config().lookup_config.copy_short_check(
+            layouter.namespace(|| "magnitude z_6"),
+            zs[L_VALUE / sinsemilla::K],
+            L_VALUE % sinsemilla::K,
+        )?;
+        let magnitude = zs[0];
+
+        let sign = self.load_private(
+            layouter.namespace(|| "sign"),
+            self.config().advices[0],
+            sign,
+        )?;
+
+        Ok((magnitude, sign))
+    }
+
+    /// Witnesses the given point exactly as [`EccInstructions::witness_point`]
+    /// does, additionally reporting whether the witnessed value was the
+    /// identity.
+    ///
+    /// The identity is still accepted and mapped to `(0, 0)`; the returned
+    /// flag lets callers notice when it was fed in by accident. The flag is
+    /// derived from the witness and is not constrained in the circuit, and it
+    /// is `None` if no witness is available. Use
+    /// [`EccInstructions::witness_point_with_flag`] for an in-circuit flag.
+    pub fn witness_point_strict(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        value: Option<C>,
+    ) -> Result<(EccPoint<C>, Option<bool>), Error> {
+        let point = self.witness_point(layouter, value)?;
+        let is_identity = value.map(|value| value == C::identity());
+        Ok((point, is_identity))
+    }
+
+    /// Witnesses the non-identity point with x-coordinate `x` whose
+    /// y-coordinate has parity `y_is_odd`, recovering `y` from the curve
+    /// equation $y^2 = x^3 + b$. Returns the point along with a boolean cell
+    /// holding the parity of `y`.
+    ///
+    /// The point is constrained on-curve as by
+    /// [`EccInstructions::witness_point_non_id`]. `y` is then decomposed into
+    /// its canonical bits, and the returned cell is its least significant bit.
+    /// The caller must constrain this cell (for example, to an instance column
+    /// or to an existing sign bit) for the parity to be bound. The
+    /// decomposition takes 255 rows plus the canonicity check.
+    ///
+    /// Returns an error if `x^3 + b` has no square root.
+    #[allow(clippy::type_complexity)]
+    pub fn witness_point_from_x_and_sign(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        x: Option<C::Base>,
+        y_is_odd: Option<bool>,
+    ) -> Result<(NonIdentityEccPoint<C>, CellValue<C::Base>), Error> {
+        let value = match (x, y_is_odd) {
+            (Some(x), Some(y_is_odd)) => {
+                let y: Option<C::Base> = (x.square() * x + C::b()).sqrt().into();
+                let y = y.ok_or(Error::SynthesisError)?;
+                let y = if (y.to_bytes()[0] & 1 == 1) == y_is_odd {
+                    y
+                } else {
+                    -y
+                };
+                let point: Option<C> = C::from_xy(x, y).into();
+                Some(point.ok_or(Error::SynthesisError)?)
+            }
+            _ => None,
+        };
+        let point = self.witness_point_non_id(layouter, value)?;
+
+        // The least significant canonical bit of y is its parity.
+        let config: scalar_bits::Config<C> = self.config().into();
+        let bits = config.assign(layouter.namespace(|| "parity of y"), point.y())?;
+
+        Ok((point, bits[0]))
+    }
+
+    /// Performs complete point addition, returning `a + b` along with the cell
+    /// containing the slope λ used by the addition.
+    ///
+    /// λ is constrained to be the slope of the line through `a` and `b` if
+    /// their x-coordinates differ, and the slope of the tangent at `a` if they
+    /// are equal and `a` is not the identity. It is unconstrained if both
+    /// points are the identity.
+    #[allow(clippy::type_complexity)]
+    pub fn add_with_slope(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        a: &EccPoint<C>,
+        b: &EccPoint<C>,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        let config: add::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            &mut layouter,
+            || "complete point addition with slope",
+            |mut region| config.assign_region_with_slope(a, b, 0, &mut region),
+        )
+    }
+
+    /// Performs fixed-base scalar multiplication using a full-width scalar, and
+    /// additionally binds the scalar's windows to its value.
+    ///
+    /// [`EccInstructions::mul_fixed`] witnesses each window independently, and
+    /// does not constrain the windows to recompose to `scalar`. This also returns
+    /// a cell constrained to equal $\sum_i k_i \cdot (2^3)^i$ over the base
+    /// field, which holds the value of `scalar` (as an integer, reduced modulo
+    /// the base field modulus). The caller must constrain this cell for the
+    /// binding to be meaningful.
+    #[allow(clippy::type_complexity)]
+    pub fn mul_fixed_checked(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
+        base: &FixedPoints,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>, CellValue<C::Base>), Error> {
+        let (result, scalar_fixed) = self.mul_fixed(&mut layouter, scalar, base)?;
+        let value = self.bind_scalar_fixed(&mut layouter, &scalar_fixed)?;
+        Ok((result, scalar_fixed, value))
+    }
+
+    /// As [`EccInstructions::mul_fixed`], additionally returning the value of
+    /// the accumulator after each of the `NUM_WINDOWS` windows. The last
+    /// element of the trace is the result `[scalar] base`.
+    ///
+    /// The trace is read from the witnessed cells; it adds no constraints.
+    #[cfg(any(test, feature = "debug-checks"))]
+    #[allow(clippy::type_complexity)]
+    pub fn mul_fixed_with_trace(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
+        base: &FixedPoints,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>, Vec<EccPoint<C>>), Error> {
+        base.validate()?;
+        let config: mul_fixed::full_width::Config<C, FixedPoints> = self.config().into();
+        config.assign_with_trace(layouter, scalar, base)
+    }
+
+    /// Performs fixed-base scalar multiplication by two full-width scalars,
+    /// returning `[a] g + [b] h` along with the witnessed scalars.
+    ///
+    /// This uses a single complete addition, instead of the three used by two
+    /// calls to `mul_fixed` followed by `add`, in the same number of rows. The
+    /// windows of `g` and `h` are not interleaved, as each row holds the
+    /// fixed-base tables of a single base.
+    ///
+    /// Returns [`Error::SynthesisError`] if `g = ±h`, for which the incomplete
+    /// additions would be unsound. `g` and `h` must otherwise have no known
+    /// discrete logarithm relation between them.
+    #[allow(clippy::type_complexity)]
+    pub fn mul_fixed_pair(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        a: Option<C::Scalar>,
+        g: &FixedPoints,
+        b: Option<C::Scalar>,
+        h: &FixedPoints,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>, EccScalarFixed<C>), Error> {
+        g.validate()?;
+        h.validate()?;
+        let config: mul_fixed::full_width::Config<C, FixedPoints> = self.config().into();
+        config.assign_pair(
+            layouter.namespace(|| format!("fixed-base mul of {:?} and {:?}", g, h)),
+            a,
+            g,
+            b,
+            h,
+        )
+    }
+
+    /// Performs variable-base scalar multiplication `[scalar] base`, using
+    /// `policy` to choose between incomplete and complete addition in the
+    /// double-and-add steps. Returns the result along with the scalar.
+    ///
+    /// [`AddPolicy::Hybrid`] lays out the same circuit as
+    /// [`EccInstructions::mul`]. See [`AddPolicy::AllIncomplete`] before
+    /// using that policy.
+    pub fn mul_with_policy(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: &CellValue<C::Base>,
+        base: &NonIdentityEccPoint<C>,
+        policy: AddPolicy,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        let config: mul::Config<C> = self.config().into();
+        config.assign_with_policy(
+            layouter.namespace(|| format!("variable-base scalar mul ({:?})", policy)),
+            *scalar,
+            base,
+            policy,
+        )
+    }
+
+    /// Performs fixed-base scalar multiplication using the base field element
+    /// with little-endian bits `bits` as the scalar, for example bits taken
+    /// from a Sinsemilla message decomposition.
+    ///
+    /// Each bit is boolean-constrained and the bits are recombined in-circuit,
+    /// so the result is the same as [`EccInstructions::mul_fixed_base_field_elem`]
+    /// on the recombined value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are 255 or more bits.
+    pub fn mul_fixed_from_bits(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        bits: &[CellValue<C::Base>],
+        base: &FixedPoints,
+    ) -> Result<EccPoint<C>, Error> {
+        let config: scalar_bits::Config<C> = self.config().into();
+        let scalar = config.recombine(layouter.namespace(|| "recombine scalar bits"), bits)?;
+        self.mul_fixed_base_field_elem(&mut layouter, scalar, base)
+    }
+
+    /// Performs fixed-base scalar multiplication by a scalar known to fit in
+    /// `max_bits` bits, returning `[scalar] base` along with the cells
+    /// containing the scalar's 3-bit windows in little-endian order.
+    ///
+    /// Only `ceil(max_bits / 3)` windows are laid out instead of `NUM_WINDOWS`,
+    /// and the scalar is range-checked to `max_bits` bits. Returns an error if
+    /// the scalar does not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bits` is zero, or if it needs all `NUM_WINDOWS` windows;
+    /// use [`EccInstructions::mul_fixed`] for full-width scalars.
+    #[allow(clippy::type_complexity)]
+    pub fn mul_fixed_bounded(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
+        max_bits: usize,
+        base: &FixedPoints,
+    ) -> Result<(EccPoint<C>, Vec<CellValue<C::Base>>), Error> {
+        base.validate()?;
+        let config: mul_fixed::full_width::Config<C, FixedPoints> = self.config().into();
+        config.assign_bounded(
+            layouter.namespace(|| format!("bounded fixed-base mul of {:?}", base)),
+            scalar,
+            max_bits,
+            base,
+        )
+    }
+
+    /// Computes the multi-scalar multiplication `[a_0] P_0 + ... + [a_{n-1}] P_{n-1}`
+    /// over the given `(a_i, P_i)` pairs.
+    ///
+    /// Each product is computed using variable-base scalar multiplication, and
+    /// the products are accumulated using complete addition. This uses the same
+    /// number of rows as separate calls to `mul` and `add`; it does not share an
+    /// accumulator between the products.
+    ///
+    /// Incomplete addition cannot be used for the accumulation: its gate leaves
+    /// the sum unconstrained when both inputs are equal, and the products are
+    /// under the prover's control.
+    pub fn mul_sum(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        terms: &[(CellValue<C::Base>, NonIdentityEccPoint<C>)],
+    ) -> Result<EccPoint<C>, Error> {
+        assert!(!terms.is_empty());
+
+        let products = terms
+            .iter()
+            .enumerate()
+            .map(|(idx, (scalar, base))| {
+                self.mul(
+                    &mut layouter.namespace(|| format!("[a_{}] P_{}", idx, idx)),
+                    scalar,
+                    base,
+                )
+                .map(|(product, _)| product)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let (first, products) = products.split_first().unwrap();
+        products.iter().try_fold(*first, |acc, product| {
+            self.add(&mut layouter, &acc, product)
+        })
+    }
+
+    /// Constrains `point` to be on the curve, and returns $[h] \mathsf{point}$
+    /// constrained to not be the identity.
+    ///
+    /// The cofactor is a parameter rather than `C::COFACTOR` so that the
+    /// double-and-add path can be tested on the prime-order Pasta curves.
+    fn mul_by_cofactor_non_id(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &EccPoint<C>,
+        cofactor: u64,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        assert!(cofactor > 0);
+
+        // Constrain the point to be the identity or a valid curve point.
+        let point = self.witness_point_from_coords(layouter, point.x(), point.y())?;
+
+        // Compute [h] point using double-and-add with complete addition,
+        // starting from the most significant bit of h.
+        let num_bits = 64 - cofactor.leading_zeros() as usize;
+        let mut acc = point;
+        for idx in (0..(num_bits - 1)).rev() {
+            acc = self.add(layouter, &acc, &acc)?;
+            if (cofactor >> idx) & 1 == 1 {
+                acc = self.add(layouter, &acc, &point)?;
+            }
+        }
+
+        // Constrain [h] point to not be the identity. For h = 1 this rejects
+        // the identity itself, as for any other cofactor.
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "[h] point is non-identity",
+            |mut region| config.copy_non_id(&acc, 0, &mut region),
+        )
+    }
 }
 
 /// A full-width scalar used for fixed-base scalar multiplication.
@@ -356,14 +1147,27 @@ impl<FixedPoints: super::FixedPoints<pallas::Affine>> EccChip<FixedPoints> {
 /// where `scalar = k_0 + k_1 * (2^3) + ... + k_84 * (2^3)^84` and
 /// each `k_i` is in the range [0..2^3).
 #[derive(Clone, Debug)]
-pub struct EccScalarFixed {
-    value: Option<pallas::Scalar>,
-    windows: ArrayVec<CellValue<pallas::Base>, { NUM_WINDOWS }>,
+pub struct EccScalarFixed<C: CurveAffine> {
+    value: Option<C::Scalar>,
+    windows: ArrayVec<CellValue<C::Base>, { NUM_WINDOWS }>,
+}
+
+impl<C: CurveAffine> EccScalarFixed<C> {
+    /// Returns the value of this scalar, if known.
+    pub fn value(&self) -> Option<C::Scalar> {
+        self.value
+    }
+
+    /// The cells containing the 3-bit windows of this scalar, in little-endian order.
+    pub fn windows(&self) -> &[CellValue<C::Base>] {
+        &self.windows
+    }
 }
 
 /// A signed short scalar used for fixed-base scalar multiplication.
-/// A short scalar must have magnitude in the range [0..2^64), with
-/// a sign of either 1 or -1.
+/// A short scalar must have magnitude in the range [0..2^64) (or
+/// [0..2^MAGNITUDE_BITS) when using a custom `mul_fixed::short::Config`),
+/// with a sign of either 1 or -1.
 /// This is decomposed into 3-bit windows in little-endian order
 /// using a running sum `z`, where z_{i+1} = (z_i - a_i) / (2^3)
 /// for element α = a_0 + (2^3) a_1 + ... + (2^{3(n-1)}) a_{n-1}.
@@ -374,10 +1178,22 @@ pub struct EccScalarFixed {
 /// each `k_i` is in the range [0..2^3).
 /// k_21 must be a single bit, i.e. 0 or 1.
 #[derive(Clone, Debug)]
-pub struct EccScalarFixedShort {
-    magnitude: CellValue<pallas::Base>,
-    sign: CellValue<pallas::Base>,
-    running_sum: ArrayVec<CellValue<pallas::Base>, { NUM_WINDOWS_SHORT + 1 }>,
+pub struct EccScalarFixedShort<C: CurveAffine> {
+    magnitude: CellValue<C::Base>,
+    sign: CellValue<C::Base>,
+    running_sum: Vec<CellValue<C::Base>>,
+}
+
+impl<C: CurveAffine> EccScalarFixedShort<C> {
+    /// The cell containing the magnitude of this scalar.
+    pub fn magnitude(&self) -> CellValue<C::Base> {
+        self.magnitude
+    }
+
+    /// The cell containing the sign of this scalar, which is either 1 or -1.
+    pub fn sign(&self) -> CellValue<C::Base> {
+        self.sign
+    }
 }
 
 /// A base field element used for fixed-base scalar multiplication.
@@ -390,23 +1206,23 @@ pub struct EccScalarFixedShort {
 /// Since z_0 is initialized as the scalar α, we store it as
 /// `base_field_elem`.
 #[derive(Clone, Debug)]
-struct EccBaseFieldElemFixed {
-    base_field_elem: CellValue<pallas::Base>,
-    running_sum: ArrayVec<CellValue<pallas::Base>, { NUM_WINDOWS + 1 }>,
+struct EccBaseFieldElemFixed<C: CurveAffine> {
+    base_field_elem: CellValue<C::Base>,
+    running_sum: ArrayVec<CellValue<C::Base>, { NUM_WINDOWS + 1 }>,
 }
 
-impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine> for EccChip<Fixed> {
-    type ScalarFixed = EccScalarFixed;
-    type ScalarFixedShort = EccScalarFixedShort;
-    type ScalarVar = CellValue<pallas::Base>;
-    type Point = EccPoint;
-    type NonIdentityPoint = NonIdentityEccPoint;
-    type X = CellValue<pallas::Base>;
+impl<C: EccCurve, Fixed: super::FixedPoints<C>> EccInstructions<C> for EccChip<C, Fixed> {
+    type ScalarFixed = EccScalarFixed<C>;
+    type ScalarFixedShort = EccScalarFixedShort<C>;
+    type ScalarVar = CellValue<C::Base>;
+    type Point = EccPoint<C>;
+    type NonIdentityPoint = NonIdentityEccPoint<C>;
+    type X = CellValue<C::Base>;
     type FixedPoints = Fixed;
 
     fn constrain_equal(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
+        layouter: &mut impl Layouter<C::Base>,
         a: &Self::Point,
         b: &Self::Point,
     ) -> Result<(), Error> {
@@ -421,56 +1237,281 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine>
         )
     }
 
+    fn constrain_point_to_instance(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+        instance: Column<Instance>,
+        x_row: usize,
+        y_row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(point.x().cell(), instance, x_row)?;
+        layouter.constrain_instance(point.y().cell(), instance, y_row)
+    }
+
+    fn constrain_scalar_fixed_equal(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::ScalarFixed,
+        b: &Self::ScalarFixed,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "constrain scalar windows equal",
+            |mut region| {
+                for (a, b) in a.windows.iter().zip(b.windows.iter()) {
+                    region.constrain_equal(a.cell(), b.cell())?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn bind_scalar_fixed(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+    ) -> Result<CellValue<C::Base>, Error> {
+        let config: mul_fixed::full_width::Config<C, Fixed> = self.config().into();
+        let value = config.recompose(
+            layouter.namespace(|| "bind scalar value"),
+            &scalar.windows,
+            scalar.value,
+        )?;
+        Ok(value)
+    }
+
     fn witness_point(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
-        value: Option<pallas::Affine>,
+        layouter: &mut impl Layouter<C::Base>,
+        value: Option<C>,
     ) -> Result<Self::Point, Error> {
-        let config: witness_point::Config = self.config().into();
-        layouter.assign_region(
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
             || "witness point",
             |mut region| config.point(value, 0, &mut region),
         )
     }
 
+    fn witness_points(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        values: &[Option<C>],
+    ) -> Result<Vec<Self::Point>, Error> {
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "witness points",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| config.point(*value, offset, &mut region))
+                    .collect()
+            },
+        )
+    }
+
+    fn witness_point_with_flag(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        value: Option<C>,
+    ) -> Result<(Self::Point, Self::Var), Error> {
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "witness point with identity flag",
+            |mut region| config.point_with_flag(value, 0, &mut region),
+        )
+    }
+
+    fn witness_point_from_coords(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        x: CellValue<C::Base>,
+        y: CellValue<C::Base>,
+    ) -> Result<Self::Point, Error> {
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "witness point from coordinates",
+            |mut region| config.point_from_coords(x, y, 0, &mut region),
+        )
+    }
+
+    fn identity(&self, layouter: &mut impl Layouter<C::Base>) -> Result<Self::Point, Error> {
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "identity point",
+            |mut region| config.constant_point(C::identity(), 0, &mut region),
+        )
+    }
+
     fn witness_point_non_id(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
-        value: Option<pallas::Affine>,
+        layouter: &mut impl Layouter<C::Base>,
+        value: Option<C>,
     ) -> Result<Self::NonIdentityPoint, Error> {
-        let config: witness_point::Config = self.config().into();
-        layouter.assign_region(
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
             || "witness non-identity point",
             |mut region| config.point_non_id(value, 0, &mut region),
         )
     }
 
     fn extract_p<Point: Into<Self::Point> + Clone>(point: &Point) -> Self::X {
-        let point: EccPoint = (point.clone()).into();
+        let point: EccPoint<C> = (point.clone()).into();
         point.x()
     }
 
+    fn extract_y(point: &Self::NonIdentityPoint) -> Self::Var {
+        point.y()
+    }
+
+    fn point_value(point: &Self::Point) -> Option<C> {
+        point.point()
+    }
+
     fn add_incomplete(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
+        layouter: &mut impl Layouter<C::Base>,
         a: &Self::NonIdentityPoint,
         b: &Self::NonIdentityPoint,
     ) -> Result<Self::NonIdentityPoint, Error> {
-        let config: add_incomplete::Config = self.config().into();
-        layouter.assign_region(
+        let config: add_incomplete::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
             || "incomplete point addition",
             |mut region| config.assign_region(a, b, 0, &mut region),
         )
     }
 
+    fn try_add_incomplete(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::NonIdentityPoint,
+        b: &Self::NonIdentityPoint,
+    ) -> Result<(Self::NonIdentityPoint, Self::Var), Error> {
+        let config: add_incomplete::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "flagged incomplete point addition",
+            |mut region| config.assign_region_flagged(a, b, 0, &mut region),
+        )
+    }
+
+    fn add_incomplete_constant(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::NonIdentityPoint,
+        constant: C,
+    ) -> Result<Self::NonIdentityPoint, Error> {
+        let config: add_incomplete::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "incomplete addition of constant point",
+            |mut region| config.assign_region_constant(a, constant, 0, &mut region),
+        )
+    }
+
+    fn assert_non_identity(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+    ) -> Result<Self::NonIdentityPoint, Error> {
+        // (0, 0) does not satisfy the curve equation, so the on-curve check
+        // performed by `copy_non_id` excludes the identity.
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "assert non-identity point",
+            |mut region| config.copy_non_id(point, 0, &mut region),
+        )
+    }
+
+    fn add_incomplete_or_copy(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::Point,
+        b: &Self::Point,
+        a_is_identity: bool,
+    ) -> Result<Self::Point, Error> {
+        if a_is_identity {
+            let config: add_incomplete::Config<C> = self.config().into();
+            return self.config.usage.assign_region(
+                layouter,
+                || "𝒪 + b",
+                |mut region| config.assign_region_identity(a, b, 0, &mut region),
+            );
+        }
+
+        // The incomplete addition gate does not constrain its inputs to be
+        // non-identity points, so do so here.
+        let a = self.assert_non_identity(layouter, a)?;
+        let b = self.assert_non_identity(layouter, b)?;
+        self.add_incomplete(layouter, &a, &b).map(EccPoint::from)
+    }
+
+    fn points_equal(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        a: &Self::Point,
+        b: &Self::Point,
+    ) -> Result<Self::Var, Error> {
+        let config: equals::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "point equality",
+            |mut region| config.assign_region(a, b, 0, &mut region),
+        )
+    }
+
+    fn assert_not_small_order(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::Point,
+    ) -> Result<(), Error> {
+        self.mul_by_cofactor_non_id(layouter, point, C::COFACTOR)
+            .map(|_| ())
+    }
+
+    fn negate(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::NonIdentityPoint,
+    ) -> Result<Self::NonIdentityPoint, Error> {
+        let config: negate::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "point negation",
+            |mut region| config.assign_region(point, 0, &mut region),
+        )
+    }
+
+    fn double(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        point: &Self::NonIdentityPoint,
+    ) -> Result<Self::NonIdentityPoint, Error> {
+        let config: double::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "point doubling",
+            |mut region| config.assign_region(point, 0, &mut region),
+        )
+    }
+
     fn add<A: Into<Self::Point> + Clone, B: Into<Self::Point> + Clone>(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
+        layouter: &mut impl Layouter<C::Base>,
         a: &A,
         b: &B,
     ) -> Result<Self::Point, Error> {
-        let config: add::Config = self.config().into();
-        layouter.assign_region(
+        let config: add::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
             || "complete point addition",
             |mut region| {
                 config.assign_region(&(a.clone()).into(), &(b.clone()).into(), 0, &mut region)
@@ -480,11 +1521,11 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine>
 
     fn mul(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
+        layouter: &mut impl Layouter<C::Base>,
         scalar: &Self::Var,
         base: &Self::NonIdentityPoint,
     ) -> Result<(Self::Point, Self::ScalarVar), Error> {
-        let config: mul::Config = self.config().into();
+        let config: mul::Config<C> = self.config().into();
         config.assign(
             layouter.namespace(|| "variable-base scalar mul"),
             *scalar,
@@ -492,13 +1533,39 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine>
         )
     }
 
+    fn add_then_mul(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::Var,
+        a: &Self::NonIdentityPoint,
+        b: &Self::NonIdentityPoint,
+    ) -> Result<(Self::Point, Self::ScalarVar), Error> {
+        let config: mul::Config<C> = self.config().into();
+        config.add_then_assign(
+            layouter.namespace(|| "add then variable-base scalar mul"),
+            *scalar,
+            a,
+            b,
+        )
+    }
+
+    fn scalar_var_bits(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarVar,
+    ) -> Result<Vec<Self::Var>, Error> {
+        let config: scalar_bits::Config<C> = self.config().into();
+        config.assign(layouter.namespace(|| "scalar bit decomposition"), *scalar)
+    }
+
     fn mul_fixed(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
-        scalar: Option<pallas::Scalar>,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
         base: &Self::FixedPoints,
     ) -> Result<(Self::Point, Self::ScalarFixed), Error> {
-        let config: mul_fixed::full_width::Config<Fixed> = self.config().into();
+        base.validate()?;
+        let config: mul_fixed::full_width::Config<C, Fixed> = self.config().into();
         config.assign(
             layouter.namespace(|| format!("fixed-base mul of {:?}", base)),
             scalar,
@@ -506,13 +1573,79 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine>
         )
     }
 
+    fn mul_fixed_const(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: C::Scalar,
+        base: &Self::FixedPoints,
+    ) -> Result<Self::Point, Error> {
+        let config: witness_point::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || format!("constant fixed-base mul of {:?}", base),
+            |mut region| {
+                config.constant_point((base.generator() * scalar).to_affine(), 0, &mut region)
+            },
+        )
+    }
+
+    fn mul_fixed_with_scalar(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+        base: &Self::FixedPoints,
+    ) -> Result<Self::Point, Error> {
+        base.validate()?;
+        let config: mul_fixed::full_width::Config<C, Fixed> = self.config().into();
+        config.assign_with_scalar(
+            layouter.namespace(|| format!("fixed-base mul of {:?} by witnessed scalar", base)),
+            scalar,
+            base,
+        )
+    }
+
+    fn mul_fixed_negated(
+        &self,
+        layouter: &mut impl Layouter<C::Base>,
+        scalar: &Self::ScalarFixed,
+        base: &Self::FixedPoints,
+        product: &Self::Point,
+    ) -> Result<(Self::Point, Self::ScalarFixed), Error> {
+        base.validate()?;
+        let config: mul_fixed::full_width::Config<C, Fixed> = self.config().into();
+        let (result, negated) = config.assign(
+            layouter.namespace(|| format!("fixed-base mul of {:?} by negated scalar", base)),
+            scalar.value().map(|scalar| -scalar),
+            base,
+        )?;
+
+        // Constrain [-scalar] base = -product. The negation gate only
+        // constrains y_r = -y_p, so it also holds for the identity (0, 0).
+        let negate_config: negate::Config<C> = self.config().into();
+        self.config.usage.assign_region(
+            layouter,
+            || "[-scalar] base = -[scalar] base",
+            |mut region| {
+                let product =
+                    NonIdentityEccPoint::from_coordinates_unchecked(product.x(), product.y());
+                let neg_product = negate_config.assign_region(&product, 0, &mut region)?;
+                region.constrain_equal(neg_product.x().cell(), result.x().cell())?;
+                region.constrain_equal(neg_product.y().cell(), result.y().cell())
+            },
+        )?;
+        Ok((result, negated))
+    }
+
     fn mul_fixed_short(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
-        magnitude_sign: (CellValue<pallas::Base>, CellValue<pallas::Base>),
+        layouter: &mut impl Layouter<C::Base>,
+        magnitude_sign: (CellValue<C::Base>, CellValue<C::Base>),
         base: &Self::FixedPoints,
     ) -> Result<(Self::Point, Self::ScalarFixedShort), Error> {
-        let config: mul_fixed::short::Config<Fixed> = self.config().into();
+        base.validate()?;
+        let config: mul_fixed::short::Config<C, Fixed, L_VALUE, NUM_WINDOWS_SHORT> =
+            self.config().into();
+        config.check_magnitude(&magnitude_sign.0)?;
         config.assign(
             layouter.namespace(|| format!("short fixed-base mul of {:?}", base)),
             magnitude_sign,
@@ -522,11 +1655,12 @@ impl<Fixed: super::FixedPoints<pallas::Affine>> EccInstructions<pallas::Affine>
 
     fn mul_fixed_base_field_elem(
         &self,
-        layouter: &mut impl Layouter<pallas::Base>,
-        base_field_elem: CellValue<pallas::Base>,
+        layouter: &mut impl Layouter<C::Base>,
+        base_field_elem: CellValue<C::Base>,
         base: &Self::FixedPoints,
     ) -> Result<Self::Point, Error> {
-        let config: mul_fixed::base_field_elem::Config<Fixed> = self.config().into();
+        base.validate()?;
+        let config: mul_fixed::base_field_elem::Config<C, Fixed> = self.config().into();
         config.assign(
             layouter.namespace(|| format!("base-field elem fixed-base mul of {:?}", base)),
             base_field_elem,
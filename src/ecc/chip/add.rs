@@ -1,6 +1,6 @@
 use std::array;
 
-use super::{copy, CellValue, EccConfig, EccPoint, Var};
+use super::{copy, CellValue, EccConfig, EccCurve, EccPoint, SelectorUsage, Var};
 use ff::Field;
 use halo2::{
     arithmetic::BatchInvert,
@@ -8,11 +8,11 @@ use halo2::{
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
-use pasta_curves::{arithmetic::FieldExt, pallas};
-use std::collections::HashSet;
+use pasta_curves::arithmetic::FieldExt;
+use std::{collections::HashSet, marker::PhantomData};
 
 #[derive(Clone, Debug)]
-pub struct Config {
+pub struct Config<C: EccCurve> {
     q_add: Selector,
     // lambda
     lambda: Column<Advice>,
@@ -32,10 +32,13 @@ pub struct Config {
     gamma: Column<Advice>,
     // δ = inv0(y_p + y_q) if x_q = x_p, 0 otherwise
     delta: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         Self {
             q_add: ecc_config.q_add,
             x_p: ecc_config.advices[0],
@@ -47,11 +50,13 @@ impl From<&EccConfig> for Config {
             beta: ecc_config.advices[6],
             gamma: ecc_config.advices[7],
             delta: ecc_config.advices[8],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
         }
     }
 }
 
-impl Config {
+impl<C: EccCurve> Config<C> {
     pub(crate) fn advice_columns(&self) -> HashSet<Column<Advice>> {
         core::array::IntoIter::new([
             self.x_p,
@@ -71,7 +76,7 @@ impl Config {
         core::array::IntoIter::new([self.x_qr, self.y_qr]).collect()
     }
 
-    pub(crate) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+    pub(crate) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         meta.create_gate("complete addition gates", |meta| {
             let q_add = meta.query_selector(self.q_add);
             let x_p = meta.query_advice(self.x_p, Rotation::cur());
@@ -102,9 +107,9 @@ impl Config {
             let if_delta = (y_q.clone() + y_p.clone()) * delta;
 
             // Useful constants
-            let one = Expression::Constant(pallas::Base::one());
-            let two = Expression::Constant(pallas::Base::from_u64(2));
-            let three = Expression::Constant(pallas::Base::from_u64(3));
+            let one = Expression::Constant(C::Base::one());
+            let two = Expression::Constant(C::Base::from_u64(2));
+            let three = Expression::Constant(C::Base::from_u64(3));
 
             // (x_q − x_p)⋅((x_q − x_p)⋅λ − (y_q−y_p)) = 0
             let poly1 = {
@@ -196,13 +201,29 @@ impl Config {
 
     pub(super) fn assign_region(
         &self,
-        p: &EccPoint,
-        q: &EccPoint,
+        p: &EccPoint<C>,
+        q: &EccPoint<C>,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<EccPoint, Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<EccPoint<C>, Error> {
+        self.assign_region_with_slope(p, q, offset, region)
+            .map(|(result, _)| result)
+    }
+
+    /// Assigns `p + q`, additionally returning the cell containing the slope λ.
+    ///
+    /// λ is constrained to be the slope of the secant through `p` and `q` if
+    /// their x-coordinates differ, and the slope of the tangent at `p` if they
+    /// are equal and `p` is not the identity.
+    pub(super) fn assign_region_with_slope(
+        &self,
+        p: &EccPoint<C>,
+        q: &EccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
         // Enable `q_add` selector
-        self.q_add.enable(region, offset)?;
+        self.usage.enable(&self.q_add, region, offset)?;
 
         // Copy point `p` into `x_p`, `y_p` columns
         copy(region, || "x_p", self.x_p, offset, &p.x)?;
@@ -278,7 +299,7 @@ impl Config {
                 if x_q == x_p {
                     delta.ok_or(Error::SynthesisError)
                 } else {
-                    Ok(pallas::Base::zero())
+                    Ok(C::Base::zero())
                 }
             },
         )?;
@@ -297,19 +318,19 @@ impl Config {
                         // know that x_q != x_p in this branch.
                         (y_q - y_p) * alpha
                     } else {
-                        if y_p != pallas::Base::zero() {
+                        if y_p != C::Base::zero() {
                             // 3(x_p)^2
-                            let three_x_p_sq = pallas::Base::from_u64(3) * x_p.square();
+                            let three_x_p_sq = C::Base::from_u64(3) * x_p.square();
                             // 1 / 2(y_p)
-                            let inv_two_y_p = y_p.invert().unwrap() * pallas::Base::TWO_INV;
+                            let inv_two_y_p = y_p.invert().unwrap() * C::Base::TWO_INV;
                             // λ = 3(x_p)^2 / 2(y_p)
                             three_x_p_sq * inv_two_y_p
                         } else {
-                            pallas::Base::zero()
+                            C::Base::zero()
                         }
                     }
                 });
-        region.assign_advice(
+        let lambda_cell = region.assign_advice(
             || "λ",
             self.lambda,
             offset,
@@ -324,15 +345,15 @@ impl Config {
                 .zip(lambda)
                 .map(|((((x_p, y_p), x_q), y_q), lambda)| {
                     {
-                        if x_p == pallas::Base::zero() {
+                        if x_p == C::Base::zero() {
                             // 0 + Q = Q
                             (x_q, y_q)
-                        } else if x_q == pallas::Base::zero() {
+                        } else if x_q == C::Base::zero() {
                             // P + 0 = P
                             (x_p, y_p)
                         } else if (x_q == x_p) && (y_q == -y_p) {
                             // P + (-P) maps to (0,0)
-                            (pallas::Base::zero(), pallas::Base::zero())
+                            (C::Base::zero(), C::Base::zero())
                         } else {
                             // x_r = λ^2 - x_p - x_q
                             let x_r = lambda.square() - x_p - x_q;
@@ -362,26 +383,22 @@ impl Config {
         )?;
 
         let result = EccPoint {
-            x: CellValue::<pallas::Base>::new(x_r_cell, x_r),
-            y: CellValue::<pallas::Base>::new(y_r_cell, y_r),
+            x: CellValue::<C::Base>::new(x_r_cell, x_r),
+            y: CellValue::<C::Base>::new(y_r_cell, y_r),
         };
 
-        #[cfg(test)]
+        #[cfg(any(test, feature = "debug-checks"))]
         // Check that the correct sum is obtained.
         {
-            use group::Curve;
-
             let p = p.point();
             let q = q.point();
             let real_sum = p.zip(q).map(|(p, q)| p + q);
             let result = result.point();
 
-            if let (Some(real_sum), Some(result)) = (real_sum, result) {
-                assert_eq!(real_sum.to_affine(), result);
-            }
+            super::debug_check_point(real_sum, result);
         }
 
-        Ok(result)
+        Ok((result, CellValue::new(lambda_cell, lambda)))
     }
 }
 
@@ -389,13 +406,25 @@ impl Config {
 pub mod tests {
     use group::{prime::PrimeCurveAffine, Curve, Group};
     use halo2::{circuit::Layouter, plonk::Error};
-    use pasta_curves::{arithmetic::CurveExt, pallas};
-
-    use crate::ecc::{chip::EccPoint, EccInstructions, NonIdentityPoint, Point};
+    use pasta_curves::{
+        arithmetic::{CurveAffine, CurveExt},
+        pallas,
+    };
+
+    use crate::{
+        ecc::{
+            chip::{EccChip, EccPoint},
+            EccInstructions, FixedPoints, NonIdentityPoint, Point,
+        },
+        utilities::Var,
+    };
 
     #[allow(clippy::too_many_arguments)]
     pub fn test_add<
-        EccChip: EccInstructions<pallas::Affine, Point = EccPoint> + Clone + Eq + std::fmt::Debug,
+        EccChip: EccInstructions<pallas::Affine, Point = EccPoint<pallas::Affine>>
+            + Clone
+            + Eq
+            + std::fmt::Debug,
     >(
         chip: EccChip,
         mut layouter: impl Layouter<pallas::Base>,
@@ -506,4 +535,41 @@ pub mod tests {
 
         Ok(())
     }
+
+    pub fn test_add_with_slope<F: FixedPoints<pallas::Affine>>(
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Generate distinct random points P and Q
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = Point::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+        let q_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let q = Point::new(chip.clone(), layouter.namespace(|| "Q"), Some(q_val))?;
+        assert_ne!(p_val, q_val);
+
+        let (result, lambda) =
+            chip.add_with_slope(layouter.namespace(|| "P + Q"), p.inner(), q.inner())?;
+        assert_eq!(result.point(), Some((p_val + q_val).to_affine()));
+
+        // (y_q - y_p) = λ⋅(x_q - x_p)
+        let p_coords = p_val.coordinates().unwrap();
+        let q_coords = q_val.coordinates().unwrap();
+        assert_eq!(
+            *q_coords.y() - *p_coords.y(),
+            lambda.value().unwrap() * (*q_coords.x() - *p_coords.x())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "witnessed result does not match the out-of-circuit computation")]
+    fn debug_check_rejects_wrong_sum() {
+        let p = pallas::Point::random(rand::rngs::OsRng);
+        let q = pallas::Point::random(rand::rngs::OsRng);
+
+        // Witness P + 2Q in place of P + Q, as a buggy assignment would.
+        let mis_witnessed = (p + q.double()).to_affine();
+        super::super::debug_check_point(Some(p + q), Some(mis_witnessed));
+    }
 }
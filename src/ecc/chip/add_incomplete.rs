@@ -1,17 +1,21 @@
-use std::{array, collections::HashSet};
+use std::{array, collections::HashSet, marker::PhantomData};
 
-use super::{copy, CellValue, EccConfig, NonIdentityEccPoint, Var};
+use super::{
+    copy, CellValue, EccConfig, EccCurve, EccPoint, NonIdentityEccPoint, SelectorUsage, Var,
+};
+use ff::Field;
 use group::Curve;
 use halo2::{
     circuit::Region,
-    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
     poly::Rotation,
 };
-use pasta_curves::{arithmetic::CurveAffine, pallas};
+use pasta_curves::arithmetic::CurveAffine;
 
 #[derive(Clone, Debug)]
-pub struct Config {
+pub struct Config<C: EccCurve> {
     q_add_incomplete: Selector,
+    q_add_incomplete_flag: Selector,
     // x-coordinate of P in P + Q = R
     pub x_p: Column<Advice>,
     // y-coordinate of P in P + Q = R
@@ -20,62 +24,236 @@ pub struct Config {
     pub x_qr: Column<Advice>,
     // y-coordinate of Q or R in P + Q = R
     pub y_qr: Column<Advice>,
+    // Inverse of x_q - x_p, or 0 if x_p = x_q
+    pub inv: Column<Advice>,
+    // Boolean flag that is 1 iff x_p = x_q
+    pub exceptional: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         Self {
             q_add_incomplete: ecc_config.q_add_incomplete,
+            q_add_incomplete_flag: ecc_config.q_add_incomplete_flag,
             x_p: ecc_config.advices[0],
             y_p: ecc_config.advices[1],
             x_qr: ecc_config.advices[2],
             y_qr: ecc_config.advices[3],
+            inv: ecc_config.advices[4],
+            exceptional: ecc_config.advices[5],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
         }
     }
 }
 
-impl Config {
+impl<C: EccCurve> Config<C> {
     pub(crate) fn advice_columns(&self) -> HashSet<Column<Advice>> {
         core::array::IntoIter::new([self.x_p, self.y_p, self.x_qr, self.y_qr]).collect()
     }
 
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+    /// Returns the incomplete addition constraints for `P + Q = R`, where `P`
+    /// and `Q` are on the current row and `R` is on the next row.
+    fn sum_polys(&self, meta: &mut VirtualCells<C::Base>) -> [Expression<C::Base>; 2] {
+        let x_p = meta.query_advice(self.x_p, Rotation::cur());
+        let y_p = meta.query_advice(self.y_p, Rotation::cur());
+        let x_q = meta.query_advice(self.x_qr, Rotation::cur());
+        let y_q = meta.query_advice(self.y_qr, Rotation::cur());
+        let x_r = meta.query_advice(self.x_qr, Rotation::next());
+        let y_r = meta.query_advice(self.y_qr, Rotation::next());
+
+        // (x_r + x_q + x_p)⋅(x_p − x_q)^2 − (y_p − y_q)^2 = 0
+        let poly1 = {
+            (x_r.clone() + x_q.clone() + x_p.clone())
+                * (x_p.clone() - x_q.clone())
+                * (x_p.clone() - x_q.clone())
+                - (y_p.clone() - y_q.clone()).square()
+        };
+
+        // (y_r + y_q)(x_p − x_q) − (y_p − y_q)(x_q − x_r) = 0
+        let poly2 = (y_r + y_q.clone()) * (x_p - x_q.clone()) - (y_p - y_q) * (x_q - x_r);
+
+        [poly1, poly2]
+    }
+
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         meta.create_gate("incomplete addition gates", |meta| {
             let q_add_incomplete = meta.query_selector(self.q_add_incomplete);
+            let [poly1, poly2] = self.sum_polys(meta);
+
+            array::IntoIter::new([("x_r", poly1), ("y_r", poly2)])
+                .map(move |(name, poly)| (name, q_add_incomplete.clone() * poly))
+        });
+    }
+
+    pub(super) fn create_flag_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("flagged incomplete addition", |meta| {
+            let q_add_incomplete_flag = meta.query_selector(self.q_add_incomplete_flag);
             let x_p = meta.query_advice(self.x_p, Rotation::cur());
-            let y_p = meta.query_advice(self.y_p, Rotation::cur());
             let x_q = meta.query_advice(self.x_qr, Rotation::cur());
-            let y_q = meta.query_advice(self.y_qr, Rotation::cur());
-            let x_r = meta.query_advice(self.x_qr, Rotation::next());
-            let y_r = meta.query_advice(self.y_qr, Rotation::next());
-
-            // (x_r + x_q + x_p)⋅(x_p − x_q)^2 − (y_p − y_q)^2 = 0
-            let poly1 = {
-                (x_r.clone() + x_q.clone() + x_p.clone())
-                    * (x_p.clone() - x_q.clone())
-                    * (x_p.clone() - x_q.clone())
-                    - (y_p.clone() - y_q.clone()).square()
-            };
+            let inv = meta.query_advice(self.inv, Rotation::cur());
+            let exceptional = meta.query_advice(self.exceptional, Rotation::cur());
+            let [poly1, poly2] = self.sum_polys(meta);
 
-            // (y_r + y_q)(x_p − x_q) − (y_p − y_q)(x_q − x_r) = 0
-            let poly2 = (y_r + y_q.clone()) * (x_p - x_q.clone()) - (y_p - y_q) * (x_q - x_r);
+            let one = Expression::Constant(C::Base::one());
+            let not_exceptional = one.clone() - exceptional.clone();
 
-            array::IntoIter::new([("x_r", poly1), ("y_r", poly2)])
-                .map(move |(name, poly)| (name, q_add_incomplete.clone() * poly))
+            array::IntoIter::new([
+                // exceptional = 1 - (x_q - x_p)⋅inv
+                (
+                    "exceptional = 1 - (x_q - x_p)⋅inv",
+                    exceptional.clone() - (one - (x_q.clone() - x_p.clone()) * inv),
+                ),
+                // (x_q - x_p)⋅exceptional = 0
+                ("(x_q - x_p)⋅exceptional = 0", (x_q - x_p) * exceptional),
+                // The sum is only constrained if the inputs are not exceptional.
+                ("x_r", not_exceptional.clone() * poly1),
+                ("y_r", not_exceptional * poly2),
+            ])
+            .map(move |(name, poly)| (name, q_add_incomplete_flag.clone() * poly))
         });
     }
 
     pub(super) fn assign_region(
         &self,
-        p: &NonIdentityEccPoint,
-        q: &NonIdentityEccPoint,
+        p: &NonIdentityEccPoint<C>,
+        q: &NonIdentityEccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        // Enable `q_add_incomplete` selector
+        self.usage.enable(&self.q_add_incomplete, region, offset)?;
+
+        // Handle exceptional cases
+        Self::check_exceptional_cases(p, q)?;
+
+        // Copy point `p` into `x_p`, `y_p` columns
+        copy(region, || "x_p", self.x_p, offset, &p.x)?;
+        copy(region, || "y_p", self.y_p, offset, &p.y)?;
+
+        // Copy point `q` into `x_qr`, `y_qr` columns
+        copy(region, || "x_q", self.x_qr, offset, &q.x)?;
+        copy(region, || "y_q", self.y_qr, offset, &q.y)?;
+
+        self.assign_sum(p, q, offset, region)
+    }
+
+    /// Adds `p` and `q`, returning `p + q` along with a boolean flag that is 1
+    /// iff `p` and `q` are an exceptional case of incomplete addition (that is,
+    /// `p = q` or `p = -q`).
+    ///
+    /// Instead of returning an error in exceptional cases, the flag is set and
+    /// the returned point is unconstrained; it is only meaningful when the flag
+    /// is 0.
+    pub(super) fn assign_region_flagged(
+        &self,
+        p: &NonIdentityEccPoint<C>,
+        q: &NonIdentityEccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<(NonIdentityEccPoint<C>, CellValue<C::Base>), Error> {
+        // Enable `q_add_incomplete_flag` selector
+        self.usage
+            .enable(&self.q_add_incomplete_flag, region, offset)?;
+
+        // Copy point `p` into `x_p`, `y_p` columns
+        copy(region, || "x_p", self.x_p, offset, &p.x)?;
+        copy(region, || "y_p", self.y_p, offset, &p.y)?;
+
+        // Copy point `q` into `x_qr`, `y_qr` columns
+        copy(region, || "x_q", self.x_qr, offset, &q.x)?;
+        copy(region, || "y_q", self.y_qr, offset, &q.y)?;
+
+        // Assign `inv`, which is 0 if x_p = x_q
+        let diff = p.x.value().zip(q.x.value()).map(|(x_p, x_q)| x_q - x_p);
+        let inv = diff.map(|diff| diff.invert().unwrap_or(C::Base::zero()));
+        region.assign_advice(
+            || "inv",
+            self.inv,
+            offset,
+            || inv.ok_or(Error::SynthesisError),
+        )?;
+
+        // Assign `exceptional` flag
+        let exceptional = diff.map(|diff| C::Base::from(diff == C::Base::zero()));
+        let exceptional_var = region.assign_advice(
+            || "exceptional",
+            self.exceptional,
+            offset,
+            || exceptional.ok_or(Error::SynthesisError),
+        )?;
+        let exceptional = CellValue::new(exceptional_var, exceptional);
+
+        let result = if exceptional.value() == Some(C::Base::one()) {
+            // The sum is unconstrained, so assign `p` as a placeholder.
+            let x_r = region.assign_advice(
+                || "x_r",
+                self.x_qr,
+                offset + 1,
+                || p.x.value().ok_or(Error::SynthesisError),
+            )?;
+            let y_r = region.assign_advice(
+                || "y_r",
+                self.y_qr,
+                offset + 1,
+                || p.y.value().ok_or(Error::SynthesisError),
+            )?;
+            NonIdentityEccPoint {
+                x: CellValue::new(x_r, p.x.value()),
+                y: CellValue::new(y_r, p.y.value()),
+            }
+        } else {
+            self.assign_sum(p, q, offset, region)?
+        };
+
+        Ok((result, exceptional))
+    }
+
+    /// Adds `p` and the fixed point `constant`, returning `p + constant`.
+    ///
+    /// The coordinates of `constant` are loaded from the circuit's constants
+    /// column, instead of being witnessed and checked to be on the curve.
+    pub(super) fn assign_region_constant(
+        &self,
+        p: &NonIdentityEccPoint<C>,
+        constant: C,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<NonIdentityEccPoint, Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        // The identity cannot be used in incomplete addition.
+        let coords = Option::<_>::from(constant.coordinates()).ok_or(Error::SynthesisError)?;
+
         // Enable `q_add_incomplete` selector
-        self.q_add_incomplete.enable(region, offset)?;
+        self.usage.enable(&self.q_add_incomplete, region, offset)?;
+
+        // Assign the constant point to `x_p`, `y_p` columns
+        let constant = {
+            let x = region.assign_advice_from_constant(|| "x_p", self.x_p, offset, *coords.x())?;
+            let y = region.assign_advice_from_constant(|| "y_p", self.y_p, offset, *coords.y())?;
+            NonIdentityEccPoint {
+                x: CellValue::new(x, Some(*coords.x())),
+                y: CellValue::new(y, Some(*coords.y())),
+            }
+        };
 
         // Handle exceptional cases
+        Self::check_exceptional_cases(&constant, p)?;
+
+        // Copy point `p` into `x_qr`, `y_qr` columns
+        copy(region, || "x_q", self.x_qr, offset, &p.x)?;
+        copy(region, || "y_q", self.y_qr, offset, &p.y)?;
+
+        self.assign_sum(&constant, p, offset, region)
+    }
+
+    /// Returns an error if `p + q` is an exceptional case of incomplete addition.
+    fn check_exceptional_cases(
+        p: &NonIdentityEccPoint<C>,
+        q: &NonIdentityEccPoint<C>,
+    ) -> Result<(), Error> {
         let (x_p, y_p) = (p.x.value(), p.y.value());
         let (x_q, y_q) = (q.x.value(), q.y.value());
         x_p.zip(y_p)
@@ -83,9 +261,9 @@ impl Config {
             .zip(y_q)
             .map(|(((x_p, y_p), x_q), y_q)| {
                 // P is point at infinity
-                if (x_p == pallas::Base::zero() && y_p == pallas::Base::zero())
+                if (x_p == C::Base::zero() && y_p == C::Base::zero())
                 // Q is point at infinity
-                || (x_q == pallas::Base::zero() && y_q == pallas::Base::zero())
+                || (x_q == C::Base::zero() && y_q == C::Base::zero())
                 // x_p = x_q
                 || (x_p == x_q)
                 {
@@ -95,15 +273,18 @@ impl Config {
                 }
             })
             .transpose()?;
+        Ok(())
+    }
 
-        // Copy point `p` into `x_p`, `y_p` columns
-        copy(region, || "x_p", self.x_p, offset, &p.x)?;
-        copy(region, || "y_p", self.y_p, offset, &p.y)?;
-
-        // Copy point `q` into `x_qr`, `y_qr` columns
-        copy(region, || "x_q", self.x_qr, offset, &q.x)?;
-        copy(region, || "y_q", self.y_qr, offset, &q.y)?;
-
+    /// Assigns `R = P + Q` on the row after `offset`, where `p` and `q` have
+    /// already been assigned on row `offset`.
+    fn assign_sum(
+        &self,
+        p: &NonIdentityEccPoint<C>,
+        q: &NonIdentityEccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
         // Compute the sum `P + Q = R`
         let r = {
             let p = p.point();
@@ -135,21 +316,44 @@ impl Config {
         )?;
 
         let result = NonIdentityEccPoint {
-            x: CellValue::<pallas::Base>::new(x_r_var, x_r),
-            y: CellValue::<pallas::Base>::new(y_r_var, y_r),
+            x: CellValue::<C::Base>::new(x_r_var, x_r),
+            y: CellValue::<C::Base>::new(y_r_var, y_r),
         };
 
         Ok(result)
     }
+
+    /// Assigns `𝒪 + q = q`, constraining `identity` to be `(0, 0)` and
+    /// copying `q` to the output.
+    pub(super) fn assign_region_identity(
+        &self,
+        identity: &EccPoint<C>,
+        q: &EccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<EccPoint<C>, Error> {
+        // Constrain the identity to be (0, 0)
+        region.constrain_constant(identity.x.cell(), C::Base::zero())?;
+        region.constrain_constant(identity.y.cell(), C::Base::zero())?;
+
+        // Copy `q` into `x_p`, `y_p` columns
+        let x = copy(region, || "x", self.x_p, offset, &q.x)?;
+        let y = copy(region, || "y", self.y_p, offset, &q.y)?;
+
+        Ok(EccPoint { x, y })
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use group::{Curve, Group};
+    use group::{prime::PrimeCurveAffine, Curve, Group};
     use halo2::{circuit::Layouter, plonk::Error};
     use pasta_curves::pallas;
 
-    use crate::ecc::{EccInstructions, NonIdentityPoint};
+    use crate::{
+        ecc::{EccInstructions, NonIdentityPoint, Point},
+        utilities::{CellValue, Var},
+    };
 
     #[allow(clippy::too_many_arguments)]
     pub fn test_add_incomplete<
@@ -192,4 +396,145 @@ pub mod tests {
 
         Ok(())
     }
+
+    pub fn test_try_add_incomplete<
+        EccChip: EccInstructions<pallas::Affine, Var = CellValue<pallas::Base>>
+            + Clone
+            + Eq
+            + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Generate random points P and Q
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+        let p_neg = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "-P"), Some(-p_val))?;
+        let q_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let q = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "Q"), Some(q_val))?;
+        assert_ne!(p_val, q_val);
+
+        fn constrain_flag(
+            mut layouter: impl Layouter<pallas::Base>,
+            flag: CellValue<pallas::Base>,
+            expected: pallas::Base,
+        ) -> Result<(), Error> {
+            assert_eq!(flag.value(), Some(expected));
+            layouter.assign_region(
+                || "constrain flag",
+                |mut region| region.constrain_constant(flag.cell(), expected),
+            )
+        }
+
+        // P + Q is not exceptional
+        {
+            let (result, flag) = p.try_add_incomplete(layouter.namespace(|| "P + Q"), &q)?;
+            constrain_flag(layouter.namespace(|| "P + Q"), flag, pallas::Base::zero())?;
+            let witnessed_result = NonIdentityPoint::new(
+                chip,
+                layouter.namespace(|| "witnessed P + Q"),
+                Some((p_val + q_val).to_affine()),
+            )?;
+            result.constrain_equal(layouter.namespace(|| "constrain P + Q"), &witnessed_result)?;
+        }
+
+        // P + P is exceptional
+        {
+            let (_, flag) = p.try_add_incomplete(layouter.namespace(|| "P + P"), &p)?;
+            constrain_flag(layouter.namespace(|| "P + P"), flag, pallas::Base::one())?;
+        }
+
+        // P + (-P) is exceptional
+        {
+            let (_, flag) = p.try_add_incomplete(layouter.namespace(|| "P + (-P)"), &p_neg)?;
+            constrain_flag(layouter.namespace(|| "P + (-P)"), flag, pallas::Base::one())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_add_incomplete_constant<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // A known constant point C = [2] G
+        let c_val = pallas::Point::generator().double().to_affine();
+
+        // Generate a random point P
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+
+        // P + C
+        {
+            let result = p.add_incomplete_constant(layouter.namespace(|| "P + C"), c_val)?;
+            let witnessed_result = NonIdentityPoint::new(
+                chip.clone(),
+                layouter.namespace(|| "witnessed P + C"),
+                Some((p_val + c_val).to_affine()),
+            )?;
+            result.constrain_equal(layouter.namespace(|| "constrain P + C"), &witnessed_result)?;
+        }
+
+        // C + C should return an error
+        let c = NonIdentityPoint::new(chip, layouter.namespace(|| "C"), Some(c_val))?;
+        c.add_incomplete_constant(layouter.namespace(|| "C + C"), c_val)
+            .expect_err("C + C should return an error");
+
+        // Adding the identity as a constant should return an error
+        p.add_incomplete_constant(layouter.namespace(|| "P + 𝒪"), pallas::Affine::identity())
+            .expect_err("P + 𝒪 should return an error");
+
+        Ok(())
+    }
+
+    pub fn test_add_incomplete_or_copy<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Accumulate four random points, starting from the identity
+        let mut acc = Point::new(
+            chip.clone(),
+            layouter.namespace(|| "identity"),
+            Some(pallas::Affine::identity()),
+        )?;
+        let mut acc_val = pallas::Point::identity();
+        for i in 0..4 {
+            let term_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let term = Point::new(
+                chip.clone(),
+                layouter.namespace(|| format!("term {}", i)),
+                Some(term_val),
+            )?;
+            acc = acc.add_incomplete_or_copy(
+                layouter.namespace(|| format!("acc + term {}", i)),
+                &term,
+                i == 0,
+            )?;
+            acc_val += term_val;
+        }
+
+        let witnessed_result = Point::new(
+            chip.clone(),
+            layouter.namespace(|| "witnessed sum"),
+            Some(acc_val.to_affine()),
+        )?;
+        acc.constrain_equal(layouter.namespace(|| "constrain sum"), &witnessed_result)?;
+
+        // Adding the identity when it is not declared as such must not copy the
+        // other operand, since the identity is not a valid incomplete addition input.
+        let identity = Point::new(
+            chip,
+            layouter.namespace(|| "identity"),
+            Some(pallas::Affine::identity()),
+        )?;
+        assert!(identity
+            .add_incomplete_or_copy(layouter.namespace(|| "undeclared 𝒪 + sum"), &acc, false)
+            .is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,170 @@
+use std::{array, marker::PhantomData};
+
+use super::{copy, CellValue, EccConfig, EccCurve, NonIdentityEccPoint, SelectorUsage, Var};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+use halo2::{
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::{CurveAffine, FieldExt};
+
+#[derive(Clone, Debug)]
+pub struct Config<C: EccCurve> {
+    q_double: Selector,
+    // x-coordinate of P in [2]P = R
+    pub x_p: Column<Advice>,
+    // y-coordinate of P in [2]P = R
+    pub y_p: Column<Advice>,
+    // x-coordinate of R in [2]P = R
+    pub x_r: Column<Advice>,
+    // y-coordinate of R in [2]P = R
+    pub y_r: Column<Advice>,
+    // λ = 3x_p^2 / 2y_p
+    pub lambda: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
+}
+
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
+        Self {
+            q_double: ecc_config.q_double,
+            x_p: ecc_config.advices[0],
+            y_p: ecc_config.advices[1],
+            x_r: ecc_config.advices[2],
+            y_r: ecc_config.advices[3],
+            lambda: ecc_config.advices[4],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("point doubling", |meta| {
+            let q_double = meta.query_selector(self.q_double);
+            let x_p = meta.query_advice(self.x_p, Rotation::cur());
+            let y_p = meta.query_advice(self.y_p, Rotation::cur());
+            let x_r = meta.query_advice(self.x_r, Rotation::cur());
+            let y_r = meta.query_advice(self.y_r, Rotation::cur());
+            let lambda = meta.query_advice(self.lambda, Rotation::cur());
+
+            let two = Expression::Constant(C::Base::from_u64(2));
+            let three = Expression::Constant(C::Base::from_u64(3));
+
+            // 2⋅y_p⋅λ − 3⋅x_p^2 = 0
+            let poly1 =
+                two.clone() * y_p.clone() * lambda.clone() - three * x_p.clone() * x_p.clone();
+
+            // λ^2 − 2⋅x_p − x_r = 0
+            let poly2 = lambda.clone() * lambda.clone() - two * x_p.clone() - x_r.clone();
+
+            // λ⋅(x_p − x_r) − y_p − y_r = 0
+            let poly3 = lambda * (x_p - x_r) - y_p - y_r;
+
+            array::IntoIter::new([("lambda", poly1), ("x_r", poly2), ("y_r", poly3)])
+                .map(move |(name, poly)| (name, q_double.clone() * poly))
+        });
+    }
+
+    pub(super) fn assign_region(
+        &self,
+        p: &NonIdentityEccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        // Enable `q_double` selector
+        self.usage.enable(&self.q_double, region, offset)?;
+
+        // Handle exceptional case: the tangent at a point with y = 0 is vertical,
+        // and doubling it would give the identity.
+        p.y.value()
+            .map(|y_p| {
+                if y_p == C::Base::zero() {
+                    Err(Error::SynthesisError)
+                } else {
+                    Ok(())
+                }
+            })
+            .transpose()?;
+
+        // Copy point `p` into `x_p`, `y_p` columns
+        copy(region, || "x_p", self.x_p, offset, &p.x)?;
+        copy(region, || "y_p", self.y_p, offset, &p.y)?;
+
+        // Assign λ = 3x_p^2 / 2y_p
+        let lambda = p.x.value().zip(p.y.value()).map(|(x_p, y_p)| {
+            C::Base::from_u64(3) * x_p.square() * (C::Base::from_u64(2) * y_p).invert().unwrap()
+        });
+        region.assign_advice(
+            || "lambda",
+            self.lambda,
+            offset,
+            || lambda.ok_or(Error::SynthesisError),
+        )?;
+
+        // Compute `[2]P = R`
+        let r = p
+            .point()
+            .map(|p| p.to_curve().double().to_affine().coordinates().unwrap());
+        let x_r = r.map(|r| *r.x());
+        let y_r = r.map(|r| *r.y());
+
+        let x_r_var = region.assign_advice(
+            || "x_r",
+            self.x_r,
+            offset,
+            || x_r.ok_or(Error::SynthesisError),
+        )?;
+        let y_r_var = region.assign_advice(
+            || "y_r",
+            self.y_r,
+            offset,
+            || y_r.ok_or(Error::SynthesisError),
+        )?;
+
+        Ok(NonIdentityEccPoint {
+            x: CellValue::<C::Base>::new(x_r_var, x_r),
+            y: CellValue::<C::Base>::new(y_r_var, y_r),
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use group::{prime::PrimeCurveAffine, Curve, Group};
+    use halo2::{circuit::Layouter, plonk::Error};
+    use pasta_curves::pallas;
+
+    use crate::ecc::{EccInstructions, NonIdentityPoint};
+
+    pub fn test_double<EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug>(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Generate a random point P
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine(); // P
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+
+        // [2]P
+        {
+            let result = p.double(layouter.namespace(|| "[2]P"))?;
+            let witnessed_result = NonIdentityPoint::new(
+                chip,
+                layouter.namespace(|| "witnessed [2]P"),
+                Some(p_val.to_curve().double().to_affine()),
+            )?;
+            result.constrain_equal(layouter.namespace(|| "constrain [2]P"), &witnessed_result)?;
+
+            // [2]P should match P + P
+            let p_plus_p = p.add(layouter.namespace(|| "P + P"), &p)?;
+            result.constrain_equal(layouter.namespace(|| "constrain [2]P = P + P"), &p_plus_p)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use std::marker::PhantomData;
+
+use super::{copy, CellValue, EccConfig, EccCurve, EccPoint, SelectorUsage, Var};
+use ff::Field;
+use halo2::{
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct Config<C: EccCurve> {
+    q_point_equals: Selector,
+    // x-coordinate of A
+    pub x_a: Column<Advice>,
+    // y-coordinate of A
+    pub y_a: Column<Advice>,
+    // x-coordinate of B
+    pub x_b: Column<Advice>,
+    // y-coordinate of B
+    pub y_b: Column<Advice>,
+    // Inverse of x_a - x_b, or 0 if x_a = x_b
+    pub inv_x: Column<Advice>,
+    // Inverse of y_a - y_b, or 0 if y_a = y_b
+    pub inv_y: Column<Advice>,
+    // Boolean flag that is 1 iff A = B
+    pub equal: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
+}
+
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
+        Self {
+            q_point_equals: ecc_config.q_point_equals,
+            x_a: ecc_config.advices[0],
+            y_a: ecc_config.advices[1],
+            x_b: ecc_config.advices[2],
+            y_b: ecc_config.advices[3],
+            inv_x: ecc_config.advices[4],
+            inv_y: ecc_config.advices[5],
+            equal: ecc_config.advices[6],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("point equality", |meta| {
+            let q_point_equals = meta.query_selector(self.q_point_equals);
+            let x_a = meta.query_advice(self.x_a, Rotation::cur());
+            let y_a = meta.query_advice(self.y_a, Rotation::cur());
+            let x_b = meta.query_advice(self.x_b, Rotation::cur());
+            let y_b = meta.query_advice(self.y_b, Rotation::cur());
+            let inv_x = meta.query_advice(self.inv_x, Rotation::cur());
+            let inv_y = meta.query_advice(self.inv_y, Rotation::cur());
+            let equal = meta.query_advice(self.equal, Rotation::cur());
+
+            let one = Expression::Constant(C::Base::one());
+
+            let x_diff = x_a - x_b;
+            let y_diff = y_a - y_b;
+
+            // x_is_zero = 1 - (x_a - x_b)⋅inv_x
+            let x_is_zero = one.clone() - x_diff.clone() * inv_x;
+            // y_is_zero = 1 - (y_a - y_b)⋅inv_y
+            let y_is_zero = one - y_diff.clone() * inv_y;
+
+            std::array::IntoIter::new([
+                // (x_a - x_b)⋅x_is_zero = 0
+                ("x_is_zero", x_diff * x_is_zero.clone()),
+                // (y_a - y_b)⋅y_is_zero = 0
+                ("y_is_zero", y_diff * y_is_zero.clone()),
+                // equal = x_is_zero ∧ y_is_zero
+                ("equal", equal - x_is_zero * y_is_zero),
+            ])
+            .map(move |(name, poly)| (name, q_point_equals.clone() * poly))
+        });
+    }
+
+    pub(super) fn assign_region(
+        &self,
+        a: &EccPoint<C>,
+        b: &EccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<CellValue<C::Base>, Error> {
+        // Enable `q_point_equals` selector
+        self.usage.enable(&self.q_point_equals, region, offset)?;
+
+        // Copy points `a` and `b`
+        copy(region, || "x_a", self.x_a, offset, &a.x)?;
+        copy(region, || "y_a", self.y_a, offset, &a.y)?;
+        copy(region, || "x_b", self.x_b, offset, &b.x)?;
+        copy(region, || "y_b", self.y_b, offset, &b.y)?;
+
+        // Assign the inverses of the coordinate differences, or 0 if a
+        // difference is 0.
+        let inv = |a: CellValue<C::Base>, b: CellValue<C::Base>| {
+            a.value()
+                .zip(b.value())
+                .map(|(a, b)| (a - b).invert().unwrap_or(C::Base::zero()))
+        };
+        let inv_x = inv(a.x, b.x);
+        region.assign_advice(
+            || "inv_x",
+            self.inv_x,
+            offset,
+            || inv_x.ok_or(Error::SynthesisError),
+        )?;
+        let inv_y = inv(a.y, b.y);
+        region.assign_advice(
+            || "inv_y",
+            self.inv_y,
+            offset,
+            || inv_y.ok_or(Error::SynthesisError),
+        )?;
+
+        // Assign the equality flag
+        let equal = a.point().zip(b.point()).map(|(a, b)| C::Base::from(a == b));
+        let equal_var = region.assign_advice(
+            || "equal",
+            self.equal,
+            offset,
+            || equal.ok_or(Error::SynthesisError),
+        )?;
+
+        Ok(CellValue::new(equal_var, equal))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use group::{prime::PrimeCurveAffine, Curve, Group};
+    use halo2::{circuit::Layouter, plonk::Error};
+    use pasta_curves::pallas;
+
+    use crate::{
+        ecc::{EccInstructions, Point},
+        utilities::Var,
+    };
+
+    pub fn test_points_equal<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Generate random points P and Q
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let q_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+
+        let mut check = |name: &str,
+                         a: pallas::Affine,
+                         b: pallas::Affine,
+                         expected: bool|
+         -> Result<(), Error> {
+            let a = Point::new(chip.clone(), layouter.namespace(|| "a"), Some(a))?;
+            let b = Point::new(chip.clone(), layouter.namespace(|| "b"), Some(b))?;
+            let equal = a.equals(layouter.namespace(|| name.to_string()), &b)?;
+            assert_eq!(equal.value(), Some(pallas::Base::from(expected)));
+            Ok(())
+        };
+
+        // P == P
+        check("P == P", p_val, p_val, true)?;
+        // P != Q
+        check("P != Q", p_val, q_val, false)?;
+        // P != -P, which has the same x-coordinate
+        check("P != -P", p_val, -p_val, false)?;
+        // 𝒪 == 𝒪
+        let identity = pallas::Affine::identity();
+        check("𝒪 == 𝒪", identity, identity, true)?;
+        // P != 𝒪
+        check("P != 𝒪", p_val, identity, false)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
-use super::{add, CellValue, EccConfig, EccPoint, NonIdentityEccPoint, Var, T_Q};
+use super::{
+    add, witness_point, CellValue, EccConfig, EccCurve, EccPoint, NonIdentityEccPoint,
+    SelectorUsage, Var, L_PALLAS_SCALAR,
+};
 use crate::utilities::copy;
 use std::ops::{Deref, Range};
 
 use bigint::U256;
-use ff::PrimeField;
+use ff::Field;
 use halo2::{
     arithmetic::FieldExt,
     circuit::{Layouter, Region},
@@ -11,50 +14,80 @@ use halo2::{
     poly::Rotation,
 };
 
-use pasta_curves::pallas;
-
 mod complete;
 mod incomplete;
 mod overflow;
 
 /// Number of bits for which complete addition needs to be used in variable-base
 /// scalar multiplication
-const NUM_COMPLETE_BITS: usize = 3;
+pub(super) const NUM_COMPLETE_BITS: usize = 3;
 
 // Bits used in incomplete addition. k_{254} to k_{4} inclusive
-const INCOMPLETE_LEN: usize = pallas::Scalar::NUM_BITS as usize - 1 - NUM_COMPLETE_BITS;
+const INCOMPLETE_LEN: usize = L_PALLAS_SCALAR - 1 - NUM_COMPLETE_BITS;
 
 // Bits k_{254} to k_{4} inclusive are used in incomplete addition.
 // The `hi` half is k_{254} to k_{130} inclusive (length 125 bits).
 // (It is a coincidence that k_{130} matches the boundary of the
 // overflow check described in [the book](https://zcash.github.io/halo2/design/gadgets/ecc/var-base-scalar-mul.html#overflow-check).)
-const INCOMPLETE_HI_RANGE: Range<usize> = 0..(INCOMPLETE_LEN / 2);
+pub(super) const INCOMPLETE_HI_RANGE: Range<usize> = 0..(INCOMPLETE_LEN / 2);
 
 // Bits k_{254} to k_{4} inclusive are used in incomplete addition.
 // The `lo` half is k_{129} to k_{4} inclusive (length 126 bits).
-const INCOMPLETE_LO_RANGE: Range<usize> = (INCOMPLETE_LEN / 2)..INCOMPLETE_LEN;
+pub(super) const INCOMPLETE_LO_RANGE: Range<usize> = (INCOMPLETE_LEN / 2)..INCOMPLETE_LEN;
 
 // Bits k_{3} to k_{1} inclusive are used in complete addition.
 // Bit k_{0} is handled separately.
 const COMPLETE_RANGE: Range<usize> = INCOMPLETE_LEN..(INCOMPLETE_LEN + NUM_COMPLETE_BITS);
 
-pub struct Config {
+/// Addition strategy for the double-and-add steps of variable-base scalar
+/// multiplication, which process bits `k_{254}` to `k_1` of the decomposed
+/// scalar. The LSB `k_0` is always handled with complete addition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddPolicy {
+    /// Complete addition for every bit. This has no exceptional cases, but
+    /// uses two rows per bit instead of one.
+    AllComplete,
+    /// Incomplete addition for bits `k_{254}` to `k_4`, and complete addition
+    /// for `k_3` to `k_1`. This is what [`EccInstructions::mul`] uses.
+    ///
+    /// [`EccInstructions::mul`]: crate::ecc::EccInstructions::mul
+    Hybrid,
+    /// Incomplete addition for every bit.
+    ///
+    /// # Soundness
+    ///
+    /// This policy is **not sound** in general. The incomplete addition gates
+    /// do not constrain the accumulator and the base to have distinct
+    /// x-coordinates, and the argument that they never collide only covers
+    /// bits `k_{254}` to `k_4`. For some scalars the accumulator reaches
+    /// `±base` in the last few steps, at which point an honest prover fails to
+    /// produce a witness and a malicious prover can satisfy the constraints
+    /// with an incorrect result. Only use it where such scalars are excluded
+    /// by other means.
+    AllIncomplete,
+}
+
+pub struct Config<C: EccCurve> {
     // Selector used to check switching logic on LSB
     q_mul_lsb: Selector,
     // Configuration used in complete addition
-    add_config: add::Config,
+    add_config: add::Config<C>,
     // Configuration used for `hi` bits of the scalar
-    hi_config: incomplete::HiConfig,
+    hi_config: incomplete::HiConfig<C>,
     // Configuration used for `lo` bits of the scalar
-    lo_config: incomplete::LoConfig,
+    lo_config: incomplete::LoConfig<C>,
     // Configuration used for complete addition part of double-and-add algorithm
-    complete_config: complete::Config,
+    complete_config: complete::Config<C>,
     // Configuration used to check for overflow
-    overflow_config: overflow::Config,
+    overflow_config: overflow::Config<C>,
+    // Configuration used to constrain a sum to be a non-identity base
+    witness_point_config: witness_point::Config<C>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         let config = Self {
             q_mul_lsb: ecc_config.q_mul_lsb,
             add_config: ecc_config.into(),
@@ -62,6 +95,8 @@ impl From<&EccConfig> for Config {
             lo_config: ecc_config.into(),
             complete_config: ecc_config.into(),
             overflow_config: ecc_config.into(),
+            witness_point_config: ecc_config.into(),
+            usage: ecc_config.usage.clone(),
         };
 
         assert_eq!(
@@ -92,8 +127,8 @@ impl From<&EccConfig> for Config {
     }
 }
 
-impl Config {
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         // If `lsb` is 0, (x, y) = (x_p, -y_p). If `lsb` is 1, (x, y) = (0,0).
         meta.create_gate("LSB check", |meta| {
             let q_mul_lsb = meta.query_selector(self.q_mul_lsb);
@@ -107,8 +142,8 @@ impl Config {
 
             //    z_0 = 2 * z_1 + k_0
             // => k_0 = z_0 - 2 * z_1
-            let lsb = z_0 - z_1 * pallas::Base::from_u64(2);
-            let one_minus_lsb = Expression::Constant(pallas::Base::one()) - lsb.clone();
+            let lsb = z_0 - z_1 * C::Base::from_u64(2);
+            let one_minus_lsb = Expression::Constant(C::Base::one()) - lsb.clone();
 
             let bool_check = lsb.clone() * one_minus_lsb.clone();
 
@@ -133,130 +168,67 @@ impl Config {
 
     pub(super) fn assign(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        alpha: CellValue<pallas::Base>,
-        base: &NonIdentityEccPoint,
-    ) -> Result<(EccPoint, CellValue<pallas::Base>), Error> {
-        let (result, zs): (EccPoint, Vec<Z<pallas::Base>>) = layouter.assign_region(
-            || "variable-base scalar mul",
-            |mut region| {
-                let offset = 0;
-
-                // Case `base` into an `EccPoint` for later use.
-                let base_point: EccPoint = (*base).into();
-
-                // Decompose `k = alpha + t_q` bitwise (big-endian bit order).
-                let bits = decompose_for_scalar_mul(alpha.value());
-
-                // Define ranges for each part of the algorithm.
-                let bits_incomplete_hi = &bits[INCOMPLETE_HI_RANGE];
-                let bits_incomplete_lo = &bits[INCOMPLETE_LO_RANGE];
-                let lsb = bits[pallas::Scalar::NUM_BITS as usize - 1];
+        layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+        base: &NonIdentityEccPoint<C>,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        self.assign_with_policy(layouter, alpha, base, AddPolicy::Hybrid)
+    }
 
-                // Initialize the accumulator `acc = [2]base`
-                let acc =
-                    self.add_config
-                        .assign_region(&base_point, &base_point, offset, &mut region)?;
+    /// Variable-base scalar multiplication, using `policy` to decide which of
+    /// the bits `k_{254}` to `k_1` are processed with incomplete addition.
+    pub(super) fn assign_with_policy(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+        base: &NonIdentityEccPoint<C>,
+        policy: AddPolicy,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        let (result, zs) = self.usage.assign_region(
+            &mut layouter,
+            || "variable-base scalar mul",
+            |mut region| self.assign_region(&mut region, 0, alpha, base, policy),
+        )?;
 
-                // Increase the offset by 1 after complete addition.
-                let offset = offset + 1;
+        self.overflow_config
+            .overflow_check(layouter.namespace(|| "overflow check"), alpha, &zs)?;
 
-                // Initialize the running sum for scalar decomposition to zero
-                let z_init = {
-                    let z_init_cell = region.assign_advice_from_constant(
-                        || "z_init = 0",
-                        self.hi_config.z,
-                        offset,
-                        pallas::Base::zero(),
-                    )?;
+        Ok((result, alpha))
+    }
 
-                    Z(CellValue::new(z_init_cell, Some(pallas::Base::zero())))
-                };
+    /// Variable-base scalar multiplication of `a + b`, computing the complete
+    /// addition in the same region as the multiplication.
+    ///
+    /// The sum is constrained to not be the identity, and returns an error if
+    /// it is known to be, as [`Config::assign`] requires a non-identity base.
+    pub(super) fn add_then_assign(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+        a: &NonIdentityEccPoint<C>,
+        b: &NonIdentityEccPoint<C>,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        let (result, zs) = self.usage.assign_region(
+            &mut layouter,
+            || "add then variable-base scalar mul",
+            |mut region| {
+                let offset = 0;
 
-                // Double-and-add (incomplete addition) for the `hi` half of the scalar decomposition
-                let (x_a, y_a, zs_incomplete_hi) = self.hi_config.double_and_add(
-                    &mut region,
+                // `a + b` is output on the row after the complete addition.
+                let sum = self.add_config.assign_region(
+                    &(*a).into(),
+                    &(*b).into(),
                     offset,
-                    base,
-                    bits_incomplete_hi,
-                    (X(acc.x), Y(acc.y), z_init),
-                )?;
-
-                // Double-and-add (incomplete addition) for the `lo` half of the scalar decomposition
-                let z = zs_incomplete_hi.last().expect("should not be empty");
-                let (x_a, y_a, zs_incomplete_lo) = self.lo_config.double_and_add(
                     &mut region,
-                    offset,
-                    base,
-                    bits_incomplete_lo,
-                    (x_a, y_a, *z),
                 )?;
 
-                // Move from incomplete addition to complete addition.
-                // Inside incomplete::double_and_add, the offset was increased once after initialization
-                // of the running sum.
-                // Then, the final assignment of double-and-add was made on row + offset + 1.
-                // Outside of incomplete addition, we must account for these offset increases by adding
-                // 2 to the incomplete addition length.
-                let offset = offset + INCOMPLETE_LO_RANGE.len() + 2;
-
-                // Complete addition
-                let (acc, zs_complete) = {
-                    let z = zs_incomplete_lo.last().expect("should not be empty");
-                    // Bits used in complete addition. k_{3} to k_{1} inclusive
-                    // The LSB k_{0} is handled separately.
-                    let bits_complete = &bits[COMPLETE_RANGE];
-                    self.complete_config.assign_region(
-                        &mut region,
-                        offset,
-                        bits_complete,
-                        &base_point,
-                        x_a,
-                        y_a,
-                        *z,
-                    )?
-                };
-
-                // Each iteration of the complete addition uses two rows.
-                let offset = offset + COMPLETE_RANGE.len() * 2;
-
-                // Process the least significant bit
-                let z_1 = zs_complete.last().unwrap();
-                let (result, z_0) = self.process_lsb(&mut region, offset, base, acc, *z_1, lsb)?;
-
-                #[cfg(test)]
-                // Check that the correct multiple is obtained.
-                {
-                    use group::Curve;
-
-                    let base = base.point();
-                    let alpha = alpha
-                        .value()
-                        .map(|alpha| pallas::Scalar::from_bytes(&alpha.to_bytes()).unwrap());
-                    let real_mul = base.zip(alpha).map(|(base, alpha)| base * alpha);
-                    let result = result.point();
-
-                    if let (Some(real_mul), Some(result)) = (real_mul, result) {
-                        assert_eq!(real_mul.to_affine(), result);
-                    }
-                }
+                // Copy the sum onto that row, constraining it to be non-identity.
+                let offset = offset + 1;
+                let base = self
+                    .witness_point_config
+                    .copy_non_id(&sum, offset, &mut region)?;
 
-                let zs = {
-                    let mut zs = std::iter::empty()
-                        .chain(Some(z_init))
-                        .chain(zs_incomplete_hi.into_iter())
-                        .chain(zs_incomplete_lo.into_iter())
-                        .chain(zs_complete.into_iter())
-                        .chain(Some(z_0))
-                        .collect::<Vec<_>>();
-                    assert_eq!(zs.len(), pallas::Scalar::NUM_BITS as usize + 1);
-
-                    // This reverses zs to give us [z_0, z_1, ..., z_{254}, z_{255}].
-                    zs.reverse();
-                    zs
-                };
-
-                Ok((result, zs))
+                self.assign_region(&mut region, offset + 1, alpha, &base, AddPolicy::Hybrid)
             },
         )?;
 
@@ -266,6 +238,256 @@ impl Config {
         Ok((result, alpha))
     }
 
+    /// Assigns `[alpha] base` in `region`, starting at `offset`, and returns it
+    /// along with the running sum `z`s of the scalar decomposition, which are
+    /// used by the overflow check.
+    #[allow(clippy::type_complexity)]
+    fn assign_region(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        alpha: CellValue<C::Base>,
+        base: &NonIdentityEccPoint<C>,
+        policy: AddPolicy,
+    ) -> Result<(EccPoint<C>, Vec<Z<C::Base>>), Error> {
+        // Case `base` into an `EccPoint` for later use.
+        let base_point: EccPoint<C> = (*base).into();
+
+        // Decompose `k = alpha + t_q` bitwise (big-endian bit order).
+        let bits = decompose_for_scalar_mul::<C>(alpha.value());
+        let lsb = bits[C::L_SCALAR - 1];
+
+        // Initialize the accumulator `acc = [2]base`
+        let acc = self
+            .add_config
+            .assign_region(&base_point, &base_point, offset, region)?;
+
+        // Increase the offset by 1 after complete addition.
+        let offset = offset + 1;
+
+        // Initialize the running sum for scalar decomposition to zero
+        let z_init = {
+            let z_init_cell = region.assign_advice_from_constant(
+                || "z_init = 0",
+                self.hi_config.z,
+                offset,
+                C::Base::zero(),
+            )?;
+
+            Z(CellValue::new(z_init_cell, Some(C::Base::zero())))
+        };
+
+        // Double-and-add over bits k_{254} to k_{1} inclusive. In each case `z_1`
+        // ends up assigned in the `z_complete` column at the returned offset, where
+        // the LSB is processed.
+        let bits = &bits[..(C::L_SCALAR - 1)];
+        let (acc, zs_double_and_add, offset) = match policy {
+            AddPolicy::AllComplete => {
+                self.double_and_add_complete(region, offset, &base_point, bits, acc, z_init)?
+            }
+            AddPolicy::Hybrid => {
+                self.double_and_add_hybrid(region, offset, base, bits, acc, z_init)?
+            }
+            AddPolicy::AllIncomplete => {
+                self.double_and_add_incomplete(region, offset, base, bits, acc, z_init)?
+            }
+        };
+
+        // Process the least significant bit
+        let z_1 = zs_double_and_add.last().unwrap();
+        let (result, z_0) = self.process_lsb(region, offset, base, acc, *z_1, lsb)?;
+
+        #[cfg(any(test, feature = "debug-checks"))]
+        // Check that the correct multiple is obtained.
+        {
+            let base = base.point();
+            // Reduce `alpha` into the scalar field, which may be smaller than the
+            // base field.
+            let alpha = alpha.value().map(|alpha| {
+                let mut bytes = [0u8; 64];
+                bytes[..32].copy_from_slice(&alpha.to_bytes());
+                C::Scalar::from_bytes_wide(&bytes)
+            });
+            let real_mul = base.zip(alpha).map(|(base, alpha)| base * alpha);
+            let result = result.point();
+
+            super::debug_check_point(real_mul, result);
+        }
+
+        let zs = {
+            let mut zs = std::iter::empty()
+                .chain(Some(z_init))
+                .chain(zs_double_and_add.into_iter())
+                .chain(Some(z_0))
+                .collect::<Vec<_>>();
+            assert_eq!(zs.len(), C::L_SCALAR + 1);
+
+            // This reverses zs to give us [z_0, z_1, ..., z_{254}, z_{255}].
+            zs.reverse();
+            zs
+        };
+
+        Ok((result, zs))
+    }
+
+    /// Double-and-add under [`AddPolicy::Hybrid`]: incomplete addition for bits
+    /// `k_{254}` to `k_4`, and complete addition for `k_3` to `k_1`.
+    ///
+    /// `acc` is the initial accumulator, assigned in the row before `offset`, and
+    /// `z_init` is assigned in `hi_config.z` at `offset`. Returns the accumulator,
+    /// the running sum `z`s, and the offset at which the LSB should be processed.
+    #[allow(clippy::type_complexity)]
+    fn double_and_add_hybrid(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        base: &NonIdentityEccPoint<C>,
+        bits: &[Option<bool>],
+        acc: EccPoint<C>,
+        z_init: Z<C::Base>,
+    ) -> Result<(EccPoint<C>, Vec<Z<C::Base>>, usize), Error> {
+        // Double-and-add (incomplete addition) for the `hi` half of the scalar decomposition
+        let (x_a, y_a, zs_incomplete_hi) = self.hi_config.double_and_add(
+            region,
+            offset,
+            base,
+            &bits[INCOMPLETE_HI_RANGE],
+            (X(acc.x), Y(acc.y), z_init),
+        )?;
+
+        // Double-and-add (incomplete addition) for the `lo` half of the scalar decomposition
+        let z = zs_incomplete_hi.last().expect("should not be empty");
+        let (x_a, y_a, zs_incomplete_lo) = self.lo_config.double_and_add(
+            region,
+            offset,
+            base,
+            &bits[INCOMPLETE_LO_RANGE],
+            (x_a, y_a, *z),
+        )?;
+
+        // Move from incomplete addition to complete addition.
+        // Inside incomplete::double_and_add, the offset was increased once after initialization
+        // of the running sum.
+        // Then, the final assignment of double-and-add was made on row + offset + 1.
+        // Outside of incomplete addition, we must account for these offset increases by adding
+        // 2 to the incomplete addition length.
+        let offset = offset + INCOMPLETE_LO_RANGE.len() + 2;
+
+        // Complete addition
+        let (acc, zs_complete) = {
+            let z = zs_incomplete_lo.last().expect("should not be empty");
+            // Bits used in complete addition. k_{3} to k_{1} inclusive
+            // The LSB k_{0} is handled separately.
+            let bits_complete = &bits[COMPLETE_RANGE];
+            self.complete_config.assign_region(
+                region,
+                offset,
+                bits_complete,
+                &(*base).into(),
+                x_a,
+                y_a,
+                *z,
+            )?
+        };
+
+        // Each iteration of the complete addition uses two rows.
+        let offset = offset + COMPLETE_RANGE.len() * 2;
+
+        let zs = std::iter::empty()
+            .chain(zs_incomplete_hi.into_iter())
+            .chain(zs_incomplete_lo.into_iter())
+            .chain(zs_complete.into_iter())
+            .collect();
+
+        Ok((acc, zs, offset))
+    }
+
+    /// Double-and-add under [`AddPolicy::AllComplete`]: complete addition for
+    /// bits `k_{254}` to `k_1`.
+    ///
+    /// Takes the same inputs and returns the same outputs as
+    /// [`Config::double_and_add_hybrid`].
+    #[allow(clippy::type_complexity)]
+    fn double_and_add_complete(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        base: &EccPoint<C>,
+        bits: &[Option<bool>],
+        acc: EccPoint<C>,
+        z_init: Z<C::Base>,
+    ) -> Result<(EccPoint<C>, Vec<Z<C::Base>>, usize), Error> {
+        // `z_init` shares its row with the output of the initial doubling, so
+        // complete addition starts on the next row.
+        let offset = offset + 1;
+
+        let (acc, zs) = self.complete_config.assign_region(
+            region,
+            offset,
+            bits,
+            base,
+            X(acc.x),
+            Y(acc.y),
+            z_init,
+        )?;
+
+        // Each iteration of the complete addition uses two rows.
+        let offset = offset + bits.len() * 2;
+
+        Ok((acc, zs, offset))
+    }
+
+    /// Double-and-add under [`AddPolicy::AllIncomplete`]: incomplete addition
+    /// for bits `k_{254}` to `k_1`, with the `lo` half extended down to `k_1`.
+    ///
+    /// Takes the same inputs and returns the same outputs as
+    /// [`Config::double_and_add_hybrid`].
+    #[allow(clippy::type_complexity)]
+    fn double_and_add_incomplete(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        base: &NonIdentityEccPoint<C>,
+        bits: &[Option<bool>],
+        acc: EccPoint<C>,
+        z_init: Z<C::Base>,
+    ) -> Result<(EccPoint<C>, Vec<Z<C::Base>>, usize), Error> {
+        let (x_a, y_a, zs_incomplete_hi) = self.hi_config.double_and_add(
+            region,
+            offset,
+            base,
+            &bits[INCOMPLETE_HI_RANGE],
+            (X(acc.x), Y(acc.y), z_init),
+        )?;
+
+        let bits_lo = &bits[INCOMPLETE_LO_RANGE.start..];
+        let z = zs_incomplete_hi.last().expect("should not be empty");
+        let (x_a, y_a, zs_incomplete_lo) =
+            self.lo_config
+                .double_and_add_bits(region, offset, base, bits_lo, (x_a, y_a, *z))?;
+
+        // As in `double_and_add_hybrid`, account for the initialization row and the
+        // final `x_a` row of incomplete addition.
+        let offset = offset + bits_lo.len() + 2;
+
+        // The LSB gate reads `z_1` from the `z_complete` column.
+        let z_1 = zs_incomplete_lo.last().expect("should not be empty");
+        copy(
+            region,
+            || "copy z_1",
+            self.complete_config.z_complete,
+            offset,
+            z_1,
+        )?;
+
+        let zs = std::iter::empty()
+            .chain(zs_incomplete_hi.into_iter())
+            .chain(zs_incomplete_lo.into_iter())
+            .collect();
+
+        Ok((EccPoint { x: *x_a, y: *y_a }, zs, offset))
+    }
+
     /// Processes the final scalar bit `k_0`.
     ///
     /// Assumptions for this sub-region:
@@ -283,22 +505,22 @@ impl Config {
     /// ```
     fn process_lsb(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        base: &NonIdentityEccPoint,
-        acc: EccPoint,
-        z_1: Z<pallas::Base>,
+        base: &NonIdentityEccPoint<C>,
+        acc: EccPoint<C>,
+        z_1: Z<C::Base>,
         lsb: Option<bool>,
-    ) -> Result<(EccPoint, Z<pallas::Base>), Error> {
+    ) -> Result<(EccPoint<C>, Z<C::Base>), Error> {
         // Enforce switching logic on LSB using a custom gate
-        self.q_mul_lsb.enable(region, offset)?;
+        self.usage.enable(&self.q_mul_lsb, region, offset)?;
 
         // z_1 has been assigned at (z_complete, offset).
         // Assign z_0 = 2⋅z_1 + k_0
         let z_0 = {
             let z_0_val = z_1.value().zip(lsb).map(|(z_1, lsb)| {
-                let lsb = pallas::Base::from_u64(lsb as u64);
-                z_1 * pallas::Base::from_u64(2) + lsb
+                let lsb = C::Base::from_u64(lsb as u64);
+                z_1 * C::Base::from_u64(2) + lsb
             });
             let z_0_cell = region.assign_advice(
                 || "z_0",
@@ -331,7 +553,7 @@ impl Config {
             if !lsb {
                 base.x.value()
             } else {
-                Some(pallas::Base::zero())
+                Some(C::Base::zero())
             }
         } else {
             None
@@ -341,7 +563,7 @@ impl Config {
             if !lsb {
                 base.y.value().map(|y_p| -y_p)
             } else {
-                Some(pallas::Base::zero())
+                Some(C::Base::zero())
             }
         } else {
             None
@@ -362,8 +584,8 @@ impl Config {
         )?;
 
         let p = EccPoint {
-            x: CellValue::<pallas::Base>::new(x_cell, x),
-            y: CellValue::<pallas::Base>::new(y_cell, y),
+            x: CellValue::<C::Base>::new(x_cell, x),
+            y: CellValue::<C::Base>::new(y_cell, y),
         };
 
         // Return the result of the final complete addition as `[scalar]B`
@@ -406,14 +628,14 @@ impl<F: FieldExt> Deref for Z<F> {
     }
 }
 
-fn decompose_for_scalar_mul(scalar: Option<pallas::Base>) -> Vec<Option<bool>> {
+fn decompose_for_scalar_mul<C: EccCurve>(scalar: Option<C::Base>) -> Vec<Option<bool>> {
     let bitstring = scalar.map(|scalar| {
         // We use `k = scalar + t_q` in the double-and-add algorithm, where
         // the scalar field `F_q = 2^254 + t_q`.
         // Note that the addition `scalar + t_q` is not reduced.
         //
         let scalar = U256::from_little_endian(&scalar.to_bytes());
-        let t_q = U256::from_little_endian(&T_Q.to_le_bytes());
+        let t_q = U256::from_little_endian(&C::T_Q.to_le_bytes());
         let k = scalar + t_q;
 
         // Big-endian bit representation of `k`.
@@ -430,7 +652,7 @@ fn decompose_for_scalar_mul(scalar: Option<pallas::Base>) -> Vec<Option<bool>> {
         };
 
         // Take the first 255 bits.
-        let mut bitstring = bitstring[0..pallas::Scalar::NUM_BITS as usize].to_vec();
+        let mut bitstring = bitstring[0..C::L_SCALAR].to_vec();
         bitstring.reverse();
         bitstring
     });
@@ -438,12 +660,13 @@ fn decompose_for_scalar_mul(scalar: Option<pallas::Base>) -> Vec<Option<bool>> {
     if let Some(bitstring) = bitstring {
         bitstring.into_iter().map(Some).collect()
     } else {
-        vec![None; pallas::Scalar::NUM_BITS as usize]
+        vec![None; C::L_SCALAR]
     }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use ff::{Field, PrimeField};
     use group::{Curve, Group};
     use halo2::{
         circuit::{Chip, Layouter},
@@ -455,10 +678,10 @@ pub mod tests {
         chip::{EccChip, EccPoint},
         EccInstructions, FixedPoints, NonIdentityPoint, Point,
     };
-    use crate::utilities::UtilitiesInstructions;
+    use crate::utilities::{UtilitiesInstructions, Var};
 
     pub fn test_mul<F: FixedPoints<pallas::Affine>>(
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
     ) -> Result<(), Error> {
         // Generate a random point P
@@ -468,7 +691,10 @@ pub mod tests {
         let column = chip.config().advices[0];
 
         fn constrain_equal_non_id<
-            EccChip: EccInstructions<pallas::Affine, Point = EccPoint> + Clone + Eq + std::fmt::Debug,
+            EccChip: EccInstructions<pallas::Affine, Point = EccPoint<pallas::Affine>>
+                + Clone
+                + Eq
+                + std::fmt::Debug,
         >(
             chip: EccChip,
             mut layouter: impl Layouter<pallas::Base>,
@@ -484,7 +710,8 @@ pub mod tests {
                 layouter.namespace(|| "expected point"),
                 Some((base_val * scalar).to_affine()),
             )?;
-            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)
+            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)?;
+            Ok(())
         }
 
         // [a]B
@@ -538,4 +765,284 @@ pub mod tests {
 
         Ok(())
     }
+
+    pub fn test_scalar_var_bits<F: FixedPoints<pallas::Affine>>(
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+
+        let column = chip.config().advices[0];
+
+        // -1 is the largest base field element, and exercises the canonicity
+        // check on the most significant bit.
+        for (idx, scalar_val) in [pallas::Base::rand(), -pallas::Base::one()]
+            .iter()
+            .enumerate()
+        {
+            let scalar = chip.load_private(
+                layouter.namespace(|| format!("scalar {}", idx)),
+                column,
+                Some(*scalar_val),
+            )?;
+            let (_, scalar) = p.mul(layouter.namespace(|| format!("[a_{}]B", idx)), &scalar)?;
+            let bits = scalar.bits(layouter.namespace(|| format!("bits of a_{}", idx)))?;
+            assert_eq!(bits.len(), pallas::Base::NUM_BITS as usize);
+
+            // Reconstruct the scalar from its little-endian bits.
+            let reconstructed = bits
+                .iter()
+                .rev()
+                .try_fold(pallas::Base::zero(), |acc, bit| {
+                    bit.value().map(|bit| acc.double() + bit)
+                });
+            assert_eq!(reconstructed, Some(*scalar_val));
+
+            // Constrain the reconstructed scalar to equal the original.
+            let reconstructed = chip.load_private(
+                layouter.namespace(|| format!("reconstructed a_{}", idx)),
+                column,
+                reconstructed,
+            )?;
+            layouter.assign_region(
+                || format!("reconstructed a_{} == a_{}", idx, idx),
+                |mut region| region.constrain_equal(reconstructed.cell(), scalar.inner.cell()),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_mul_sum<F: FixedPoints<pallas::Affine>>(
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let column = chip.config().advices[0];
+
+        // Generate three random (scalar, point) pairs
+        let mut terms = vec![];
+        let mut expected = pallas::Point::identity();
+        for idx in 0..3 {
+            let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let p = NonIdentityPoint::new(
+                chip.clone(),
+                layouter.namespace(|| format!("P_{}", idx)),
+                Some(p_val),
+            )?;
+
+            let scalar_val = pallas::Base::rand();
+            let scalar = chip.load_private(
+                layouter.namespace(|| format!("a_{}", idx)),
+                column,
+                Some(scalar_val),
+            )?;
+
+            // Move scalar from base field into scalar field (which always fits
+            // for Pallas).
+            expected += p_val * pallas::Scalar::from_bytes(&scalar_val.to_bytes()).unwrap();
+            terms.push((scalar, *p.inner()));
+        }
+
+        // [a_0] P_0 + [a_1] P_1 + [a_2] P_2
+        let result = chip.mul_sum(layouter.namespace(|| "mul_sum"), &terms)?;
+        let result = Point::from_inner(chip.clone(), result);
+        let expected = NonIdentityPoint::new(
+            chip.clone(),
+            layouter.namespace(|| "expected sum"),
+            Some(expected.to_affine()),
+        )?;
+        result.constrain_equal(layouter.namespace(|| "constrain sum"), &expected)?;
+
+        // [a_0] P_0 + [a_0] P_0 + [a_1] P_1, where the first two products are
+        // equal and so cannot be accumulated with incomplete addition.
+        {
+            let terms = vec![terms[0], terms[0], terms[1]];
+            let result = chip.mul_sum(layouter.namespace(|| "mul_sum (equal products)"), &terms)?;
+            let expected = terms.iter().fold(pallas::Point::identity(), |acc, (a, p)| {
+                let a = pallas::Scalar::from_bytes(&a.value().unwrap().to_bytes()).unwrap();
+                acc + p.point().unwrap() * a
+            });
+            let result = Point::from_inner(chip.clone(), result);
+            let expected = NonIdentityPoint::new(
+                chip,
+                layouter.namespace(|| "expected sum (equal products)"),
+                Some(expected.to_affine()),
+            )?;
+            result.constrain_equal(
+                layouter.namespace(|| "constrain sum (equal products)"),
+                &expected,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn mul_with_policy() {
+        use super::AddPolicy;
+        use crate::ecc::{chip::EccConfig, tests::FixedBase};
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+
+        #[derive(Default)]
+        struct MyCircuit {
+            base: Option<pallas::Affine>,
+            scalars: Vec<Option<pallas::Base>>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self {
+                    base: None,
+                    scalars: vec![None; self.scalars.len()],
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                let column = config.advices[0];
+                let base = chip.witness_point_non_id(&mut layouter, self.base)?;
+
+                for (idx, scalar) in self.scalars.iter().enumerate() {
+                    let scalar = chip.load_private(
+                        layouter.namespace(|| format!("scalar {}", idx)),
+                        column,
+                        *scalar,
+                    )?;
+
+                    let results = [
+                        AddPolicy::AllComplete,
+                        AddPolicy::Hybrid,
+                        AddPolicy::AllIncomplete,
+                    ]
+                    .iter()
+                    .map(|policy| {
+                        chip.mul_with_policy(
+                            layouter.namespace(|| format!("[a_{}]B ({:?})", idx, policy)),
+                            &scalar,
+                            &base,
+                            *policy,
+                        )
+                        .map(|(result, _)| result)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                    // All policies must agree, both on the witnessed values and
+                    // in-circuit.
+                    for result in results[1..].iter() {
+                        assert_eq!(result.point(), results[0].point());
+                        chip.constrain_equal(&mut layouter, &results[0], result)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        // Random scalars are non-exceptional with overwhelming probability.
+        let circuit = MyCircuit {
+            base: Some(pallas::Point::random(rand::rngs::OsRng).to_affine()),
+            scalars: (0..2).map(|_| Some(pallas::Base::rand())).collect(),
+        };
+        let prover = MockProver::<pallas::Base>::run(12, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn add_then_mul() {
+        use crate::ecc::{chip::EccConfig, tests::FixedBase};
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+
+        #[derive(Default)]
+        struct MyCircuit {
+            p: Option<pallas::Affine>,
+            q: Option<pallas::Affine>,
+            scalar: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), self.p)?;
+                let q = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "Q"), self.q)?;
+                let scalar = chip.load_private(
+                    layouter.namespace(|| "scalar"),
+                    config.advices[0],
+                    self.scalar,
+                )?;
+
+                // [s](P + Q), with the addition in the multiplication's region.
+                let (fused, _) =
+                    p.add_then_mul(layouter.namespace(|| "[s](P + Q)"), &q, &scalar)?;
+
+                // [s](P + Q), as separate calls.
+                let expected = p
+                    .add(layouter.namespace(|| "P + Q"), &q)?
+                    .assert_non_identity(layouter.namespace(|| "P + Q != 𝒪"))?
+                    .mul(layouter.namespace(|| "[s](P + Q) separately"), &scalar)?
+                    .0;
+
+                assert_eq!(fused.value(), expected.value());
+                fused.constrain_equal(layouter.namespace(|| "fused == separate"), &expected)?;
+                Ok(())
+            }
+        }
+
+        let p = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let q = pallas::Point::random(rand::rngs::OsRng).to_affine();
+
+        let circuit = MyCircuit {
+            p: Some(p),
+            q: Some(q),
+            scalar: Some(pallas::Base::rand()),
+        };
+        let prover = MockProver::<pallas::Base>::run(12, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // P + (-P) is the identity, which cannot be multiplied.
+        let circuit = MyCircuit {
+            p: Some(p),
+            q: Some(-p),
+            scalar: Some(pallas::Base::rand()),
+        };
+        assert!(MockProver::<pallas::Base>::run(12, &circuit, vec![]).is_err());
+    }
 }
@@ -1,29 +1,33 @@
-use super::super::{add, copy, CellValue, EccConfig, EccPoint, Var};
-use super::{COMPLETE_RANGE, X, Y, Z};
+use super::super::{add, copy, CellValue, EccConfig, EccCurve, EccPoint, SelectorUsage, Var};
+use super::{X, Y, Z};
 
+use ff::Field;
 use halo2::{
     circuit::Region,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
 
-use pasta_curves::{arithmetic::FieldExt, pallas};
+use pasta_curves::arithmetic::FieldExt;
 
-pub struct Config {
+pub struct Config<C: EccCurve> {
     // Selector used to constrain the cells used in complete addition.
     q_mul_decompose_var: Selector,
     // Advice column used to decompose scalar in complete addition.
     pub z_complete: Column<Advice>,
     // Configuration used in complete addition
-    add_config: add::Config,
+    add_config: add::Config<C>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         let config = Self {
             q_mul_decompose_var: ecc_config.q_mul_decompose_var,
             z_complete: ecc_config.advices[9],
             add_config: ecc_config.into(),
+            usage: ecc_config.usage.clone(),
         };
 
         let add_config_advices = config.add_config.advice_columns();
@@ -36,12 +40,12 @@ impl From<&EccConfig> for Config {
     }
 }
 
-impl Config {
+impl<C: EccCurve> Config<C> {
     /// Gate used to check scalar decomposition is correct.
     /// This is used to check the bits used in complete addition, since the incomplete
     /// addition gate (controlled by `q_mul`) already checks scalar decomposition for
     /// the other bits.
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         // | y_p | z_complete |
         // --------------------
         // | y_p | z_{i + 1}  |
@@ -57,8 +61,8 @@ impl Config {
                 let z_next = meta.query_advice(self.z_complete, Rotation::next());
 
                 // k_{i} = z_{i} - 2⋅z_{i+1}
-                let k = z_next - Expression::Constant(pallas::Base::from_u64(2)) * z_prev;
-                let k_minus_one = k.clone() - Expression::Constant(pallas::Base::one());
+                let k = z_next - Expression::Constant(C::Base::from_u64(2)) * z_prev;
+                let k_minus_one = k.clone() - Expression::Constant(C::Base::one());
                 // (k_i) ⋅ (k_i - 1) = 0
                 let bool_check = k.clone() * k_minus_one.clone();
 
@@ -82,26 +86,28 @@ impl Config {
     #[allow(clippy::too_many_arguments)]
     pub(super) fn assign_region(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
         bits: &[Option<bool>],
-        base: &EccPoint,
-        x_a: X<pallas::Base>,
-        y_a: Y<pallas::Base>,
-        z: Z<pallas::Base>,
-    ) -> Result<(EccPoint, Vec<Z<pallas::Base>>), Error> {
-        // Make sure we have the correct number of bits for the complete addition
-        // part of variable-base scalar mul.
-        assert_eq!(bits.len(), COMPLETE_RANGE.len());
+        base: &EccPoint<C>,
+        x_a: X<C::Base>,
+        y_a: Y<C::Base>,
+        z: Z<C::Base>,
+    ) -> Result<(EccPoint<C>, Vec<Z<C::Base>>), Error> {
+        // Make sure we have at least one bit for the complete addition part of
+        // variable-base scalar mul. This is `COMPLETE_RANGE` under the default
+        // policy, but may cover more bits under `AddPolicy::AllComplete`.
+        assert!(!bits.is_empty());
 
         // Enable selectors for complete range
-        for row in 0..COMPLETE_RANGE.len() {
+        for row in 0..bits.len() {
             // Each iteration uses 2 rows (two complete additions)
             let row = 2 * row;
             // Check scalar decomposition for each iteration. Since the gate enabled by
             // `q_mul_decompose_var` queries the previous row, we enable the selector on
             // `row + offset + 1` (instead of `row + offset`).
-            self.q_mul_decompose_var.enable(region, row + offset + 1)?;
+            self.usage
+                .enable(&self.q_mul_decompose_var, region, row + offset + 1)?;
         }
 
         // Use x_a, y_a output from incomplete addition
@@ -120,7 +126,7 @@ impl Config {
         };
 
         // Store interstitial running sum `z`s in vector
-        let mut zs: Vec<Z<pallas::Base>> = Vec::with_capacity(bits.len());
+        let mut zs: Vec<Z<C::Base>> = Vec::with_capacity(bits.len());
 
         // Complete addition
         for (iter, k) in bits.iter().enumerate() {
@@ -136,9 +142,10 @@ impl Config {
             // Update `z`.
             z = {
                 // z_next = z_cur * 2 + k_next
-                let z_val = z.value().zip(k.as_ref()).map(|(z_val, k)| {
-                    pallas::Base::from_u64(2) * z_val + pallas::Base::from_u64(*k as u64)
-                });
+                let z_val = z
+                    .value()
+                    .zip(k.as_ref())
+                    .map(|(z_val, k)| C::Base::from_u64(2) * z_val + C::Base::from_u64(*k as u64));
                 let z_cell = region.assign_advice(
                     || "z",
                     self.z_complete,
@@ -171,7 +178,7 @@ impl Config {
                     row + offset,
                     || y_p.ok_or(Error::SynthesisError),
                 )?;
-                CellValue::<pallas::Base>::new(y_p_cell, y_p)
+                CellValue::<C::Base>::new(y_p_cell, y_p)
             };
 
             // U = P if the bit is set; U = -P is the bit is not set.
@@ -1,6 +1,6 @@
-use std::ops::Deref;
+use std::{marker::PhantomData, ops::Deref};
 
-use super::super::{copy, CellValue, EccConfig, NonIdentityEccPoint, Var};
+use super::super::{copy, CellValue, EccConfig, EccCurve, NonIdentityEccPoint, SelectorUsage, Var};
 use super::{INCOMPLETE_HI_RANGE, INCOMPLETE_LO_RANGE, X, Y, Z};
 use ff::Field;
 use halo2::{
@@ -9,10 +9,10 @@ use halo2::{
     poly::Rotation,
 };
 
-use pasta_curves::{arithmetic::FieldExt, pallas};
+use pasta_curves::arithmetic::FieldExt;
 
-#[derive(Copy, Clone)]
-pub(super) struct Config {
+#[derive(Clone)]
+pub(super) struct Config<C: EccCurve> {
     // Number of bits covered by this incomplete range.
     num_bits: usize,
     // Selectors used to constrain the cells used in incomplete addition.
@@ -29,13 +29,16 @@ pub(super) struct Config {
     pub(super) lambda1: Column<Advice>,
     // lambda2 in each double-and-add iteration.
     pub(super) lambda2: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
 }
 
 // Columns used in processing the `hi` bits of the scalar.
 // `x_p, y_p` are shared across the `hi` and `lo` halves.
-pub(super) struct HiConfig(Config);
-impl From<&EccConfig> for HiConfig {
-    fn from(ecc_config: &EccConfig) -> Self {
+pub(super) struct HiConfig<C: EccCurve>(Config<C>);
+impl<C: EccCurve> From<&EccConfig<C>> for HiConfig<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         let config = Config {
             num_bits: INCOMPLETE_HI_RANGE.len(),
             q_mul: ecc_config.q_mul_hi,
@@ -45,23 +48,25 @@ impl From<&EccConfig> for HiConfig {
             x_a: ecc_config.advices[3],
             lambda1: ecc_config.advices[4],
             lambda2: ecc_config.advices[5],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
         };
         Self(config)
     }
 }
-impl Deref for HiConfig {
-    type Target = Config;
+impl<C: EccCurve> Deref for HiConfig<C> {
+    type Target = Config<C>;
 
-    fn deref(&self) -> &Config {
+    fn deref(&self) -> &Config<C> {
         &self.0
     }
 }
 
 // Columns used in processing the `lo` bits of the scalar.
 // `x_p, y_p` are shared across the `hi` and `lo` halves.
-pub(super) struct LoConfig(Config);
-impl From<&EccConfig> for LoConfig {
-    fn from(ecc_config: &EccConfig) -> Self {
+pub(super) struct LoConfig<C: EccCurve>(Config<C>);
+impl<C: EccCurve> From<&EccConfig<C>> for LoConfig<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         let config = Config {
             num_bits: INCOMPLETE_LO_RANGE.len(),
             q_mul: ecc_config.q_mul_lo,
@@ -71,23 +76,25 @@ impl From<&EccConfig> for LoConfig {
             x_a: ecc_config.advices[7],
             lambda1: ecc_config.advices[8],
             lambda2: ecc_config.advices[2],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
         };
         Self(config)
     }
 }
-impl Deref for LoConfig {
-    type Target = Config;
+impl<C: EccCurve> Deref for LoConfig<C> {
+    type Target = Config<C>;
 
-    fn deref(&self) -> &Config {
+    fn deref(&self) -> &Config<C> {
         &self.0
     }
 }
 
-impl Config {
+impl<C: EccCurve> Config<C> {
     // Gate for incomplete addition part of variable-base scalar multiplication.
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         // Closure to compute x_{R,i} = λ_{1,i}^2 - x_{A,i} - x_{P,i}
-        let x_r = |meta: &mut VirtualCells<pallas::Base>, rotation: Rotation| {
+        let x_r = |meta: &mut VirtualCells<C::Base>, rotation: Rotation| {
             let x_a = meta.query_advice(self.x_a, rotation);
             let x_p = meta.query_advice(self.x_p, rotation);
             let lambda_1 = meta.query_advice(self.lambda1, rotation);
@@ -95,19 +102,19 @@ impl Config {
         };
 
         // Closure to compute y_{A,i} = (λ_{1,i} + λ_{2,i}) * (x_{A,i} - x_{R,i}) / 2
-        let y_a = |meta: &mut VirtualCells<pallas::Base>, rotation: Rotation| {
+        let y_a = |meta: &mut VirtualCells<C::Base>, rotation: Rotation| {
             let x_a = meta.query_advice(self.x_a, rotation);
             let lambda_1 = meta.query_advice(self.lambda1, rotation);
             let lambda_2 = meta.query_advice(self.lambda2, rotation);
 
-            (lambda_1 + lambda_2) * (x_a - x_r(meta, rotation)) * pallas::Base::TWO_INV
+            (lambda_1 + lambda_2) * (x_a - x_r(meta, rotation)) * C::Base::TWO_INV
         };
 
         // Constraints used for q_mul_{2, 3} == 1
-        let for_loop = |meta: &mut VirtualCells<pallas::Base>,
-                        q_mul: Expression<pallas::Base>,
-                        y_a_next: Expression<pallas::Base>| {
-            let one = Expression::Constant(pallas::Base::one());
+        let for_loop = |meta: &mut VirtualCells<C::Base>,
+                        q_mul: Expression<C::Base>,
+                        y_a_next: Expression<C::Base>| {
+            let one = Expression::Constant(C::Base::one());
 
             // z_i
             let z_cur = meta.query_advice(self.z, Rotation::cur());
@@ -131,13 +138,13 @@ impl Config {
             // The current bit in the scalar decomposition, k_i = z_i - 2⋅z_{i+1}.
             // Recall that we assigned the cumulative variable `z_i` in descending order,
             // i from n down to 0. So z_{i+1} corresponds to the `z_prev` query.
-            let k = z_cur - z_prev * pallas::Base::from_u64(2);
+            let k = z_cur - z_prev * C::Base::from_u64(2);
             // Check booleanity of decomposition.
             let bool_check = k.clone() * (one.clone() - k.clone());
 
             // λ_{1,i}⋅(x_{A,i} − x_{P,i}) − y_{A,i} + (2k_i - 1) y_{P,i} = 0
             let gradient_1 = lambda1_cur * (x_a_cur.clone() - x_p_cur) - y_a_cur.clone()
-                + (k * pallas::Base::from_u64(2) - one) * y_p_cur;
+                + (k * C::Base::from_u64(2) - one) * y_p_cur;
 
             // λ_{2,i}^2 − x_{A,i-1} − x_{R,i} − x_{A,i} = 0
             let secant_line = lambda2_cur.clone().square()
@@ -208,24 +215,43 @@ impl Config {
     #[allow(clippy::type_complexity)]
     pub(super) fn double_and_add(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        base: &NonIdentityEccPoint,
+        base: &NonIdentityEccPoint<C>,
         bits: &[Option<bool>],
-        acc: (X<pallas::Base>, Y<pallas::Base>, Z<pallas::Base>),
-    ) -> Result<(X<pallas::Base>, Y<pallas::Base>, Vec<Z<pallas::Base>>), Error> {
+        acc: (X<C::Base>, Y<C::Base>, Z<C::Base>),
+    ) -> Result<(X<C::Base>, Y<C::Base>, Vec<Z<C::Base>>), Error> {
         // Check that we have the correct number of bits for this double-and-add.
         assert_eq!(bits.len(), self.num_bits);
 
+        self.double_and_add_bits(region, offset, base, bits, acc)
+    }
+
+    /// Double-and-add over an arbitrary number of bits, without checking them
+    /// against the range this config was created for. Only used by
+    /// `AddPolicy::AllIncomplete`, which extends the `lo` half down to `k_1`.
+    /// Returns (x, y, z).
+    #[allow(clippy::type_complexity)]
+    pub(super) fn double_and_add_bits(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        base: &NonIdentityEccPoint<C>,
+        bits: &[Option<bool>],
+        acc: (X<C::Base>, Y<C::Base>, Z<C::Base>),
+    ) -> Result<(X<C::Base>, Y<C::Base>, Vec<Z<C::Base>>), Error> {
+        assert!(!bits.is_empty());
+        let num_bits = bits.len();
+
         // Handle exceptional cases
         let (x_p, y_p) = (base.x.value(), base.y.value());
         let (x_a, y_a) = (acc.0.value(), acc.1.value());
 
         if let (Some(x_a), Some(y_a), Some(x_p), Some(y_p)) = (x_a, y_a, x_p, y_p) {
             // A is point at infinity
-            if (x_p == pallas::Base::zero() && y_p == pallas::Base::zero())
+            if (x_p == C::Base::zero() && y_p == C::Base::zero())
             // Q is point at infinity
-            || (x_a == pallas::Base::zero() && y_a == pallas::Base::zero())
+            || (x_a == C::Base::zero() && y_a == C::Base::zero())
             // x_p = x_a
             || (x_p == x_a)
             {
@@ -236,16 +262,17 @@ impl Config {
         // Set q_mul values
         {
             // q_mul_1 = 1 on offset 0
-            self.q_mul.0.enable(region, offset)?;
+            self.usage.enable(&self.q_mul.0, region, offset)?;
 
             let offset = offset + 1;
             // q_mul_2 = 1 on all rows after offset 0, excluding the last row.
-            for idx in 0..(self.num_bits - 1) {
-                self.q_mul.1.enable(region, offset + idx)?;
+            for idx in 0..(num_bits - 1) {
+                self.usage.enable(&self.q_mul.1, region, offset + idx)?;
             }
 
             // q_mul_3 = 1 on the last row.
-            self.q_mul.2.enable(region, offset + self.num_bits - 1)?;
+            self.usage
+                .enable(&self.q_mul.2, region, offset + num_bits - 1)?;
         }
 
         // Initialise double-and-add
@@ -264,14 +291,15 @@ impl Config {
         let offset = offset + 1;
 
         // Initialise vector to store all interstitial `z` running sum values.
-        let mut zs: Vec<Z<pallas::Base>> = Vec::with_capacity(bits.len());
+        let mut zs: Vec<Z<C::Base>> = Vec::with_capacity(bits.len());
 
         // Incomplete addition
         for (row, k) in bits.iter().enumerate() {
             // z_{i} = 2 * z_{i+1} + k_i
-            let z_val = z.value().zip(k.as_ref()).map(|(z_val, k)| {
-                pallas::Base::from_u64(2) * z_val + pallas::Base::from_u64(*k as u64)
-            });
+            let z_val = z
+                .value()
+                .zip(k.as_ref())
+                .map(|(z_val, k)| C::Base::from_u64(2) * z_val + C::Base::from_u64(*k as u64));
             let z_cell = region.assign_advice(
                 || "z",
                 self.z,
@@ -326,7 +354,7 @@ impl Config {
                     .zip(x_a.value())
                     .zip(x_r)
                     .map(|(((lambda1, y_a), x_a), x_r)| {
-                        pallas::Base::from_u64(2) * y_a * (x_a - x_r).invert().unwrap() - lambda1
+                        C::Base::from_u64(2) * y_a * (x_a - x_r).invert().unwrap() - lambda1
                     });
             region.assign_advice(
                 || "lambda2",
@@ -360,7 +388,7 @@ impl Config {
             let cell = region.assign_advice(
                 || "y_a",
                 self.lambda1,
-                offset + self.num_bits,
+                offset + num_bits,
                 || y_a.ok_or(Error::SynthesisError),
             )?;
             CellValue::new(cell, y_a)
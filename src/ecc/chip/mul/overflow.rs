@@ -1,5 +1,5 @@
-use super::super::{copy, CellValue, EccConfig, Var};
-use super::{T_Q, Z};
+use super::super::{copy, CellValue, EccConfig, EccCurve, SelectorUsage, Var};
+use super::Z;
 use crate::{primitives::sinsemilla, utilities::lookup_range_check::LookupRangeCheckConfig};
 use halo2::{
     circuit::Layouter,
@@ -8,21 +8,23 @@ use halo2::{
 };
 
 use ff::Field;
-use pasta_curves::{arithmetic::FieldExt, pallas};
+use pasta_curves::arithmetic::FieldExt;
 
 use std::iter;
 
-pub struct Config {
+pub struct Config<C: EccCurve> {
     // Selector to check z_0 = alpha + t_q (mod p)
     q_mul_overflow: Selector,
     // 10-bit lookup table
-    lookup_config: LookupRangeCheckConfig<pallas::Base, { sinsemilla::K }>,
+    lookup_config: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
     // Advice columns
     advices: [Column<Advice>; 3],
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         Self {
             q_mul_overflow: ecc_config.q_mul_overflow,
             lookup_config: ecc_config.lookup_config.clone(),
@@ -33,20 +35,21 @@ impl From<&EccConfig> for Config {
                 ecc_config.advices[7],
                 ecc_config.advices[8],
             ],
+            usage: ecc_config.usage.clone(),
         }
     }
 }
 
-impl Config {
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         meta.create_gate("overflow checks", |meta| {
             let q_mul_overflow = meta.query_selector(self.q_mul_overflow);
 
             // Constant expressions
-            let one = Expression::Constant(pallas::Base::one());
-            let two_pow_124 = Expression::Constant(pallas::Base::from_u128(1 << 124));
+            let one = Expression::Constant(C::Base::one());
+            let two_pow_124 = Expression::Constant(C::Base::from_u128(1 << 124));
             let two_pow_130 =
-                two_pow_124.clone() * Expression::Constant(pallas::Base::from_u128(1 << 6));
+                two_pow_124.clone() * Expression::Constant(C::Base::from_u128(1 << 6));
 
             let z_0 = meta.query_advice(self.advices[0], Rotation::prev());
             let z_130 = meta.query_advice(self.advices[0], Rotation::cur());
@@ -61,9 +64,9 @@ impl Config {
             let s = meta.query_advice(self.advices[2], Rotation::cur());
             let s_check = s - (alpha.clone() + k_254.clone() * two_pow_130);
 
-            // q = 2^254 + t_q is the Pallas scalar field modulus.
+            // q = 2^254 + t_q is the curve's scalar field modulus.
             // We cast t_q into the base field to check alpha + t_q (mod p).
-            let t_q = Expression::Constant(pallas::Base::from_u128(T_Q));
+            let t_q = Expression::Constant(C::Base::from_u128(C::T_Q));
 
             // z_0 - alpha - t_q = 0 (mod p)
             let recovery = z_0 - alpha - t_q;
@@ -90,9 +93,9 @@ impl Config {
 
     pub(super) fn overflow_check(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        alpha: CellValue<pallas::Base>,
-        zs: &[Z<pallas::Base>], // [z_0, z_1, ..., z_{254}, z_{255}]
+        mut layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+        zs: &[Z<C::Base>], // [z_0, z_1, ..., z_{254}, z_{255}]
     ) -> Result<(), Error> {
         // s = alpha + k_254 ⋅ 2^130 is witnessed here, and then copied into
         // the decomposition as well as the overflow check gate.
@@ -103,7 +106,7 @@ impl Config {
             let s_val = alpha
                 .value()
                 .zip(k_254.value())
-                .map(|(alpha, k_254)| alpha + k_254 * pallas::Base::from_u128(1 << 65).square());
+                .map(|(alpha, k_254)| alpha + k_254 * C::Base::from_u128(1 << 65).square());
 
             layouter.assign_region(
                 || "s = alpha + k_254 ⋅ 2^130",
@@ -124,13 +127,15 @@ impl Config {
         let s_minus_lo_130 =
             self.s_minus_lo_130(layouter.namespace(|| "decompose s_{0..=129}"), s)?;
 
-        layouter.assign_region(
+        self.usage.assign_region(
+            &mut layouter,
             || "overflow check",
             |mut region| {
                 let offset = 0;
 
                 // Enable overflow check gate
-                self.q_mul_overflow.enable(&mut region, offset + 1)?;
+                self.usage
+                    .enable(&self.q_mul_overflow, &mut region, offset + 1)?;
 
                 // Copy `z_0`
                 copy(&mut region, || "copy z_0", self.advices[0], offset, &*zs[0])?;
@@ -147,8 +152,8 @@ impl Config {
                 // Witness η = inv0(z_130), where inv0(x) = 0 if x = 0, 1/x otherwise
                 {
                     let eta = zs[130].value().map(|z_130| {
-                        if z_130 == pallas::Base::zero() {
-                            pallas::Base::zero()
+                        if z_130 == C::Base::zero() {
+                            C::Base::zero()
                         } else {
                             z_130.invert().unwrap()
                         }
@@ -200,9 +205,9 @@ impl Config {
 
     fn s_minus_lo_130(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        s: CellValue<pallas::Base>,
-    ) -> Result<CellValue<pallas::Base>, Error> {
+        mut layouter: impl Layouter<C::Base>,
+        s: CellValue<C::Base>,
+    ) -> Result<CellValue<C::Base>, Error> {
         // Number of k-bit words we can use in the lookup decomposition.
         let num_words = 130 / sinsemilla::K;
         assert!(num_words * sinsemilla::K == 130);
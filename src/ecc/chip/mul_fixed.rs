@@ -1,37 +1,32 @@
 use super::{
-    add, add_incomplete, CellValue, EccBaseFieldElemFixed, EccConfig, EccScalarFixed,
-    EccScalarFixedShort, FixedPoints, NonIdentityEccPoint, Var, FIXED_BASE_WINDOW_SIZE, H,
+    add, add_incomplete, CellValue, EccBaseFieldElemFixed, EccConfig, EccCurve, EccScalarFixed,
+    EccScalarFixedShort, FixedPoints, NonIdentityEccPoint, SelectorUsage, Var,
+    FIXED_BASE_WINDOW_SIZE, H,
 };
 use std::marker::PhantomData;
 
+use ff::Field;
 use group::Curve;
 use halo2::{
     circuit::Region,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, VirtualCells},
     poly::Rotation,
 };
-use lazy_static::lazy_static;
-use pasta_curves::{
-    arithmetic::{CurveAffine, FieldExt},
-    pallas,
-};
+use pasta_curves::arithmetic::{CurveAffine, FieldExt};
 
 pub mod base_field_elem;
 pub mod full_width;
 pub mod short;
 pub mod util;
 
-pub use util::{compute_lagrange_coeffs, compute_window_table, find_zs_and_us};
-
-lazy_static! {
-    static ref TWO_SCALAR: pallas::Scalar = pallas::Scalar::from_u64(2);
-    // H = 2^3 (3-bit window)
-    static ref H_SCALAR: pallas::Scalar = pallas::Scalar::from_u64(H as u64);
-    static ref H_BASE: pallas::Base = pallas::Base::from_u64(H as u64);
-}
+pub use util::{
+    check_tables, compute_lagrange_coeffs, compute_window_table, dump_tables, find_zs_and_us,
+    find_zs_and_us_bounded, load_tables, FixedBaseTable, FixedBaseTables, GeneratorFixedBase,
+    WindowError, MAX_Z_TRIES,
+};
 
 #[derive(Clone, Debug)]
-pub struct Config<F: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> {
+pub struct Config<C: EccCurve, F: FixedPoints<C>, const NUM_WINDOWS: usize> {
     q_mul_fixed_running_sum: Selector,
     // The fixed Lagrange interpolation coefficients for `x_p`.
     lagrange_coeffs: [Column<Fixed>; H],
@@ -47,16 +42,18 @@ pub struct Config<F: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> {
     // y-coordinate of accumulator (only used in the final row).
     u: Column<Advice>,
     // Configuration for `add`
-    add_config: add::Config,
+    add_config: add::Config<C>,
     // Configuration for `add_incomplete`
-    add_incomplete_config: add_incomplete::Config,
+    add_incomplete_config: add_incomplete::Config<C>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
     _marker: PhantomData<F>,
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> From<&EccConfig>
-    for Config<Fixed, NUM_WINDOWS>
+impl<C: EccCurve, Fixed: FixedPoints<C>, const NUM_WINDOWS: usize> From<&EccConfig<C>>
+    for Config<C, Fixed, NUM_WINDOWS>
 {
-    fn from(ecc_config: &EccConfig) -> Self {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         let config = Self {
             q_mul_fixed_running_sum: ecc_config.q_mul_fixed_running_sum,
             lagrange_coeffs: ecc_config.lagrange_coeffs,
@@ -67,6 +64,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> From<&EccConf
             u: ecc_config.advices[5],
             add_config: ecc_config.into(),
             add_incomplete_config: ecc_config.into(),
+            usage: ecc_config.usage.clone(),
             _marker: PhantomData,
         };
 
@@ -104,7 +102,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> From<&EccConf
     }
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed, NUM_WINDOWS> {
+impl<C: EccCurve, Fixed: FixedPoints<C>, const NUM_WINDOWS: usize> Config<C, Fixed, NUM_WINDOWS> {
     /// Check that each window in the running sum decomposition uses the correct y_p
     /// and interpolated x_p.
     ///
@@ -114,7 +112,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
     /// This gate is not used in the mul_fixed::full_width helper, since the full-width
     /// scalar is witnessed directly as three-bit windows instead of being decomposed
     /// via a running sum.
-    pub(crate) fn running_sum_coords_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+    pub(crate) fn running_sum_coords_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         meta.create_gate("Running sum coordinates check", |meta| {
             let q_mul_fixed_running_sum = meta.query_selector(self.q_mul_fixed_running_sum);
 
@@ -123,7 +121,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
             //    z_{i+1} = (z_i - a_i) / 2^3
             // => a_i = z_i - z_{i+1} * 2^3
-            let word = z_cur - z_next * pallas::Base::from_u64(H as u64);
+            let word = z_cur - z_next * C::Base::from_u64(H as u64);
 
             self.coords_check(meta, q_mul_fixed_running_sum, word)
         });
@@ -132,25 +130,25 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
     #[allow(clippy::op_ref)]
     fn coords_check(
         &self,
-        meta: &mut VirtualCells<'_, pallas::Base>,
-        toggle: Expression<pallas::Base>,
-        window: Expression<pallas::Base>,
-    ) -> Vec<(&'static str, Expression<pallas::Base>)> {
+        meta: &mut VirtualCells<'_, C::Base>,
+        toggle: Expression<C::Base>,
+        window: Expression<C::Base>,
+    ) -> Vec<(&'static str, Expression<C::Base>)> {
         let y_p = meta.query_advice(self.y_p, Rotation::cur());
         let x_p = meta.query_advice(self.x_p, Rotation::cur());
         let z = meta.query_fixed(self.fixed_z, Rotation::cur());
         let u = meta.query_advice(self.u, Rotation::cur());
 
-        let window_pow: Vec<Expression<pallas::Base>> = (0..H)
+        let window_pow: Vec<Expression<C::Base>> = (0..H)
             .map(|pow| {
-                (0..pow).fold(Expression::Constant(pallas::Base::one()), |acc, _| {
+                (0..pow).fold(Expression::Constant(C::Base::one()), |acc, _| {
                     acc * window.clone()
                 })
             })
             .collect();
 
         let interpolated_x = window_pow.iter().zip(self.lagrange_coeffs.iter()).fold(
-            Expression::Constant(pallas::Base::zero()),
+            Expression::Constant(C::Base::zero()),
             |acc, (window_pow, coeff)| {
                 acc + (window_pow.clone() * meta.query_fixed(*coeff, Rotation::cur()))
             },
@@ -161,8 +159,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
         // Check that `y + z = u^2`, where `z` is fixed and `u`, `y` are witnessed
         let y_check = u.square() - y_p.clone() - z;
         // Check that (x, y) is on the curve
-        let on_curve =
-            y_p.square() - x_p.clone().square() * x_p - Expression::Constant(pallas::Affine::b());
+        let on_curve = y_p.square() - x_p.clone().square() * x_p - Expression::Constant(C::b());
 
         vec![
             ("check x", toggle.clone() * x_check),
@@ -174,20 +171,39 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
     #[allow(clippy::type_complexity)]
     fn assign_region_inner(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        scalar: &ScalarFixed<C>,
+        base: &Fixed,
+        coords_check_toggle: Selector,
+    ) -> Result<(NonIdentityEccPoint<C>, NonIdentityEccPoint<C>), Error> {
+        self.assign_region_inner_with_trace(region, offset, scalar, base, coords_check_toggle, None)
+    }
+
+    /// As [`Config::assign_region_inner`], additionally pushing the value of
+    /// the accumulator after each incomplete-addition window onto `trace`
+    /// if it is provided.
+    #[allow(clippy::type_complexity)]
+    fn assign_region_inner_with_trace(
+        &self,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        scalar: &ScalarFixed,
+        scalar: &ScalarFixed<C>,
         base: &Fixed,
         coords_check_toggle: Selector,
-    ) -> Result<(NonIdentityEccPoint, NonIdentityEccPoint), Error> {
+        mut trace: Option<&mut Vec<NonIdentityEccPoint<C>>>,
+    ) -> Result<(NonIdentityEccPoint<C>, NonIdentityEccPoint<C>), Error> {
         // Assign fixed columns for given fixed base
         self.assign_fixed_constants(region, offset, base, coords_check_toggle)?;
 
         // Initialize accumulator
         let acc = self.initialize_accumulator(region, offset, base, scalar)?;
+        if let Some(trace) = trace.as_mut() {
+            trace.push(acc);
+        }
 
         // Process all windows excluding least and most significant windows
-        let acc = self.add_incomplete(region, offset, acc, base, scalar)?;
+        let acc = self.add_incomplete(region, offset, acc, base, scalar, trace)?;
 
         // Process most significant window using complete addition
         let mul_b = self.process_msb(region, offset, base, scalar)?;
@@ -197,10 +213,29 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
     fn assign_fixed_constants(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
         base: &Fixed,
         coords_check_toggle: Selector,
+    ) -> Result<(), Error> {
+        self.assign_fixed_constants_for_windows(
+            region,
+            offset,
+            base,
+            coords_check_toggle,
+            NUM_WINDOWS,
+        )
+    }
+
+    /// Assigns the fixed columns for the `num_windows` least significant
+    /// windows of the given fixed base.
+    fn assign_fixed_constants_for_windows(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        base: &Fixed,
+        coords_check_toggle: Selector,
+        num_windows: usize,
     ) -> Result<(), Error> {
         let lagrange_coeffs = base.lagrange_coeffs();
         assert_eq!(lagrange_coeffs.len(), NUM_WINDOWS);
@@ -208,9 +243,12 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
         let z = base.z();
         assert_eq!(z.len(), NUM_WINDOWS);
 
+        assert!(num_windows <= NUM_WINDOWS);
+
         // Assign fixed columns for given fixed base
-        for window in 0..NUM_WINDOWS {
-            coords_check_toggle.enable(region, window + offset)?;
+        for window in 0..num_windows {
+            self.usage
+                .enable(&coords_check_toggle, region, window + offset)?;
 
             // Assign x-coordinate Lagrange interpolation coefficients
             for k in 0..(H) {
@@ -232,7 +270,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
                 || format!("z-value for window: {:?}", window),
                 self.fixed_z,
                 window + offset,
-                || Ok(pallas::Base::from_u64(z[window])),
+                || Ok(C::Base::from_u64(z[window])),
             )?;
         }
 
@@ -241,26 +279,47 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
     fn process_window(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
         w: usize,
-        k: Option<pallas::Scalar>,
+        k: Option<C::Scalar>,
         k_usize: Option<usize>,
         base: &Fixed,
-    ) -> Result<NonIdentityEccPoint, Error> {
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
         let base_value = base.generator();
         let base_u = base.u();
         assert_eq!(base_u.len(), NUM_WINDOWS);
 
         // Compute [(k_w + 2) ⋅ 8^w]B
         let mul_b = {
-            let mul_b =
-                k.map(|k| base_value * (k + *TWO_SCALAR) * H_SCALAR.pow(&[w as u64, 0, 0, 0]));
-            let mul_b = mul_b.map(|mul_b| mul_b.to_affine().coordinates().unwrap());
+            let mul_b = k.map(|k| {
+                (base_value
+                    * (k + C::Scalar::from_u64(2))
+                    * C::Scalar::from_u64(H as u64).pow(&[w as u64, 0, 0, 0]))
+                .to_affine()
+            });
+
+            #[cfg(any(test, feature = "debug-checks"))]
+            // Check this window's tables against the expected point, so that a
+            // malformed table fails here instead of as an unsatisfied constraint.
+            {
+                if let (Some(mul_b), Some(k)) = (mul_b, k_usize) {
+                    util::check_window(
+                        w,
+                        k,
+                        &base.lagrange_coeffs()[w],
+                        base.z()[w],
+                        &base_u[w][k],
+                        mul_b,
+                    )?;
+                }
+            }
+
+            let mul_b = mul_b.map(|mul_b| mul_b.coordinates().unwrap());
 
             let x = mul_b.map(|mul_b| {
                 let x = *mul_b.x();
-                assert!(x != pallas::Base::zero());
+                assert!(x != C::Base::zero());
                 x
             });
             let x_cell = region.assign_advice(
@@ -273,7 +332,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
             let y = mul_b.map(|mul_b| {
                 let y = *mul_b.y();
-                assert!(y != pallas::Base::zero());
+                assert!(y != C::Base::zero());
                 y
             });
             let y_cell = region.assign_advice(
@@ -288,7 +347,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
         };
 
         // Assign u = (y_p + z_w).sqrt()
-        let u_val = k_usize.map(|k| pallas::Base::from_bytes(&base_u[w][k]).unwrap());
+        let u_val = k_usize.map(|k| C::Base::from_bytes(&base_u[w][k]).unwrap());
         region.assign_advice(
             || "u",
             self.u,
@@ -301,11 +360,11 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
     fn initialize_accumulator(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
         base: &Fixed,
-        scalar: &ScalarFixed,
-    ) -> Result<NonIdentityEccPoint, Error> {
+        scalar: &ScalarFixed<C>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
         // Recall that the message at each window `w` is represented as
         // `m_w = [(k_w + 2) ⋅ 8^w]B`.
         // When `w = 0`, we have `m_0 = [(k_0 + 2)]B`.
@@ -317,12 +376,13 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
     fn add_incomplete(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        mut acc: NonIdentityEccPoint,
+        mut acc: NonIdentityEccPoint<C>,
         base: &Fixed,
-        scalar: &ScalarFixed,
-    ) -> Result<NonIdentityEccPoint, Error> {
+        scalar: &ScalarFixed<C>,
+        mut trace: Option<&mut Vec<NonIdentityEccPoint<C>>>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
         let scalar_windows_field = scalar.windows_field();
         let scalar_windows_usize = scalar.windows_usize();
 
@@ -340,21 +400,26 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
             acc = self
                 .add_incomplete_config
                 .assign_region(&mul_b, &acc, offset + w, region)?;
+            if let Some(trace) = trace.as_mut() {
+                trace.push(acc);
+            }
         }
         Ok(acc)
     }
 
     fn process_msb(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
         base: &Fixed,
-        scalar: &ScalarFixed,
-    ) -> Result<NonIdentityEccPoint, Error> {
+        scalar: &ScalarFixed<C>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        let windows_usize = scalar.windows_usize();
+
         // Assign u = (y_p + z_w).sqrt() for the most significant window
         {
-            let u_val = scalar.windows_usize()[NUM_WINDOWS - 1]
-                .map(|k| pallas::Base::from_bytes(&base.u()[NUM_WINDOWS - 1][k]).unwrap());
+            let u_val = windows_usize[NUM_WINDOWS - 1]
+                .map(|k| C::Base::from_bytes(&base.u()[NUM_WINDOWS - 1][k]).unwrap());
             region.assign_advice(
                 || "u",
                 self.u,
@@ -364,21 +429,44 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
         }
 
         // offset_acc = \sum_{j = 0}^{NUM_WINDOWS - 2} 2^{FIXED_BASE_WINDOW_SIZE*j + 1}
-        let offset_acc = (0..(NUM_WINDOWS - 1)).fold(pallas::Scalar::zero(), |acc, w| {
-            acc + (*TWO_SCALAR).pow(&[FIXED_BASE_WINDOW_SIZE as u64 * w as u64 + 1, 0, 0, 0])
+        let offset_acc = (0..(NUM_WINDOWS - 1)).fold(C::Scalar::zero(), |acc, w| {
+            acc + C::Scalar::from_u64(2).pow(&[
+                FIXED_BASE_WINDOW_SIZE as u64 * w as u64 + 1,
+                0,
+                0,
+                0,
+            ])
         });
 
         // `scalar = [k * 8^84 - offset_acc]`, where `offset_acc = \sum_{j = 0}^{83} 2^{FIXED_BASE_WINDOW_SIZE*j + 1}`.
-        let scalar = scalar.windows_field()[scalar.windows_field().len() - 1]
-            .map(|k| k * (*H_SCALAR).pow(&[(NUM_WINDOWS - 1) as u64, 0, 0, 0]) - offset_acc);
+        let scalar = scalar.windows_field()[scalar.windows_field().len() - 1].map(|k| {
+            k * C::Scalar::from_u64(H as u64).pow(&[(NUM_WINDOWS - 1) as u64, 0, 0, 0]) - offset_acc
+        });
 
         let mul_b = {
-            let mul_b = scalar.map(|scalar| base.generator() * scalar);
-            let mul_b = mul_b.map(|mul_b| mul_b.to_affine().coordinates().unwrap());
+            let mul_b = scalar.map(|scalar| (base.generator() * scalar).to_affine());
+
+            #[cfg(any(test, feature = "debug-checks"))]
+            // Check the most significant window's tables, as for the other windows.
+            {
+                let w = NUM_WINDOWS - 1;
+                if let (Some(mul_b), Some(k)) = (mul_b, windows_usize[w]) {
+                    util::check_window(
+                        w,
+                        k,
+                        &base.lagrange_coeffs()[w],
+                        base.z()[w],
+                        &base.u()[w][k],
+                        mul_b,
+                    )?;
+                }
+            }
+
+            let mul_b = mul_b.map(|mul_b| mul_b.coordinates().unwrap());
 
             let x = mul_b.map(|mul_b| {
                 let x = *mul_b.x();
-                assert!(x != pallas::Base::zero());
+                assert!(x != C::Base::zero());
                 x
             });
             let x_cell = region.assign_advice(
@@ -392,7 +480,7 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
 
             let y = mul_b.map(|mul_b| {
                 let y = *mul_b.y();
-                assert!(y != pallas::Base::zero());
+                assert!(y != C::Base::zero());
                 y
             });
             let y_cell = region.assign_advice(
@@ -410,43 +498,43 @@ impl<Fixed: FixedPoints<pallas::Affine>, const NUM_WINDOWS: usize> Config<Fixed,
     }
 }
 
-enum ScalarFixed {
-    FullWidth(EccScalarFixed),
-    Short(EccScalarFixedShort),
-    BaseFieldElem(EccBaseFieldElemFixed),
+enum ScalarFixed<C: CurveAffine> {
+    FullWidth(EccScalarFixed<C>),
+    Short(EccScalarFixedShort<C>),
+    BaseFieldElem(EccBaseFieldElemFixed<C>),
 }
 
-impl From<&EccScalarFixed> for ScalarFixed {
-    fn from(scalar_fixed: &EccScalarFixed) -> Self {
+impl<C: CurveAffine> From<&EccScalarFixed<C>> for ScalarFixed<C> {
+    fn from(scalar_fixed: &EccScalarFixed<C>) -> Self {
         Self::FullWidth(scalar_fixed.clone())
     }
 }
 
-impl From<&EccScalarFixedShort> for ScalarFixed {
-    fn from(scalar_fixed: &EccScalarFixedShort) -> Self {
+impl<C: CurveAffine> From<&EccScalarFixedShort<C>> for ScalarFixed<C> {
+    fn from(scalar_fixed: &EccScalarFixedShort<C>) -> Self {
         Self::Short(scalar_fixed.clone())
     }
 }
 
-impl From<&EccBaseFieldElemFixed> for ScalarFixed {
-    fn from(base_field_elem: &EccBaseFieldElemFixed) -> Self {
+impl<C: CurveAffine> From<&EccBaseFieldElemFixed<C>> for ScalarFixed<C> {
+    fn from(base_field_elem: &EccBaseFieldElemFixed<C>) -> Self {
         Self::BaseFieldElem(base_field_elem.clone())
     }
 }
 
-impl ScalarFixed {
+impl<C: CurveAffine> ScalarFixed<C> {
     // The scalar decomposition was done in the base field. For computation
     // outside the circuit, we now convert them back into the scalar field.
-    fn windows_field(&self) -> Vec<Option<pallas::Scalar>> {
-        let running_sum_to_windows = |zs: Vec<CellValue<pallas::Base>>| {
+    fn windows_field(&self) -> Vec<Option<C::Scalar>> {
+        let running_sum_to_windows = |zs: Vec<CellValue<C::Base>>| {
             (0..(zs.len() - 1))
                 .map(|idx| {
                     let z_cur = zs[idx].value();
                     let z_next = zs[idx + 1].value();
                     let word = z_cur
                         .zip(z_next)
-                        .map(|(z_cur, z_next)| z_cur - z_next * *H_BASE);
-                    word.map(|word| pallas::Scalar::from_bytes(&word.to_bytes()).unwrap())
+                        .map(|(z_cur, z_next)| z_cur - z_next * C::Base::from_u64(H as u64));
+                    word.map(|word| C::Scalar::from_bytes(&word.to_bytes()).unwrap())
                 })
                 .collect::<Vec<_>>()
         };
@@ -458,7 +546,7 @@ impl ScalarFixed {
                 .iter()
                 .map(|bits| {
                     bits.value()
-                        .map(|value| pallas::Scalar::from_bytes(&value.to_bytes()).unwrap())
+                        .map(|value| C::Scalar::from_bytes(&value.to_bytes()).unwrap())
                 })
                 .collect::<Vec<_>>(),
         }
@@ -1,8 +1,7 @@
 use super::super::{
-    EccBaseFieldElemFixed, EccConfig, EccPoint, FixedPoints, FIXED_BASE_WINDOW_SIZE, L_PALLAS_BASE,
-    NUM_WINDOWS, T_P,
+    EccBaseFieldElemFixed, EccConfig, EccCurve, EccPoint, FixedPoints, SelectorUsage,
+    FIXED_BASE_WINDOW_SIZE, H, NUM_WINDOWS,
 };
-use super::H_BASE;
 
 use crate::{
     primitives::sinsemilla,
@@ -16,21 +15,23 @@ use halo2::{
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
-use pasta_curves::{arithmetic::FieldExt, pallas};
+use pasta_curves::arithmetic::FieldExt;
 
 use std::convert::TryInto;
 
-pub struct Config<Fixed: FixedPoints<pallas::Affine>> {
+pub struct Config<C: EccCurve, Fixed: FixedPoints<C>> {
     q_mul_fixed_running_sum: Selector,
     q_mul_fixed_base_field: Selector,
     canon_advices: [Column<Advice>; 3],
-    lookup_config: LookupRangeCheckConfig<pallas::Base, { sinsemilla::K }>,
-    running_sum_config: RunningSumConfig<pallas::Base, { FIXED_BASE_WINDOW_SIZE }>,
-    super_config: super::Config<Fixed, { NUM_WINDOWS }>,
+    lookup_config: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    running_sum_config: RunningSumConfig<C::Base, { FIXED_BASE_WINDOW_SIZE }>,
+    super_config: super::Config<C, Fixed, { NUM_WINDOWS }>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> From<&EccConfig> for Config<Fixed> {
-    fn from(config: &EccConfig) -> Self {
+impl<C: EccCurve, Fixed: FixedPoints<C>> From<&EccConfig<C>> for Config<C, Fixed> {
+    fn from(config: &EccConfig<C>) -> Self {
         let config = Self {
             q_mul_fixed_running_sum: config.q_mul_fixed_running_sum,
             q_mul_fixed_base_field: config.q_mul_fixed_base_field,
@@ -38,6 +39,7 @@ impl<Fixed: FixedPoints<pallas::Affine>> From<&EccConfig> for Config<Fixed> {
             lookup_config: config.lookup_config.clone(),
             running_sum_config: config.running_sum_config.clone(),
             super_config: config.into(),
+            usage: config.usage.clone(),
         };
 
         let add_incomplete_advices = config.super_config.add_incomplete_config.advice_columns();
@@ -54,8 +56,8 @@ impl<Fixed: FixedPoints<pallas::Affine>> From<&EccConfig> for Config<Fixed> {
     }
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
-    pub fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+impl<C: EccCurve, Fixed: FixedPoints<C>> Config<C, Fixed> {
+    pub fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         // Check that the base field element is canonical.
         meta.create_gate("Canonicity checks", |meta| {
             let q_mul_fixed_base_field = meta.query_selector(self.q_mul_fixed_base_field);
@@ -69,7 +71,7 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             //
             // α_0 is derived, not witnessed.
             let alpha_0 = {
-                let two_pow_252 = pallas::Base::from_u128(1 << 126).square();
+                let two_pow_252 = C::Base::from_u128(1 << 126).square();
                 alpha - (z_84_alpha.clone() * two_pow_252)
             };
             let alpha_1 = meta.query_advice(self.canon_advices[1], Rotation::cur());
@@ -87,7 +89,7 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
                 let alpha_2_range_check = range_check(alpha_2.clone(), 1 << 1);
                 // Check that α_1 + 2^2 α_2 = z_84_alpha
                 let z_84_alpha_check = z_84_alpha.clone()
-                    - (alpha_1.clone() + alpha_2.clone() * pallas::Base::from_u64(1 << 2));
+                    - (alpha_1.clone() + alpha_2.clone() * C::Base::from_u64(1 << 2));
 
                 std::iter::empty()
                     .chain(Some(("alpha_1_range_check", alpha_1_range_check)))
@@ -97,8 +99,8 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
 
             // Check α_0_prime = α_0 + 2^130 - t_p
             let alpha_0_prime_check = {
-                let two_pow_130 = Expression::Constant(pallas::Base::from_u128(1 << 65).square());
-                let t_p = Expression::Constant(pallas::Base::from_u128(T_P));
+                let two_pow_130 = Expression::Constant(C::Base::from_u128(1 << 65).square());
+                let t_p = Expression::Constant(C::Base::from_u128(C::T_P));
                 alpha_0_prime - (alpha_0 + two_pow_130 - t_p)
             };
 
@@ -125,12 +127,11 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             let canon_checks = {
                 // alpha_0_hi_120 = z_44 - 2^120 z_84
                 let alpha_0_hi_120 = {
-                    let two_pow_120 =
-                        Expression::Constant(pallas::Base::from_u128(1 << 60).square());
+                    let two_pow_120 = Expression::Constant(C::Base::from_u128(1 << 60).square());
                     z_44_alpha.clone() - z_84_alpha * two_pow_120
                 };
                 // a_43 = z_43 - (2^3)z_44
-                let a_43 = z_43_alpha - z_44_alpha * *H_BASE;
+                let a_43 = z_43_alpha - z_44_alpha * C::Base::from_u64(H as u64);
 
                 std::iter::empty()
                     .chain(Some(("MSB = 1 => alpha_1 = 0", alpha_2.clone() * alpha_1)))
@@ -157,11 +158,12 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
 
     pub fn assign(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        scalar: CellValue<pallas::Base>,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: CellValue<C::Base>,
         base: &Fixed,
-    ) -> Result<EccPoint, Error> {
-        let (scalar, acc, mul_b) = layouter.assign_region(
+    ) -> Result<EccPoint<C>, Error> {
+        let (scalar, acc, mul_b) = self.usage.assign_region(
+            &mut layouter,
             || "Base-field elem fixed-base mul (incomplete addition)",
             |mut region| {
                 let offset = 0;
@@ -173,7 +175,7 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
                         offset,
                         scalar,
                         true,
-                        L_PALLAS_BASE,
+                        C::L_BASE,
                         NUM_WINDOWS,
                     )?;
                     EccBaseFieldElemFixed {
@@ -195,7 +197,8 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
         )?;
 
         // Add to the accumulator and return the final result as `[scalar]B`.
-        let result = layouter.assign_region(
+        let result = self.usage.assign_region(
+            &mut layouter,
             || "Base-field elem fixed-base mul (complete addition)",
             |mut region| {
                 self.super_config.add_config.assign_region(
@@ -207,27 +210,26 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             },
         )?;
 
-        #[cfg(test)]
+        #[cfg(any(test, feature = "debug-checks"))]
         // Check that the correct multiple is obtained.
         {
-            use group::Curve;
-
-            let scalar = &scalar
-                .base_field_elem
-                .value()
-                .map(|scalar| pallas::Scalar::from_bytes(&scalar.to_bytes()).unwrap());
+            // Reduce the base field element into the scalar field, which may be
+            // smaller than the base field.
+            let scalar = &scalar.base_field_elem.value().map(|scalar| {
+                let mut bytes = [0u8; 64];
+                bytes[..32].copy_from_slice(&scalar.to_bytes());
+                C::Scalar::from_bytes_wide(&bytes)
+            });
             let real_mul = scalar.map(|scalar| base.generator() * scalar);
             let result = result.point();
 
-            if let (Some(real_mul), Some(result)) = (real_mul, result) {
-                assert_eq!(real_mul.to_affine(), result);
-            }
+            super::super::debug_check_point(real_mul, result);
         }
 
         // We want to enforce canonicity of a 255-bit base field element, α.
-        // That is, we want to check that 0 ≤ α < p, where p is Pallas base
+        // That is, we want to check that 0 ≤ α < p, where p is the base
         // field modulus p = 2^254 + t_p
-        //                 = 2^254 + 45560315531419706090280762371685220353.
+        //                 = 2^254 + 45560315531419706090280762371685220353 (for Pallas).
         // Note that t_p < 2^130.
         //
         // α has been decomposed into three pieces in little-endian order:
@@ -253,15 +255,15 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             .value()
             .zip(z_84_alpha.value())
             .map(|(alpha, z_84_alpha)| {
-                let two_pow_252 = pallas::Base::from_u128(1 << 126).square();
+                let two_pow_252 = C::Base::from_u128(1 << 126).square();
                 alpha - z_84_alpha * two_pow_252
             });
 
         let (alpha_0_prime, z_13_alpha_0_prime) = {
             // alpha_0_prime = alpha + 2^130 - t_p.
             let alpha_0_prime = alpha_0.map(|alpha_0| {
-                let two_pow_130 = pallas::Base::from_u128(1 << 65).square();
-                let t_p = pallas::Base::from_u128(T_P);
+                let two_pow_130 = C::Base::from_u128(1 << 65).square();
+                let t_p = C::Base::from_u128(C::T_P);
                 alpha_0 + two_pow_130 - t_p
             });
             let zs = self.lookup_config.witness_check(
@@ -275,11 +277,13 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             (alpha_0_prime, zs[13])
         };
 
-        layouter.assign_region(
+        self.usage.assign_region(
+            &mut layouter,
             || "Canonicity checks",
             |mut region| {
                 // Activate canonicity check gate
-                self.q_mul_fixed_base_field.enable(&mut region, 1)?;
+                self.usage
+                    .enable(&self.q_mul_fixed_base_field, &mut region, 1)?;
 
                 // Offset 0
                 {
@@ -404,7 +408,7 @@ pub mod tests {
 
     pub fn test_mul_fixed_base_field<F: FixedPoints<pallas::Affine>>(
         base: F,
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
     ) -> Result<(), Error> {
         test_single_base(
@@ -415,21 +419,63 @@ pub mod tests {
         )
     }
 
+    pub fn test_mul_fixed_from_bits<F: FixedPoints<pallas::Affine>>(
+        base: F,
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::ecc::EccInstructions;
+        use ff::PrimeFieldBits;
+
+        let column = chip.config().advices[0];
+
+        // A 64-bit scalar, as might come out of a Sinsemilla message piece.
+        let scalar_val = pallas::Base::from_u64(rand::random::<u64>());
+        let bits = scalar_val
+            .to_le_bits()
+            .iter()
+            .by_val()
+            .take(64)
+            .enumerate()
+            .map(|(idx, bit)| {
+                chip.load_private(
+                    layouter.namespace(|| format!("b_{}", idx)),
+                    column,
+                    Some(pallas::Base::from(bit)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result = chip.mul_fixed_from_bits(layouter.namespace(|| "[bits]B"), &bits, &base)?;
+
+        // Compare against the same value as a base field element.
+        let expected = {
+            let scalar = chip.load_private(
+                layouter.namespace(|| "recombined scalar"),
+                column,
+                Some(scalar_val),
+            )?;
+            chip.mul_fixed_base_field_elem(&mut layouter, scalar, &base)?
+        };
+        assert_eq!(result.point(), expected.point());
+        chip.constrain_equal(&mut layouter, &result, &expected)
+    }
+
     #[allow(clippy::op_ref)]
     fn test_single_base<F: FixedPoints<pallas::Affine>>(
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
-        base: FixedPoint<pallas::Affine, EccChip<F>>,
+        base: FixedPoint<pallas::Affine, EccChip<pallas::Affine, F>>,
         base_val: pallas::Affine,
     ) -> Result<(), Error> {
         let column = chip.config().advices[0];
 
         fn constrain_equal_non_id<F: FixedPoints<pallas::Affine>>(
-            chip: EccChip<F>,
+            chip: EccChip<pallas::Affine, F>,
             mut layouter: impl Layouter<pallas::Base>,
             base_val: pallas::Affine,
             scalar_val: pallas::Base,
-            result: Point<pallas::Affine, EccChip<F>>,
+            result: Point<pallas::Affine, EccChip<pallas::Affine, F>>,
         ) -> Result<(), Error> {
             // Move scalar from base field into scalar field (which always fits for Pallas).
             let scalar = pallas::Scalar::from_bytes(&scalar_val.to_bytes()).unwrap();
@@ -438,7 +484,8 @@ pub mod tests {
                 layouter.namespace(|| "expected point"),
                 Some((base_val * scalar).to_affine()),
             )?;
-            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)
+            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)?;
+            Ok(())
         }
 
         // [a]B
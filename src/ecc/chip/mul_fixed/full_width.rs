@@ -1,33 +1,56 @@
 use super::super::{
-    EccConfig, EccPoint, EccScalarFixed, FixedPoints, FIXED_BASE_WINDOW_SIZE, H, L_PALLAS_SCALAR,
-    NUM_WINDOWS,
+    witness_point, EccConfig, EccCurve, EccPoint, EccScalarFixed, FixedPoints, NonIdentityEccPoint,
+    SelectorUsage, FIXED_BASE_WINDOW_SIZE, H, NUM_WINDOWS,
 };
 
-use crate::utilities::{decompose_word, range_check, CellValue, Var};
+use crate::{
+    primitives::sinsemilla,
+    utilities::{
+        copy, decompose_word, lookup_range_check::LookupRangeCheckConfig, range_check, CellValue,
+        Var,
+    },
+};
 use arrayvec::ArrayVec;
+use ff::{Field, PrimeFieldBits};
+use group::Curve;
 use halo2::{
     circuit::{Layouter, Region},
-    plonk::{ConstraintSystem, Error, Selector},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
-use pasta_curves::{arithmetic::FieldExt, pallas};
+use pasta_curves::arithmetic::{CurveAffine, FieldExt};
 
-pub struct Config<Fixed: FixedPoints<pallas::Affine>> {
+pub struct Config<C: EccCurve, Fixed: FixedPoints<C>> {
     q_mul_fixed_full: Selector,
-    super_config: super::Config<Fixed, NUM_WINDOWS>,
+    // Selector for the recomposition of the windows into the scalar
+    q_mul_fixed_full_recompose: Selector,
+    // Running sum z_i = k_i + 8⋅z_{i+1} of the windows
+    recompose_z: Column<Advice>,
+    // Configuration for range-checking the most significant window of a bounded scalar
+    lookup_config: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    // Configuration for loading constant points
+    witness_point_config: witness_point::Config<C>,
+    super_config: super::Config<C, Fixed, NUM_WINDOWS>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> From<&EccConfig> for Config<Fixed> {
-    fn from(config: &EccConfig) -> Self {
+impl<C: EccCurve, Fixed: FixedPoints<C>> From<&EccConfig<C>> for Config<C, Fixed> {
+    fn from(config: &EccConfig<C>) -> Self {
         Self {
             q_mul_fixed_full: config.q_mul_fixed_full,
+            q_mul_fixed_full_recompose: config.q_mul_fixed_full_recompose,
+            recompose_z: config.advices[5],
+            lookup_config: config.lookup_config.clone(),
+            witness_point_config: config.into(),
             super_config: config.into(),
+            usage: config.usage.clone(),
         }
     }
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
-    pub fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+impl<C: EccCurve, Fixed: FixedPoints<C>> Config<C, Fixed> {
+    pub fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         // Check that each window `k` is within 3 bits
         meta.create_gate("Full-width fixed-base scalar mul", |meta| {
             let q_mul_fixed_full = meta.query_selector(self.q_mul_fixed_full);
@@ -45,16 +68,31 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
         });
     }
 
+    pub fn create_recompose_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("Full-width scalar recomposition", |meta| {
+            let q_recompose = meta.query_selector(self.q_mul_fixed_full_recompose);
+            let window = meta.query_advice(self.super_config.window, Rotation::cur());
+            let z_cur = meta.query_advice(self.recompose_z, Rotation::cur());
+            let z_next = meta.query_advice(self.recompose_z, Rotation::next());
+
+            // z_i = k_i + 2^3⋅z_{i+1}
+            vec![(
+                "recompose",
+                q_recompose * (z_cur - (window + z_next * C::Base::from_u64(H as u64))),
+            )]
+        });
+    }
+
     /// Witnesses the given scalar as `NUM_WINDOWS` 3-bit windows.
     ///
     /// The scalar is allowed to be non-canonical.
     fn witness(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        scalar: Option<pallas::Scalar>,
-    ) -> Result<EccScalarFixed, Error> {
-        let windows = self.decompose_scalar_fixed::<L_PALLAS_SCALAR>(scalar, offset, region)?;
+        scalar: Option<C::Scalar>,
+    ) -> Result<EccScalarFixed<C>, Error> {
+        let windows = self.decompose_scalar_fixed(scalar, offset, region)?;
 
         Ok(EccScalarFixed {
             value: scalar,
@@ -65,30 +103,30 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
     /// Witnesses the given scalar as `NUM_WINDOWS` 3-bit windows.
     ///
     /// The scalar is allowed to be non-canonical.
-    fn decompose_scalar_fixed<const SCALAR_NUM_BITS: usize>(
+    fn decompose_scalar_fixed(
         &self,
-        scalar: Option<pallas::Scalar>,
+        scalar: Option<C::Scalar>,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<ArrayVec<CellValue<pallas::Base>, NUM_WINDOWS>, Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<ArrayVec<CellValue<C::Base>, NUM_WINDOWS>, Error> {
         // Enable `q_mul_fixed_full` selector
         for idx in 0..NUM_WINDOWS {
-            self.q_mul_fixed_full.enable(region, offset + idx)?;
+            self.usage
+                .enable(&self.q_mul_fixed_full, region, offset + idx)?;
         }
 
         // Decompose scalar into `k-bit` windows
-        let scalar_windows: Option<Vec<u8>> = scalar.map(|scalar| {
-            decompose_word::<pallas::Scalar>(scalar, SCALAR_NUM_BITS, FIXED_BASE_WINDOW_SIZE)
-        });
+        let scalar_windows: Option<Vec<u8>> = scalar
+            .map(|scalar| decompose_word::<C::Scalar>(scalar, C::L_SCALAR, FIXED_BASE_WINDOW_SIZE));
 
         // Store the scalar decomposition
-        let mut windows: ArrayVec<CellValue<pallas::Base>, NUM_WINDOWS> = ArrayVec::new();
+        let mut windows: ArrayVec<CellValue<C::Base>, NUM_WINDOWS> = ArrayVec::new();
 
-        let scalar_windows: Vec<Option<pallas::Base>> = if let Some(windows) = scalar_windows {
+        let scalar_windows: Vec<Option<C::Base>> = if let Some(windows) = scalar_windows {
             assert_eq!(windows.len(), NUM_WINDOWS);
             windows
                 .into_iter()
-                .map(|window| Some(pallas::Base::from_u64(window as u64)))
+                .map(|window| Some(C::Base::from_u64(window as u64)))
                 .collect()
         } else {
             vec![None; NUM_WINDOWS]
@@ -107,33 +145,451 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
         Ok(windows)
     }
 
+    /// Copies the windows of an already-witnessed scalar, constraining them
+    /// to be equal to the original cells.
+    fn copy_scalar_fixed(
+        &self,
+        region: &mut Region<'_, C::Base>,
+        offset: usize,
+        scalar: &EccScalarFixed<C>,
+    ) -> Result<EccScalarFixed<C>, Error> {
+        // Enable `q_mul_fixed_full` selector
+        for idx in 0..NUM_WINDOWS {
+            self.usage
+                .enable(&self.q_mul_fixed_full, region, offset + idx)?;
+        }
+
+        let mut windows: ArrayVec<CellValue<C::Base>, NUM_WINDOWS> = ArrayVec::new();
+        for (idx, window) in scalar.windows.iter().enumerate() {
+            windows.push(copy(
+                region,
+                || format!("k[{:?}]", offset + idx),
+                self.super_config.window,
+                offset + idx,
+                window,
+            )?);
+        }
+
+        Ok(EccScalarFixed {
+            value: scalar.value,
+            windows,
+        })
+    }
+
     pub fn assign(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        scalar: Option<pallas::Scalar>,
+        layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
+        base: &Fixed,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>), Error> {
+        self.assign_inner(
+            layouter,
+            |region, offset| self.witness(region, offset, scalar),
+            base,
+            None,
+        )
+    }
+
+    /// Returns `[scalar]B`, reusing the windows of an already-witnessed scalar.
+    pub fn assign_with_scalar(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        scalar: &EccScalarFixed<C>,
+        base: &Fixed,
+    ) -> Result<EccPoint<C>, Error> {
+        self.assign_inner(
+            layouter,
+            |region, offset| self.copy_scalar_fixed(region, offset, scalar),
+            base,
+            None,
+        )
+        .map(|(result, _)| result)
+    }
+
+    /// As [`Config::assign`], additionally returning the value of the
+    /// accumulator after each window. The last element of the trace is the
+    /// result `[scalar]B`.
+    #[cfg(any(test, feature = "debug-checks"))]
+    #[allow(clippy::type_complexity)]
+    pub fn assign_with_trace(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
         base: &Fixed,
-    ) -> Result<(EccPoint, EccScalarFixed), Error> {
-        let (scalar, acc, mul_b) = layouter.assign_region(
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>, Vec<EccPoint<C>>), Error> {
+        let mut trace = vec![];
+        let (result, scalar) = self.assign_inner(
+            layouter,
+            |region, offset| self.witness(region, offset, scalar),
+            base,
+            Some(&mut trace),
+        )?;
+        Ok((result, scalar, trace))
+    }
+
+    /// Constrains `windows` to recompose to the value of `scalar`, returning
+    /// the cell holding that value.
+    ///
+    /// The windows are recombined as $\sum_i k_i \cdot (2^3)^i$ over the base
+    /// field, so the returned cell holds the integer representation of `scalar`
+    /// reduced modulo the base field modulus. The binding is only meaningful if
+    /// the returned cell is itself constrained by the caller.
+    pub fn recompose(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        windows: &[CellValue<C::Base>],
+        scalar: Option<C::Scalar>,
+    ) -> Result<CellValue<C::Base>, Error> {
+        assert_eq!(windows.len(), NUM_WINDOWS);
+
+        // The integer representation of the scalar, reduced into the base field.
+        let scalar = scalar.map(|scalar| {
+            let mut bytes = [0u8; 64];
+            bytes[..32].copy_from_slice(&scalar.to_bytes());
+            C::Base::from_bytes_wide(&bytes)
+        });
+
+        self.usage.assign_region(
+            &mut layouter,
+            || "Full-width scalar recomposition",
+            |mut region| {
+                // z_{NUM_WINDOWS} = 0
+                let zero = C::Base::zero();
+                region.assign_advice_from_constant(
+                    || format!("z_{}", NUM_WINDOWS),
+                    self.recompose_z,
+                    NUM_WINDOWS,
+                    zero,
+                )?;
+
+                let mut z = Some(zero);
+                for (idx, window) in windows.iter().enumerate().rev() {
+                    self.usage
+                        .enable(&self.q_mul_fixed_full_recompose, &mut region, idx)?;
+                    copy(
+                        &mut region,
+                        || format!("k_{}", idx),
+                        self.super_config.window,
+                        idx,
+                        window,
+                    )?;
+
+                    // z_i = k_i + 2^3⋅z_{i+1}
+                    z = z
+                        .zip(window.value())
+                        .map(|(z, k)| k + z * C::Base::from_u64(H as u64));
+                    if idx > 0 {
+                        region.assign_advice(
+                            || format!("z_{}", idx),
+                            self.recompose_z,
+                            idx,
+                            || z.ok_or(Error::SynthesisError),
+                        )?;
+                    }
+                }
+
+                // z_0 is witnessed from the scalar, rather than from the windows.
+                let z_0 = region.assign_advice(
+                    || "z_0 = scalar",
+                    self.recompose_z,
+                    0,
+                    || scalar.ok_or(Error::SynthesisError),
+                )?;
+                Ok(CellValue::new(z_0, scalar))
+            },
+        )
+    }
+
+    /// Returns `[scalar]B` for the pair of bases `g`, `h` and scalars `a`, `b`,
+    /// along with the witnessed scalars, as `[a]G + [b]H`.
+    ///
+    /// The windows of each scalar are processed in their own region, since the
+    /// fixed-base tables of one base occupy the fixed columns of every row
+    /// they use. The two accumulators and the last window of `G` are combined
+    /// using incomplete addition, so that only the last window of `H` is added
+    /// using complete addition. This takes `2 * NUM_WINDOWS + 6` rows, the same
+    /// as two calls to [`Config::assign`] followed by a complete addition; it
+    /// replaces two of the three complete additions with incomplete additions.
+    ///
+    /// The incomplete additions leave their output unconstrained if the
+    /// accumulators share an x-coordinate, which a prover can arrange if `G`
+    /// and `H` are related. Returns [`Error::SynthesisError`] if `G = ±H`;
+    /// otherwise `G` and `H` must have no known discrete logarithm relation.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_pair(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        a: Option<C::Scalar>,
+        g: &Fixed,
+        b: Option<C::Scalar>,
+        h: &Fixed,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>, EccScalarFixed<C>), Error> {
+        // Fixed bases are never the identity, so they have coordinates.
+        let x = |base: &Fixed| *base.generator().coordinates().unwrap().x();
+        if x(g) == x(h) {
+            return Err(Error::SynthesisError);
+        }
+
+        let (a, acc_g, mul_g, _) = self.assign_incomplete(
+            layouter.namespace(|| "[a]G"),
+            |region, offset| self.witness(region, offset, a),
+            g,
+            false,
+        )?;
+        let (b, acc_h, mul_h, _) = self.assign_incomplete(
+            layouter.namespace(|| "[b]H"),
+            |region, offset| self.witness(region, offset, b),
+            h,
+            false,
+        )?;
+
+        // Combine the accumulators, and the last window of `G`, using incomplete addition.
+        let acc = self.usage.assign_region(
+            &mut layouter,
+            || "[a]G + [b]H (incomplete addition)",
+            |mut region| {
+                let add_incomplete = &self.super_config.add_incomplete_config;
+                let acc = add_incomplete.assign_region(&acc_g, &acc_h, 0, &mut region)?;
+                add_incomplete.assign_region(&acc, &mul_g, 2, &mut region)
+            },
+        )?;
+
+        // Add the last window of `H` using complete addition.
+        let result = self.usage.assign_region(
+            &mut layouter,
+            || "[a]G + [b]H (last window, complete addition)",
+            |mut region| {
+                self.super_config.add_config.assign_region(
+                    &mul_h.into(),
+                    &acc.into(),
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+
+        #[cfg(any(test, feature = "debug-checks"))]
+        // Check that the correct multiple is obtained.
+        {
+            let real_mul = a
+                .value
+                .zip(b.value)
+                .map(|(a, b)| g.generator() * a + h.generator() * b);
+            let result = result.point();
+
+            super::super::debug_check_point(real_mul, result);
+        }
+
+        Ok((result, a, b))
+    }
+
+    /// Returns `[scalar]B` for a scalar of at most `max_bits` bits, along with
+    /// the cells containing its 3-bit windows in little-endian order.
+    ///
+    /// Only `ceil(max_bits / 3)` windows are laid out. Every window `w` is
+    /// added to the accumulator as `[(k_w + 2) ⋅ 8^w]B` using incomplete
+    /// addition, and the constant `[-\sum_w 2 ⋅ 8^w]B` is then added using
+    /// complete addition. If `max_bits` is not a multiple of 3, the most
+    /// significant window is additionally range-checked to the remaining bits.
+    ///
+    /// Returns an error if the scalar does not fit in `max_bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bits` is zero, or if it needs all `NUM_WINDOWS` windows.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_bounded(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: Option<C::Scalar>,
+        max_bits: usize,
+        base: &Fixed,
+    ) -> Result<(EccPoint<C>, Vec<CellValue<C::Base>>), Error> {
+        let num_windows = (max_bits + FIXED_BASE_WINDOW_SIZE - 1) / FIXED_BASE_WINDOW_SIZE;
+        assert!(num_windows > 0 && num_windows < NUM_WINDOWS);
+
+        // Return an error if the scalar does not fit in `max_bits` bits.
+        if let Some(scalar) = scalar {
+            if scalar.to_le_bits().into_iter().skip(max_bits).any(|b| b) {
+                return Err(Error::SynthesisError);
+            }
+        }
+
+        let (acc, windows) = self.usage.assign_region(
+            &mut layouter,
+            || "Bounded full-width fixed-base mul (incomplete addition)",
+            |mut region| {
+                let offset = 0;
+
+                // Assign fixed columns for the windows that are used
+                self.super_config.assign_fixed_constants_for_windows(
+                    &mut region,
+                    offset,
+                    base,
+                    self.q_mul_fixed_full,
+                    num_windows,
+                )?;
+
+                // Decompose scalar into `k-bit` windows
+                let scalar_windows: Vec<Option<u8>> = if let Some(scalar) = scalar {
+                    decompose_word::<C::Scalar>(scalar, max_bits, FIXED_BASE_WINDOW_SIZE)
+                        .into_iter()
+                        .map(Some)
+                        .collect()
+                } else {
+                    vec![None; num_windows]
+                };
+                assert_eq!(scalar_windows.len(), num_windows);
+
+                let mut acc: Option<NonIdentityEccPoint<C>> = None;
+                let mut windows = Vec::with_capacity(num_windows);
+                for (w, window) in scalar_windows.into_iter().enumerate() {
+                    let window_val = window.map(|k| C::Base::from_u64(k as u64));
+                    let window_cell = region.assign_advice(
+                        || format!("k[{:?}]", offset + w),
+                        self.super_config.window,
+                        offset + w,
+                        || window_val.ok_or(Error::SynthesisError),
+                    )?;
+                    windows.push(CellValue::new(window_cell, window_val));
+
+                    // Compute [(k_w + 2) ⋅ 8^w]B
+                    let mul_b = self.super_config.process_window(
+                        &mut region,
+                        offset,
+                        w,
+                        window.map(|k| C::Scalar::from_u64(k as u64)),
+                        window.map(|k| k as usize),
+                        base,
+                    )?;
+
+                    // Add to the accumulator
+                    acc = Some(match acc {
+                        None => mul_b,
+                        Some(acc) => self.super_config.add_incomplete_config.assign_region(
+                            &mul_b,
+                            &acc,
+                            offset + w,
+                            &mut region,
+                        )?,
+                    });
+                }
+
+                Ok((acc.unwrap(), windows))
+            },
+        )?;
+
+        // offset_acc = \sum_{j = 0}^{num_windows - 1} 2^{FIXED_BASE_WINDOW_SIZE*j + 1}
+        let offset_acc = (0..num_windows).fold(C::Scalar::zero(), |acc, w| {
+            acc + C::Scalar::from_u64(2).pow(&[
+                FIXED_BASE_WINDOW_SIZE as u64 * w as u64 + 1,
+                0,
+                0,
+                0,
+            ])
+        });
+        let offset_point = (base.generator() * -offset_acc).to_affine();
+
+        // Subtract the offset and return the final result as `[scalar]B`.
+        let result = self.usage.assign_region(
+            &mut layouter,
+            || "Bounded full-width fixed-base mul (offset, complete addition)",
+            |mut region| {
+                let offset_point =
+                    self.witness_point_config
+                        .constant_point(offset_point, 0, &mut region)?;
+                self.super_config.add_config.assign_region(
+                    &offset_point,
+                    &acc.into(),
+                    1,
+                    &mut region,
+                )
+            },
+        )?;
+
+        // Range-check the most significant window to the remaining bits.
+        let top_bits = max_bits % FIXED_BASE_WINDOW_SIZE;
+        if top_bits != 0 {
+            self.lookup_config.copy_short_check(
+                layouter.namespace(|| "most significant window"),
+                windows[num_windows - 1],
+                top_bits,
+            )?;
+        }
+
+        #[cfg(any(test, feature = "debug-checks"))]
+        // Check that the correct multiple is obtained.
+        {
+            let real_mul = scalar.map(|scalar| base.generator() * scalar);
+            let result = result.point();
+
+            super::super::debug_check_point(real_mul, result);
+        }
+
+        Ok((result, windows))
+    }
+
+    /// Witnesses the windows of a scalar and accumulates all but the last
+    /// window of `[scalar]B` using incomplete addition, returning the scalar,
+    /// the accumulator, and the multiple of `B` for the last window.
+    ///
+    /// If `trace` is set, the accumulator after each window is also returned.
+    #[allow(clippy::type_complexity)]
+    fn assign_incomplete(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: impl Fn(&mut Region<'_, C::Base>, usize) -> Result<EccScalarFixed<C>, Error>,
+        base: &Fixed,
+        trace: bool,
+    ) -> Result<
+        (
+            EccScalarFixed<C>,
+            NonIdentityEccPoint<C>,
+            NonIdentityEccPoint<C>,
+            Vec<NonIdentityEccPoint<C>>,
+        ),
+        Error,
+    > {
+        self.usage.assign_region(
+            &mut layouter,
             || "Full-width fixed-base mul (incomplete addition)",
             |mut region| {
                 let offset = 0;
 
-                let scalar = self.witness(&mut region, offset, scalar)?;
+                let scalar = scalar(&mut region, offset)?;
 
-                let (acc, mul_b) = self.super_config.assign_region_inner(
+                let mut acc_trace = vec![];
+                let (acc, mul_b) = self.super_config.assign_region_inner_with_trace(
                     &mut region,
                     offset,
                     &(&scalar).into(),
                     base,
                     self.q_mul_fixed_full,
+                    if trace { Some(&mut acc_trace) } else { None },
                 )?;
 
-                Ok((scalar, acc, mul_b))
+                Ok((scalar, acc, mul_b, acc_trace))
             },
+        )
+    }
+
+    fn assign_inner(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalar: impl Fn(&mut Region<'_, C::Base>, usize) -> Result<EccScalarFixed<C>, Error>,
+        base: &Fixed,
+        mut trace: Option<&mut Vec<EccPoint<C>>>,
+    ) -> Result<(EccPoint<C>, EccScalarFixed<C>), Error> {
+        let (scalar, acc, mul_b, acc_trace) = self.assign_incomplete(
+            layouter.namespace(|| "incomplete addition"),
+            scalar,
+            base,
+            trace.is_some(),
         )?;
 
         // Add to the accumulator and return the final result as `[scalar]B`.
-        let result = layouter.assign_region(
+        let result = self.usage.assign_region(
+            &mut layouter,
             || "Full-width fixed-base mul (last window, complete addition)",
             |mut region| {
                 self.super_config.add_config.assign_region(
@@ -145,17 +601,18 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             },
         )?;
 
-        #[cfg(test)]
+        if let Some(trace) = trace.as_mut() {
+            trace.extend(acc_trace.into_iter().map(EccPoint::from));
+            trace.push(result);
+        }
+
+        #[cfg(any(test, feature = "debug-checks"))]
         // Check that the correct multiple is obtained.
         {
-            use group::Curve;
-
             let real_mul = scalar.value.map(|scalar| base.generator() * scalar);
             let result = result.point();
 
-            if let (Some(real_mul), Some(result)) = (real_mul, result) {
-                assert_eq!(real_mul.to_affine(), result);
-            }
+            super::super::debug_check_point(real_mul, result);
         }
 
         Ok((result, scalar))
@@ -164,54 +621,300 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
 
 #[cfg(test)]
 pub mod tests {
-    use group::Curve;
-    use halo2::{circuit::Layouter, plonk::Error};
+    use group::{prime::PrimeCurveAffine, Curve};
+    use halo2::{
+        circuit::{Chip, Layouter},
+        plonk::Error,
+    };
     use pasta_curves::{arithmetic::FieldExt, pallas};
 
-    use crate::ecc::{chip::EccChip, FixedPoint, FixedPoints, NonIdentityPoint, Point, H};
+    use crate::ecc::{
+        chip::{EccChip, NUM_WINDOWS},
+        FixedPoint, FixedPoints, NonIdentityPoint, Point, H,
+    };
 
     pub fn test_mul_fixed<F: FixedPoints<pallas::Affine>>(
         base: F,
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
     ) -> Result<(), Error> {
         test_single_base(
             chip.clone(),
             layouter.namespace(|| "fixed base"),
-            FixedPoint::from_inner(chip, base.clone()),
+            FixedPoint::from_inner(chip.clone(), base.clone()),
             base.generator(),
         )?;
 
+        // The accumulator trace has one element per window, the last of
+        // which is the result [a]B.
+        {
+            let scalar_fixed = pallas::Scalar::rand();
+            let (result, _, trace) = chip.mul_fixed_with_trace(
+                layouter.namespace(|| "[a]B with trace"),
+                Some(scalar_fixed),
+                &base,
+            )?;
+            assert_eq!(trace.len(), NUM_WINDOWS);
+            let last = trace.last().unwrap();
+            assert_eq!(last.point(), result.point());
+            assert_eq!(
+                last.point(),
+                Some((base.generator() * scalar_fixed).to_affine())
+            );
+        }
+
+        // [a]B for a secret a matches the multiplication by its value.
+        {
+            use crate::utilities::SecretScalar;
+
+            let fixed = FixedPoint::from_inner(chip.clone(), base.clone());
+            let secret = SecretScalar::new(Some(pallas::Scalar::rand()));
+            let (result, _) = fixed.mul_secret(layouter.namespace(|| "secret [a]B"), &secret)?;
+            assert_eq!(
+                result.inner().point(),
+                secret
+                    .value()
+                    .map(|scalar| (base.generator() * scalar).to_affine())
+            );
+        }
+
+        // [c]B for a constant c matches the windowed multiplication.
+        {
+            let fixed = FixedPoint::from_inner(chip.clone(), base.clone());
+            let scalar_fixed = pallas::Scalar::rand();
+            let (expected, _) =
+                fixed.mul(layouter.namespace(|| "windowed [c]B"), Some(scalar_fixed))?;
+            let result = fixed.mul_const(layouter.namespace(|| "constant [c]B"), scalar_fixed)?;
+            assert_eq!(result.inner().point(), expected.inner().point());
+            result.constrain_equal(layouter.namespace(|| "constant [c]B = [c]B"), &expected)?;
+
+            let zero = fixed.mul_const(
+                layouter.namespace(|| "constant [0]B"),
+                pallas::Scalar::zero(),
+            )?;
+            assert!(zero.inner().is_identity().unwrap());
+        }
+
+        // The value of a canonical scalar can be bound to its windows.
+        {
+            use crate::utilities::{UtilitiesInstructions, Var};
+
+            let fixed = FixedPoint::from_inner(chip.clone(), base.clone());
+            // Any base field element is a canonical scalar.
+            let value = pallas::Base::rand();
+            let scalar_fixed = pallas::Scalar::from_bytes(&value.to_bytes()).unwrap();
+            let (_, scalar) = fixed.mul(layouter.namespace(|| "[a]B"), Some(scalar_fixed))?;
+
+            let bound = scalar.bind_value(layouter.namespace(|| "bind a"))?;
+            assert_eq!(bound.value(), Some(value));
+
+            let expected = chip.load_private(
+                layouter.namespace(|| "expected a"),
+                chip.config().advices[0],
+                Some(value),
+            )?;
+            layouter.assign_region(
+                || "a == expected",
+                |mut region| region.constrain_equal(bound.cell(), expected.cell()),
+            )?;
+        }
+
+        // A single mul_fixed adds its enabled rows to the selector usage.
+        #[cfg(feature = "dev")]
+        {
+            use crate::ecc::EccInstructions;
+
+            let config = chip.config();
+            let selectors = [
+                config.q_mul_fixed_full,
+                config.q_add_incomplete,
+                config.q_add,
+            ];
+            let counts = || {
+                let usage = chip.selector_usage();
+                selectors
+                    .iter()
+                    .map(|selector| usage.get(selector).copied().unwrap_or(0))
+                    .collect::<Vec<_>>()
+            };
+            let before = counts();
+
+            chip.mul_fixed(
+                &mut layouter.namespace(|| "[a]B with selector usage"),
+                Some(pallas::Scalar::rand()),
+                &base,
+            )?;
+            let after = counts();
+            assert_eq!(after[0] - before[0], NUM_WINDOWS);
+            assert_eq!(after[1] - before[1], NUM_WINDOWS - 2);
+            assert_eq!(after[2] - before[2], 1);
+        }
+
+        Ok(())
+    }
+
+    pub fn test_mul_with_scalar<F: FixedPoints<pallas::Affine>>(
+        bases: [F; 2],
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let base_0 = FixedPoint::from_inner(chip.clone(), bases[0].clone());
+        let base_1 = FixedPoint::from_inner(chip.clone(), bases[1].clone());
+
+        // Witness the scalar once as part of [a]B_0, and reuse it for [a]B_1.
+        let scalar_val = pallas::Scalar::rand();
+        let (result_0, scalar) = base_0.mul(layouter.namespace(|| "[a]B_0"), Some(scalar_val))?;
+        let result_1 = base_1.mul_with_scalar(layouter.namespace(|| "[a]B_1"), &scalar)?;
+
+        for (idx, (result, base)) in [result_0, result_1].iter().zip(bases.iter()).enumerate() {
+            let expected = NonIdentityPoint::new(
+                chip.clone(),
+                layouter.namespace(|| format!("expected [a]B_{}", idx)),
+                Some((base.generator() * scalar_val).to_affine()),
+            )?;
+            result.constrain_equal(
+                layouter.namespace(|| format!("constrain [a]B_{}", idx)),
+                &expected,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_mul_fixed_pair<F: FixedPoints<pallas::Affine>>(
+        bases: [F; 2],
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let a_val = pallas::Scalar::rand();
+        let b_val = pallas::Scalar::rand();
+        let (result, a, b) = chip.mul_fixed_pair(
+            layouter.namespace(|| "[a]G + [b]H"),
+            Some(a_val),
+            &bases[0],
+            Some(b_val),
+            &bases[1],
+        )?;
+        assert_eq!(a.value(), Some(a_val));
+        assert_eq!(b.value(), Some(b_val));
+
+        let expected = NonIdentityPoint::new(
+            chip.clone(),
+            layouter.namespace(|| "expected [a]G + [b]H"),
+            Some((bases[0].generator() * a_val + bases[1].generator() * b_val).to_affine()),
+        )?;
+        Point::from_inner(chip.clone(), result)
+            .constrain_equal(layouter.namespace(|| "constrain [a]G + [b]H"), &expected)?;
+
+        // The same base cannot be used twice.
+        let err = chip
+            .mul_fixed_pair(
+                layouter.namespace(|| "[a]G + [b]G"),
+                Some(a_val),
+                &bases[0],
+                Some(b_val),
+                &bases[0],
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::SynthesisError));
+
+        Ok(())
+    }
+
+    pub fn test_mul_fixed_bounded<F: FixedPoints<pallas::Affine>>(
+        base: F,
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        const MAX_BITS: usize = 200;
+
+        // A random 200-bit scalar
+        let scalar_val = {
+            let mut bytes = pallas::Scalar::rand().to_bytes();
+            for byte in bytes[(MAX_BITS / 8)..].iter_mut() {
+                *byte = 0;
+            }
+            pallas::Scalar::from_bytes(&bytes).unwrap()
+        };
+
+        #[cfg(feature = "dev")]
+        let before = chip
+            .selector_usage()
+            .get(&chip.config().q_mul_fixed_full)
+            .copied()
+            .unwrap_or(0);
+
+        let (result, windows) = chip.mul_fixed_bounded(
+            layouter.namespace(|| "bounded [a]B"),
+            Some(scalar_val),
+            MAX_BITS,
+            &base,
+        )?;
+
+        // Only ceil(200 / 3) = 67 windows are laid out.
+        assert_eq!(windows.len(), 67);
+        assert!(windows.len() < NUM_WINDOWS);
+        #[cfg(feature = "dev")]
+        assert_eq!(
+            chip.selector_usage()[&chip.config().q_mul_fixed_full] - before,
+            67
+        );
+
+        let expected = NonIdentityPoint::new(
+            chip.clone(),
+            layouter.namespace(|| "expected bounded [a]B"),
+            Some((base.generator() * scalar_val).to_affine()),
+        )?;
+        Point::from_inner(chip.clone(), result)
+            .constrain_equal(layouter.namespace(|| "constrain bounded [a]B"), &expected)?;
+
+        // A scalar that does not fit in 200 bits should return an error.
+        chip.mul_fixed_bounded(
+            layouter.namespace(|| "bounded [2^200]B"),
+            Some({
+                let mut bytes = [0u8; 32];
+                bytes[MAX_BITS / 8] = 1;
+                pallas::Scalar::from_bytes(&bytes).unwrap()
+            }),
+            MAX_BITS,
+            &base,
+        )
+        .expect_err("a scalar wider than max_bits should return an error");
+
         Ok(())
     }
 
     #[allow(clippy::op_ref)]
     fn test_single_base<F: FixedPoints<pallas::Affine>>(
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
-        base: FixedPoint<pallas::Affine, EccChip<F>>,
+        base: FixedPoint<pallas::Affine, EccChip<pallas::Affine, F>>,
         base_val: pallas::Affine,
     ) -> Result<(), Error> {
         fn constrain_equal_non_id<F: FixedPoints<pallas::Affine>>(
-            chip: EccChip<F>,
+            chip: EccChip<pallas::Affine, F>,
             mut layouter: impl Layouter<pallas::Base>,
             base_val: pallas::Affine,
             scalar_val: pallas::Scalar,
-            result: Point<pallas::Affine, EccChip<F>>,
+            result: Point<pallas::Affine, EccChip<pallas::Affine, F>>,
         ) -> Result<(), Error> {
             let expected = NonIdentityPoint::new(
                 chip,
                 layouter.namespace(|| "expected point"),
                 Some((base_val * scalar_val).to_affine()),
             )?;
-            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)
+            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)?;
+            Ok(())
         }
 
         // [a]B
         {
             let scalar_fixed = pallas::Scalar::rand();
 
-            let (result, _) = base.mul(layouter.namespace(|| "random [a]B"), Some(scalar_fixed))?;
+            let (result, scalar) =
+                base.mul(layouter.namespace(|| "random [a]B"), Some(scalar_fixed))?;
+            assert_eq!(scalar.inner().value(), Some(scalar_fixed));
+            assert_eq!(scalar.inner().windows().len(), NUM_WINDOWS);
             constrain_equal_non_id(
                 chip.clone(),
                 layouter.namespace(|| "random [a]B"),
@@ -221,6 +924,26 @@ pub mod tests {
             )?;
         }
 
+        // [a]B + [-a]B = 𝒪
+        {
+            let scalar_fixed = pallas::Scalar::rand();
+
+            let (result, scalar) =
+                base.mul(layouter.namespace(|| "random [a]B"), Some(scalar_fixed))?;
+            let (negated, negated_result) =
+                scalar.negate(layouter.namespace(|| "[-a]B"), &base, &result)?;
+            assert_eq!(negated.inner().value(), Some(-scalar_fixed));
+            assert_eq!(negated.inner().windows().len(), NUM_WINDOWS);
+
+            let sum = result.add(layouter.namespace(|| "[a]B + [-a]B"), &negated_result)?;
+            let identity = Point::new(
+                chip.clone(),
+                layouter.namespace(|| "identity"),
+                Some(pallas::Affine::identity()),
+            )?;
+            sum.constrain_equal(layouter.namespace(|| "[a]B + [-a]B = 𝒪"), &identity)?;
+        }
+
         // There is a single canonical sequence of window values for which a doubling occurs on the last step:
         // 1333333333333333333333333333333333333333333333333333333333333333333333333333333333334 in octal.
         // (There is another *non-canonical* sequence
@@ -267,4 +990,235 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn mul_fixed_negated_is_bound() {
+        use crate::ecc::{
+            chip::{EccChip, EccConfig},
+            tests::{FixedBase, MyCircuit},
+            FixedPoint,
+        };
+        use ff::Field;
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+
+        #[derive(Default)]
+        struct NegateCircuit {
+            scalar: Option<pallas::Scalar>,
+            // The scalar whose product is passed as `[scalar] B`
+            product_scalar: Option<pallas::Scalar>,
+        }
+
+        impl Circuit<pallas::Base> for NegateCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                MyCircuit::<FixedBase>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config);
+                let base = FixedPoint::from_inner(chip, FixedBase::FullWidth);
+
+                let (_, scalar) = base.mul(layouter.namespace(|| "[a]B"), self.scalar)?;
+                let (product, _) =
+                    base.mul(layouter.namespace(|| "product"), self.product_scalar)?;
+                scalar.negate(layouter.namespace(|| "[-a]B"), &base, &product)?;
+
+                Ok(())
+            }
+        }
+
+        // The product of the scalar being negated should pass
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = NegateCircuit {
+                scalar: Some(scalar),
+                product_scalar: Some(scalar),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // The product of a different scalar should fail
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = NegateCircuit {
+                scalar: Some(scalar),
+                product_scalar: Some(scalar + pallas::Scalar::one()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn recompose_tampered_windows() {
+        use crate::ecc::{
+            chip::{EccChip, EccConfig},
+            tests::FixedBase,
+            EccInstructions,
+        };
+        use ff::Field;
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+
+        #[derive(Default)]
+        struct MyCircuit {
+            // The scalar that is decomposed into windows
+            scalar: Option<pallas::Scalar>,
+            // The scalar that the windows are claimed to recompose to
+            claimed: Option<pallas::Scalar>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config);
+
+                if self.scalar == self.claimed {
+                    chip.mul_fixed_checked(
+                        layouter.namespace(|| "[a]B"),
+                        self.scalar,
+                        &FixedBase::FullWidth,
+                    )?;
+                } else {
+                    let (_, scalar) =
+                        chip.mul_fixed(&mut layouter, self.scalar, &FixedBase::FullWidth)?;
+                    let config: super::Config<pallas::Affine, FixedBase> = chip.config().into();
+                    config.recompose(
+                        layouter.namespace(|| "recompose"),
+                        &scalar.windows,
+                        self.claimed,
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+
+        // Windows that recompose to the scalar should pass
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = MyCircuit {
+                scalar: Some(scalar),
+                claimed: Some(scalar),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Windows of a different scalar should fail
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = MyCircuit {
+                scalar: Some(scalar),
+                claimed: Some(scalar + pallas::Scalar::one()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn constrain_scalar_fixed_equal() {
+        use crate::ecc::{
+            chip::{EccChip, EccConfig},
+            tests::FixedBase,
+            FixedPoint,
+        };
+        use ff::Field;
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+
+        #[derive(Default)]
+        struct MyCircuit {
+            a: Option<pallas::Scalar>,
+            b: Option<pallas::Scalar>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config);
+
+                // Decompose the scalars in two separate multiplications.
+                let g = FixedPoint::from_inner(chip.clone(), FixedBase::FullWidth);
+                let h = FixedPoint::from_inner(chip, FixedBase::FullWidthAlt);
+                let (_, a) = g.mul(layouter.namespace(|| "[a]G"), self.a)?;
+                let (_, b) = h.mul(layouter.namespace(|| "[b]H"), self.b)?;
+
+                a.constrain_equal(layouter.namespace(|| "a == b"), &b)?;
+                Ok(())
+            }
+        }
+
+        // The same scalar decomposed twice should pass
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = MyCircuit {
+                a: Some(scalar),
+                b: Some(scalar),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Different scalars should fail
+        {
+            let scalar = pallas::Scalar::rand();
+            let circuit = MyCircuit {
+                a: Some(scalar),
+                b: Some(scalar + pallas::Scalar::one()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
 }
@@ -1,49 +1,77 @@
-use std::{array, convert::TryInto};
+use std::array;
 
 use super::super::{
-    EccConfig, EccPoint, EccScalarFixedShort, FixedPoints, FIXED_BASE_WINDOW_SIZE, L_VALUE,
-    NUM_WINDOWS_SHORT,
+    EccConfig, EccCurve, EccPoint, EccScalarFixedShort, FixedPoints, SelectorUsage,
+    FIXED_BASE_WINDOW_SIZE,
 };
 use crate::utilities::{copy, decompose_running_sum::RunningSumConfig, CellValue, Var};
 
+use ff::{Field, PrimeFieldBits};
 use halo2::{
     circuit::{Layouter, Region},
     plonk::{ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
-use pasta_curves::pallas;
 
+/// Configuration for fixed-base scalar mul with a short signed exponent, whose
+/// magnitude is at most `MAGNITUDE_BITS` bits.
+///
+/// `NUM_WINDOWS` must be the number of 3-bit windows needed to hold
+/// `MAGNITUDE_BITS` bits. `MAGNITUDE_BITS` must not be congruent to 2 mod 3,
+/// since the gate only range-constrains the top partial window to a single bit.
 #[derive(Clone)]
-pub struct Config<Fixed: FixedPoints<pallas::Affine>> {
+pub struct Config<
+    C: EccCurve,
+    Fixed: FixedPoints<C>,
+    const MAGNITUDE_BITS: usize,
+    const NUM_WINDOWS: usize,
+> {
     // Selector used for fixed-base scalar mul with short signed exponent.
     q_mul_fixed_short: Selector,
     q_mul_fixed_running_sum: Selector,
-    running_sum_config: RunningSumConfig<pallas::Base, { FIXED_BASE_WINDOW_SIZE }>,
-    super_config: super::Config<Fixed, NUM_WINDOWS_SHORT>,
+    running_sum_config: RunningSumConfig<C::Base, { FIXED_BASE_WINDOW_SIZE }>,
+    super_config: super::Config<C, Fixed, NUM_WINDOWS>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> From<&EccConfig> for Config<Fixed> {
-    fn from(config: &EccConfig) -> Self {
+impl<C: EccCurve, Fixed: FixedPoints<C>, const MAGNITUDE_BITS: usize, const NUM_WINDOWS: usize>
+    From<&EccConfig<C>> for Config<C, Fixed, MAGNITUDE_BITS, NUM_WINDOWS>
+{
+    fn from(config: &EccConfig<C>) -> Self {
+        assert_eq!(
+            NUM_WINDOWS,
+            (MAGNITUDE_BITS + FIXED_BASE_WINDOW_SIZE - 1) / FIXED_BASE_WINDOW_SIZE
+        );
+        assert!(MAGNITUDE_BITS % FIXED_BASE_WINDOW_SIZE != 2);
+
         Self {
             q_mul_fixed_short: config.q_mul_fixed_short,
             q_mul_fixed_running_sum: config.q_mul_fixed_running_sum,
             running_sum_config: config.running_sum_config.clone(),
             super_config: config.into(),
+            usage: config.usage.clone(),
         }
     }
 }
 
-impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
-    pub(crate) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
+impl<C: EccCurve, Fixed: FixedPoints<C>, const MAGNITUDE_BITS: usize, const NUM_WINDOWS: usize>
+    Config<C, Fixed, MAGNITUDE_BITS, NUM_WINDOWS>
+{
+    /// Index of the running sum `z_i` that holds the bits of the magnitude above
+    /// the last full window. For a valid magnitude this is either 0 or 1.
+    const LAST_WINDOW: usize = MAGNITUDE_BITS / FIXED_BASE_WINDOW_SIZE;
+
+    pub(crate) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
         meta.create_gate("Short fixed-base mul gate", |meta| {
             let q_mul_fixed_short = meta.query_selector(self.q_mul_fixed_short);
             let y_p = meta.query_advice(self.super_config.y_p, Rotation::cur());
             let y_a = meta.query_advice(self.super_config.add_config.y_qr, Rotation::cur());
-            // z_21
+            // z_{MAGNITUDE_BITS / 3}
             let last_window = meta.query_advice(self.super_config.u, Rotation::cur());
             let sign = meta.query_advice(self.super_config.window, Rotation::cur());
 
-            let one = Expression::Constant(pallas::Base::one());
+            let one = Expression::Constant(C::Base::one());
 
             // Check that last window is either 0 or 1.
             let last_window_check = last_window.clone() * (one.clone() - last_window);
@@ -71,10 +99,10 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
 
     fn decompose(
         &self,
-        region: &mut Region<'_, pallas::Base>,
+        region: &mut Region<'_, C::Base>,
         offset: usize,
-        magnitude_sign: (CellValue<pallas::Base>, CellValue<pallas::Base>),
-    ) -> Result<EccScalarFixedShort, Error> {
+        magnitude_sign: (CellValue<C::Base>, CellValue<C::Base>),
+    ) -> Result<EccScalarFixedShort<C>, Error> {
         let (magnitude, sign) = magnitude_sign;
 
         // Decompose magnitude
@@ -83,24 +111,46 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             offset,
             magnitude,
             true,
-            L_VALUE,
-            NUM_WINDOWS_SHORT,
+            MAGNITUDE_BITS,
+            NUM_WINDOWS,
         )?;
 
         Ok(EccScalarFixedShort {
             magnitude,
             sign,
-            running_sum: (*running_sum).as_slice().try_into().unwrap(),
+            running_sum: (*running_sum).clone(),
         })
     }
 
+    /// Returns [`Error::SynthesisError`] if the value of `magnitude` is known and
+    /// does not fit in `MAGNITUDE_BITS` bits.
+    ///
+    /// This lets the prover fail fast on an out-of-range magnitude. It does not
+    /// replace the range check performed in-circuit by [`Config::assign`].
+    pub(crate) fn check_magnitude(&self, magnitude: &CellValue<C::Base>) -> Result<(), Error> {
+        let in_range = magnitude.value().map_or(true, |magnitude| {
+            magnitude
+                .to_le_bits()
+                .iter()
+                .by_val()
+                .skip(MAGNITUDE_BITS)
+                .all(|bit| !bit)
+        });
+        if in_range {
+            Ok(())
+        } else {
+            Err(Error::SynthesisError)
+        }
+    }
+
     pub fn assign(
         &self,
-        mut layouter: impl Layouter<pallas::Base>,
-        magnitude_sign: (CellValue<pallas::Base>, CellValue<pallas::Base>),
+        mut layouter: impl Layouter<C::Base>,
+        magnitude_sign: (CellValue<C::Base>, CellValue<C::Base>),
         base: &Fixed,
-    ) -> Result<(EccPoint, EccScalarFixedShort), Error> {
-        let (scalar, acc, mul_b) = layouter.assign_region(
+    ) -> Result<(EccPoint<C>, EccScalarFixedShort<C>), Error> {
+        let (scalar, acc, mul_b) = self.usage.assign_region(
+            &mut layouter,
             || "Short fixed-base mul (incomplete addition)",
             |mut region| {
                 let offset = 0;
@@ -121,7 +171,8 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
         )?;
 
         // Last window
-        let result = layouter.assign_region(
+        let result = self.usage.assign_region(
+            &mut layouter,
             || "Short fixed-base mul (most significant word)",
             |mut region| {
                 let offset = 0;
@@ -148,19 +199,19 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
                 // Copy last window to `u` column.
                 // (Although the last window is not a `u` value; we are copying it into the `u`
                 // column because there is an available cell there.)
-                let z_21 = scalar.running_sum[21];
+                let last_window = scalar.running_sum[Self::LAST_WINDOW];
                 copy(
                     &mut region,
                     || "last_window",
                     self.super_config.u,
                     offset,
-                    &z_21,
+                    &last_window,
                 )?;
 
                 // Conditionally negate `y`-coordinate
                 let y_val = if let Some(sign) = sign.value() {
-                    if sign == -pallas::Base::one() {
-                        magnitude_mul.y.value().map(|y: pallas::Base| -y)
+                    if sign == -C::Base::one() {
+                        magnitude_mul.y.value().map(|y: C::Base| -y)
                     } else {
                         magnitude_mul.y.value()
                     }
@@ -169,7 +220,8 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
                 };
 
                 // Enable mul_fixed_short selector on final row
-                self.q_mul_fixed_short.enable(&mut region, offset)?;
+                self.usage
+                    .enable(&self.q_mul_fixed_short, &mut region, offset)?;
 
                 // Assign final `y` to `y_p` column and return final point
                 let y_var = region.assign_advice(
@@ -186,32 +238,30 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
             },
         )?;
 
-        #[cfg(test)]
+        #[cfg(any(test, feature = "debug-checks"))]
         // Check that the correct multiple is obtained.
-        // This inlined test is only done for valid 64-bit magnitudes
+        // This inlined test is only done for valid `MAGNITUDE_BITS`-bit magnitudes
         // and valid +/- 1 signs.
         // Invalid values result in constraint failures which are
         // tested at the circuit-level.
         {
-            use group::Curve;
             use pasta_curves::arithmetic::FieldExt;
 
             if let (Some(magnitude), Some(sign)) = (scalar.magnitude.value(), scalar.sign.value()) {
                 let magnitude_is_valid =
-                    magnitude <= pallas::Base::from_u64(0xFFFF_FFFF_FFFF_FFFFu64);
-                let sign_is_valid = sign * sign == pallas::Base::one();
+                    magnitude < C::Base::from_u64(2).pow(&[MAGNITUDE_BITS as u64, 0, 0, 0]);
+                let sign_is_valid = sign * sign == C::Base::one();
                 if magnitude_is_valid && sign_is_valid {
                     let scalar = scalar.magnitude.value().zip(scalar.sign.value()).map(
                         |(magnitude, sign)| {
-                            // Move magnitude from base field into scalar field (which always fits
-                            // for Pallas).
-                            let magnitude =
-                                pallas::Scalar::from_bytes(&magnitude.to_bytes()).unwrap();
+                            // Move magnitude from base field into scalar field (which always fits,
+                            // as the magnitude is short).
+                            let magnitude = C::Scalar::from_bytes(&magnitude.to_bytes()).unwrap();
 
-                            let sign = if sign == pallas::Base::one() {
-                                pallas::Scalar::one()
+                            let sign = if sign == C::Base::one() {
+                                C::Scalar::one()
                             } else {
-                                -pallas::Scalar::one()
+                                -C::Scalar::one()
                             };
 
                             magnitude * sign
@@ -221,9 +271,7 @@ impl<Fixed: FixedPoints<pallas::Affine>> Config<Fixed> {
 
                     let result = result.point();
 
-                    if let (Some(real_mul), Some(result)) = (real_mul, result) {
-                        assert_eq!(real_mul.to_affine(), result);
-                    }
+                    super::super::debug_check_point(real_mul, result);
                 }
             }
         }
@@ -243,20 +291,20 @@ pub mod tests {
 
     use crate::{
         ecc::{chip::EccChip, FixedPoint, FixedPoints, NonIdentityPoint, Point},
-        utilities::{CellValue, UtilitiesInstructions},
+        utilities::{CellValue, UtilitiesInstructions, Var},
     };
 
     #[allow(clippy::op_ref)]
     pub fn test_mul_fixed_short<F: FixedPoints<pallas::Affine>>(
         base: F,
-        chip: EccChip<F>,
+        chip: EccChip<pallas::Affine, F>,
         mut layouter: impl Layouter<pallas::Base>,
     ) -> Result<(), Error> {
         let base_val = base.generator();
         let base = FixedPoint::from_inner(chip.clone(), base);
 
         fn load_magnitude_sign<F: FixedPoints<pallas::Affine>>(
-            chip: EccChip<F>,
+            chip: EccChip<pallas::Affine, F>,
             mut layouter: impl Layouter<pallas::Base>,
             magnitude: pallas::Base,
             sign: pallas::Base,
@@ -270,18 +318,19 @@ pub mod tests {
         }
 
         fn constrain_equal_non_id<F: FixedPoints<pallas::Affine>>(
-            chip: EccChip<F>,
+            chip: EccChip<pallas::Affine, F>,
             mut layouter: impl Layouter<pallas::Base>,
             base_val: pallas::Affine,
             scalar_val: pallas::Scalar,
-            result: Point<pallas::Affine, EccChip<F>>,
+            result: Point<pallas::Affine, EccChip<pallas::Affine, F>>,
         ) -> Result<(), Error> {
             let expected = NonIdentityPoint::new(
                 chip,
                 layouter.namespace(|| "expected point"),
                 Some((base_val * scalar_val).to_affine()),
             )?;
-            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)
+            result.constrain_equal(layouter.namespace(|| "constrain result"), &expected)?;
+            Ok(())
         }
 
         let magnitude_signs = [
@@ -368,6 +417,120 @@ pub mod tests {
             assert!(result.inner().is_identity().unwrap());
         }
 
+        // A constant magnitude and sign give the same point as witnessed ones.
+        for (magnitude, sign) in [
+            (rand::random::<u64>(), 1),
+            (rand::random::<u64>(), -1),
+            (0xFFFF_FFFF_FFFF_FFFF, -1),
+            (0, 1),
+        ]
+        .iter()
+        {
+            let name = format!("[{}{}]B", if *sign < 0 { "-" } else { "" }, magnitude);
+            let magnitude_sign = load_magnitude_sign(
+                chip.clone(),
+                layouter.namespace(|| name.clone()),
+                pallas::Base::from_u64(*magnitude),
+                if *sign < 0 {
+                    -pallas::Base::one()
+                } else {
+                    pallas::Base::one()
+                },
+            )?;
+            let (expected, _) =
+                base.mul_short(layouter.namespace(|| name.clone()), magnitude_sign)?;
+            let result = base.mul_short_const(
+                layouter.namespace(|| format!("constant {}", name)),
+                *magnitude,
+                *sign,
+            )?;
+            assert_eq!(result.inner().point(), expected.inner().point());
+            result.constrain_equal(
+                layouter.namespace(|| format!("constant {} = {}", name, name)),
+                &expected,
+            )?;
+        }
+
+        // A constant sign other than 1 or -1 is rejected.
+        assert!(matches!(
+            base.mul_short_const(layouter.namespace(|| "constant sign 0"), 1, 0),
+            Err(crate::ecc::EccError::OutOfRangeScalar)
+        ));
+
+        // The magnitude and sign cells of the scalar can be reused downstream.
+        {
+            let magnitude = pallas::Base::from_u64(rand::random::<u64>());
+            let sign = -pallas::Base::one();
+            let magnitude_sign = load_magnitude_sign(
+                chip.clone(),
+                layouter.namespace(|| "reused magnitude and sign"),
+                magnitude,
+                sign,
+            )?;
+            let (_, scalar) = base.mul_short(
+                layouter.namespace(|| "reused magnitude and sign"),
+                magnitude_sign,
+            )?;
+            assert_eq!(scalar.inner().magnitude().value(), Some(magnitude));
+            assert_eq!(scalar.inner().sign().value(), Some(sign));
+
+            // Copy-constrain the sign against an independently witnessed cell.
+            let other_sign = chip.load_private(
+                layouter.namespace(|| "independent sign"),
+                chip.config().advices[1],
+                Some(sign),
+            )?;
+            layouter.assign_region(
+                || "reuse sign",
+                |mut region| {
+                    region.constrain_equal(scalar.inner().sign().cell(), other_sign.cell())
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_witness_scalar_fixed_short<F: FixedPoints<pallas::Affine>>(
+        base: F,
+        chip: EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        let base_val = base.generator();
+        let base = FixedPoint::from_inner(chip.clone(), base);
+
+        let values = [
+            ("negative", -(rand::random::<u32>() as i64) - 1),
+            ("zero", 0),
+            ("positive", rand::random::<u32>() as i64 + 1),
+            ("i64::MIN", i64::MIN),
+        ];
+
+        for (name, value) in values.iter() {
+            let magnitude_sign =
+                chip.witness_scalar_fixed_short(layouter.namespace(|| *name), Some(*value))?;
+            let (result, _) = base.mul_short(layouter.namespace(|| *name), magnitude_sign)?;
+
+            let scalar = {
+                let magnitude = pallas::Scalar::from_u64(value.unsigned_abs());
+                if *value < 0 {
+                    -magnitude
+                } else {
+                    magnitude
+                }
+            };
+            if *value == 0 {
+                assert!(result.inner().is_identity().unwrap());
+            } else {
+                let expected = NonIdentityPoint::new(
+                    chip.clone(),
+                    layouter.namespace(|| format!("expected {}", name)),
+                    Some((base_val * scalar).to_affine()),
+                )?;
+                result.constrain_equal(layouter.namespace(|| *name), &expected)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -375,7 +538,7 @@ pub mod tests {
     fn invalid_magnitude_sign() {
         use crate::{
             ecc::{
-                chip::{compute_lagrange_coeffs, EccConfig, NUM_WINDOWS_SHORT},
+                chip::{compute_lagrange_coeffs, EccConfig, L_VALUE, NUM_WINDOWS_SHORT},
                 H,
             },
             utilities::lookup_range_check::LookupRangeCheckConfig,
@@ -426,7 +589,7 @@ pub mod tests {
         }
 
         impl Circuit<pallas::Base> for MyCircuit {
-            type Config = EccConfig;
+            type Config = EccConfig<pallas::Affine>;
             type FloorPlanner = SimpleFloorPlanner;
 
             fn without_witnesses(&self) -> Self {
@@ -463,7 +626,12 @@ pub mod tests {
                 meta.enable_constant(constants);
 
                 let range_check = LookupRangeCheckConfig::configure(meta, advices[9], lookup_table);
-                EccChip::<FixedBase>::configure(meta, advices, lagrange_coeffs, range_check)
+                EccChip::<pallas::Affine, FixedBase>::configure(
+                    meta,
+                    advices,
+                    lagrange_coeffs,
+                    range_check,
+                )
             }
 
             fn synthesize(
@@ -473,7 +641,12 @@ pub mod tests {
             ) -> Result<(), Error> {
                 let column = config.advices[0];
 
-                let short_config: super::Config<FixedBase> = (&config).into();
+                let short_config: super::Config<
+                    pallas::Affine,
+                    FixedBase,
+                    L_VALUE,
+                    NUM_WINDOWS_SHORT,
+                > = (&config).into();
                 let magnitude_sign = {
                     let magnitude = self.load_private(
                         layouter.namespace(|| "load magnitude"),
@@ -533,7 +706,7 @@ pub mod tests {
                     Err(vec![
                         VerifyFailure::ConstraintNotSatisfied {
                             constraint: (
-                                (17, "Short fixed-base mul gate").into(),
+                                (19, "Short fixed-base mul gate").into(),
                                 0,
                                 "last_window_check"
                             )
@@ -565,13 +738,13 @@ pub mod tests {
                 prover.verify(),
                 Err(vec![
                     VerifyFailure::ConstraintNotSatisfied {
-                        constraint: ((17, "Short fixed-base mul gate").into(), 1, "sign_check")
+                        constraint: ((19, "Short fixed-base mul gate").into(), 1, "sign_check")
                             .into(),
                         row: 26
                     },
                     VerifyFailure::ConstraintNotSatisfied {
                         constraint: (
-                            (17, "Short fixed-base mul gate").into(),
+                            (19, "Short fixed-base mul gate").into(),
                             3,
                             "negation_check"
                         )
@@ -582,4 +755,190 @@ pub mod tests {
             );
         }
     }
+
+    #[test]
+    fn magnitude_out_of_range_fails_at_synthesis() {
+        use crate::ecc::{
+            chip::{EccChip, EccConfig},
+            tests::FixedBase,
+            EccInstructions,
+        };
+        use halo2::{
+            circuit::{Layouter, SimpleFloorPlanner},
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem, Error},
+        };
+
+        #[derive(Default)]
+        struct MyCircuit {
+            magnitude: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                let column = config.advices[0];
+
+                let magnitude = chip.load_private(
+                    layouter.namespace(|| "load magnitude"),
+                    column,
+                    self.magnitude,
+                )?;
+                let sign = chip.load_private(
+                    layouter.namespace(|| "load sign"),
+                    column,
+                    Some(pallas::Base::one()),
+                )?;
+
+                chip.mul_fixed_short(&mut layouter, (magnitude, sign), &FixedBase::Short)?;
+
+                Ok(())
+            }
+        }
+
+        // 2^64 - 1 is the largest valid magnitude.
+        let circuit = MyCircuit {
+            magnitude: Some(pallas::Base::from_u64(0xFFFF_FFFF_FFFF_FFFFu64)),
+        };
+        let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 2^64 is rejected during synthesis, before any constraints are checked.
+        let circuit = MyCircuit {
+            magnitude: Some(pallas::Base::from_u128(1 << 64)),
+        };
+        assert!(MockProver::<pallas::Base>::run(11, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn mul_fixed_short_96_bits() {
+        use crate::ecc::{
+            chip::{EccChip, EccConfig, GeneratorFixedBase},
+            NonIdentityPoint,
+        };
+        use group::{Curve, Group};
+        use halo2::{
+            circuit::{Layouter, SimpleFloorPlanner},
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem, Error},
+        };
+        use lazy_static::lazy_static;
+
+        const MAGNITUDE_BITS: usize = 96;
+        const NUM_WINDOWS: usize = 32;
+
+        type FixedBase = GeneratorFixedBase<pallas::Affine>;
+
+        lazy_static! {
+            static ref BASE: FixedBase =
+                GeneratorFixedBase::new(pallas::Point::generator().to_affine(), NUM_WINDOWS);
+        }
+
+        #[derive(Default)]
+        struct MyCircuit {
+            magnitude: Option<u128>,
+            sign: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                let column = config.advices[0];
+
+                let magnitude = self.magnitude.map(pallas::Base::from_u128);
+                let magnitude_sign = {
+                    let magnitude = chip.load_private(
+                        layouter.namespace(|| "load magnitude"),
+                        column,
+                        magnitude,
+                    )?;
+                    let sign =
+                        chip.load_private(layouter.namespace(|| "load sign"), column, self.sign)?;
+                    (magnitude, sign)
+                };
+
+                let (result, _) = chip.mul_fixed_short_with_bits::<MAGNITUDE_BITS, NUM_WINDOWS>(
+                    layouter.namespace(|| "[magnitude * sign] B"),
+                    magnitude_sign,
+                    &*BASE,
+                )?;
+
+                // Only witness the expected point for in-range magnitudes.
+                if let (Some(magnitude), Some(sign)) = (self.magnitude, self.sign) {
+                    if magnitude < (1 << MAGNITUDE_BITS) {
+                        let scalar = pallas::Scalar::from_u128(magnitude);
+                        let scalar = if sign == pallas::Base::one() {
+                            scalar
+                        } else {
+                            -scalar
+                        };
+                        let expected = NonIdentityPoint::new(
+                            chip.clone(),
+                            layouter.namespace(|| "expected point"),
+                            Some((BASE.generator() * scalar).to_affine()),
+                        )?;
+                        let result = Point::from_inner(chip, result);
+                        result.constrain_equal(
+                            layouter.namespace(|| "constrain result"),
+                            &expected,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        // 96-bit magnitudes with both signs should pass
+        for magnitude in [rand::random::<u128>() >> 32, (1 << MAGNITUDE_BITS) - 1].iter() {
+            for sign in [pallas::Base::one(), -pallas::Base::one()].iter() {
+                let circuit = MyCircuit {
+                    magnitude: Some(*magnitude),
+                    sign: Some(*sign),
+                };
+                let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+                assert_eq!(prover.verify(), Ok(()));
+            }
+        }
+
+        // Magnitude larger than 96 bits should fail
+        {
+            let circuit = MyCircuit {
+                magnitude: Some(1 << MAGNITUDE_BITS),
+                sign: Some(pallas::Base::one()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
 }
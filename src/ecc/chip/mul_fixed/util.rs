@@ -1,10 +1,26 @@
 //! Utilities to compute associated constants for fixed bases.
-use super::{FIXED_BASE_WINDOW_SIZE, H};
+use super::super::{NUM_WINDOWS, NUM_WINDOWS_SHORT};
+use super::{FixedPoints, FIXED_BASE_WINDOW_SIZE, H};
 use arrayvec::ArrayVec;
 use ff::Field;
-use group::Curve;
-use halo2::arithmetic::lagrange_interpolate;
-use pasta_curves::arithmetic::{CurveAffine, FieldExt};
+use group::{Curve, Group, GroupEncoding};
+use halo2::{arithmetic::lagrange_interpolate, plonk::Error};
+use pasta_curves::{
+    arithmetic::{Coordinates, CurveAffine, FieldExt},
+    vesta,
+};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    fmt, io,
+    sync::{Arc, Mutex},
+};
+
+/// Magic bytes at the start of an encoding produced by [`dump_tables`].
+const TABLES_MAGIC: [u8; 4] = *b"h2fb";
+
+/// Version of the encoding produced by [`dump_tables`].
+const TABLES_VERSION: u32 = 1;
 
 /// For each fixed base, we calculate its scalar multiples in three-bit windows.
 /// Each window will have $2^3 = 8$ points.
@@ -59,6 +75,10 @@ pub fn compute_window_table<C: CurveAffine>(base: C, num_windows: usize) -> Vec<
 
 /// For each window, we interpolate the $x$-coordinate.
 /// Here, we pre-compute and store the coefficients of the interpolation polynomial.
+///
+/// Returns `num_windows` entries. Entry $w$ holds the $H$ coefficients of the
+/// polynomial mapping each window value $k \in [0..H)$ to the $x$-coordinate of
+/// the window table point for $k$, in order of increasing degree.
 pub fn compute_lagrange_coeffs<C: CurveAffine>(base: C, num_windows: usize) -> Vec<[C::Base; H]> {
     // We are interpolating over the 3-bit window, k \in [0..8)
     let points: Vec<_> = (0..H).map(|i| C::Base::from_u64(i as u64)).collect();
@@ -81,40 +101,627 @@ pub fn compute_lagrange_coeffs<C: CurveAffine>(base: C, num_windows: usize) -> V
         .collect()
 }
 
+/// The number of candidate `z` values tried for each window by [`find_zs_and_us`].
+pub const MAX_Z_TRIES: u64 = 1000 * (1 << (2 * H));
+
 /// For each window, $z$ is a field element such that for each point $(x, y)$ in the window:
 /// - $z + y = u^2$ (some square in the field); and
 /// - $z - y$ is not a square.
 /// If successful, return a vector of `(z: u64, us: [C::Base; H])` for each window.
+///
+/// Returns `num_windows` entries. Each $u$ is encoded as the canonical 32-byte
+/// little-endian representation of a base field element, and `us[k]` is the
+/// square root for the window table point for $k \in [0..H)$.
+///
+/// This tries up to [`MAX_Z_TRIES`] candidates for each window; see
+/// [`find_zs_and_us_bounded`].
 pub fn find_zs_and_us<C: CurveAffine>(
     base: C,
     num_windows: usize,
 ) -> Option<Vec<(u64, [[u8; 32]; H])>> {
-    // Closure to find z and u's for one window
-    let find_z_and_us = |window_points: &[C]| {
-        assert_eq!(H, window_points.len());
+    find_zs_and_us_bounded(base, num_windows, MAX_Z_TRIES)
+}
+
+/// Like [`find_zs_and_us`], but tries only the candidates $z \in [0, \mathsf{max\_tries})$
+/// for each window. Returns `None` if no valid $z$ is found for some window.
+///
+/// With the `multicore` feature enabled, the windows are searched in parallel.
+/// The output is the same as for the serial search.
+pub fn find_zs_and_us_bounded<C: CurveAffine>(
+    base: C,
+    num_windows: usize,
+    max_tries: u64,
+) -> Option<Vec<(u64, [[u8; 32]; H])>> {
+    let window_table = compute_window_table(base, num_windows);
+
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
 
-        let ys: Vec<_> = window_points
+        window_table
+            .par_iter()
+            .map(|window_points| find_z_and_us(window_points, max_tries))
+            .collect()
+    }
+
+    #[cfg(not(feature = "multicore"))]
+    {
+        window_table
             .iter()
-            .map(|point| *point.coordinates().unwrap().y())
-            .collect();
-        (0..(1000 * (1 << (2 * H)))).find_map(|z| {
-            ys.iter()
-                .map(|&y| {
-                    let u = if (-y + C::Base::from_u64(z)).sqrt().is_none().into() {
-                        (y + C::Base::from_u64(z)).sqrt().into()
-                    } else {
-                        None
-                    };
-                    u.map(|u: C::Base| u.to_bytes())
-                })
-                .collect::<Option<ArrayVec<[u8; 32], H>>>()
-                .map(|us| (z, us.into_inner().unwrap()))
+            .map(|window_points| find_z_and_us(window_points, max_tries))
+            .collect()
+    }
+}
+
+/// Finds `z` and the `u`s for a single window, trying the candidates
+/// $z \in [0, \mathsf{max\_tries})$.
+fn find_z_and_us<C: CurveAffine>(
+    window_points: &[C; H],
+    max_tries: u64,
+) -> Option<(u64, [[u8; 32]; H])> {
+    let ys: Vec<_> = window_points
+        .iter()
+        .map(|point| *point.coordinates().unwrap().y())
+        .collect();
+    (0..max_tries).find_map(|z| {
+        ys.iter()
+            .map(|&y| {
+                let u = if (-y + C::Base::from_u64(z)).sqrt().is_none().into() {
+                    (y + C::Base::from_u64(z)).sqrt().into()
+                } else {
+                    None
+                };
+                u.map(|u: C::Base| u.to_bytes())
+            })
+            .collect::<Option<ArrayVec<[u8; 32], H>>>()
+            .map(|us| (z, us.into_inner().unwrap()))
+    })
+}
+
+/// A window whose precomputed tables do not describe the expected multiple of
+/// the fixed base.
+///
+/// `halo2::plonk::Error` cannot carry the window index, so fixed-base scalar
+/// multiplication reports this as [`Error::SynthesisError`]. Use
+/// [`check_tables`] to find the offending window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowError {
+    /// For window value `k`, the interpolated x-coordinate and `y = u^2 - z`
+    /// are not a point on the curve.
+    OffCurve { window: usize, k: usize },
+    /// For window value `k`, the tables describe a point on the curve, but not
+    /// the expected multiple of the base.
+    WrongPoint { window: usize, k: usize },
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::OffCurve { window, k } => {
+                write!(f, "off-curve at window {} (k = {})", window, k)
+            }
+            WindowError::WrongPoint { window, k } => {
+                write!(
+                    f,
+                    "wrong multiple of the base at window {} (k = {})",
+                    window, k
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
+
+impl From<WindowError> for Error {
+    fn from(_: WindowError) -> Self {
+        Error::SynthesisError
+    }
+}
+
+/// Checks the tables of a single window against `expected`, the multiple of
+/// the base that window value `k` maps to.
+pub(crate) fn check_window<C: CurveAffine>(
+    window: usize,
+    k: usize,
+    lagrange_coeffs: &[C::Base; H],
+    z: u64,
+    u: &[u8; 32],
+    expected: C,
+) -> Result<(), WindowError> {
+    // Evaluate the interpolation polynomial at k, by Horner's rule.
+    let k_field = C::Base::from_u64(k as u64);
+    let x = lagrange_coeffs
+        .iter()
+        .rev()
+        .fold(C::Base::zero(), |acc, coeff| acc * k_field + coeff);
+    let y =
+        Option::<C::Base>::from(C::Base::from_bytes(u)).map(|u| u.square() - C::Base::from_u64(z));
+
+    let y = match y {
+        Some(y) if y.square() == x.square() * x + C::b() => y,
+        _ => return Err(WindowError::OffCurve { window, k }),
+    };
+
+    let expected: Option<Coordinates<C>> = expected.coordinates().into();
+    if expected.map(|expected| (*expected.x(), *expected.y())) == Some((x, y)) {
+        Ok(())
+    } else {
+        Err(WindowError::WrongPoint { window, k })
+    }
+}
+
+/// Checks every window of `base`'s precomputed tables, returning the first
+/// window value whose tables do not describe the expected multiple of the
+/// base.
+///
+/// # Panics
+///
+/// Panics if the tables have inconsistent lengths; see [`FixedPoints::validate`].
+pub fn check_tables<C: CurveAffine, F: FixedPoints<C>>(base: &F) -> Result<(), WindowError> {
+    let lagrange_coeffs = base.lagrange_coeffs();
+    let zs = base.z();
+    let us = base.u();
+    let window_table = compute_window_table(base.generator(), lagrange_coeffs.len());
+
+    for (window, points) in window_table.iter().enumerate() {
+        for (k, point) in points.iter().enumerate() {
+            check_window(
+                window,
+                k,
+                &lagrange_coeffs[window],
+                zs[window],
+                &us[window][k],
+                *point,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Computes the `z`s, `u`s and Lagrange coefficients for a full-width fixed
+/// base, and encodes them as bytes to be loaded later with [`load_tables`].
+///
+/// The encoding is:
+/// - the magic bytes `h2fb`, followed by the version and number of windows,
+///   each as a little-endian `u32`;
+/// - for each window, `z` as a little-endian `u64`, followed by the $H$ `u`s;
+/// - for each window, the $H$ Lagrange coefficients.
+///
+/// Field elements are encoded in their canonical 32-byte little-endian form.
+pub fn dump_tables<C: CurveAffine>(base: C) -> Vec<u8> {
+    let zs_and_us =
+        find_zs_and_us(base, NUM_WINDOWS).expect("z and u values exist for every fixed base");
+    let lagrange_coeffs = compute_lagrange_coeffs(base, NUM_WINDOWS);
+
+    let mut bytes = Vec::with_capacity(12 + NUM_WINDOWS * (8 + 2 * H * 32));
+    bytes.extend_from_slice(&TABLES_MAGIC);
+    bytes.extend_from_slice(&TABLES_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(NUM_WINDOWS as u32).to_le_bytes());
+    for (z, us) in zs_and_us.iter() {
+        bytes.extend_from_slice(&z.to_le_bytes());
+        for u in us.iter() {
+            bytes.extend_from_slice(u);
+        }
+    }
+    for coeffs in lagrange_coeffs.iter() {
+        for coeff in coeffs.iter() {
+            bytes.extend_from_slice(&coeff.to_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decodes the `z`s, `u`s and Lagrange coefficients for a full-width fixed base
+/// from bytes produced by [`dump_tables`].
+///
+/// Returns an error if the header does not match, if the encoding is not for
+/// [`NUM_WINDOWS`] windows, or if a Lagrange coefficient is not a canonical
+/// field element.
+#[allow(clippy::type_complexity)]
+pub fn load_tables<C: CurveAffine>(
+    bytes: &[u8],
+) -> io::Result<(Vec<(u64, [[u8; 32]; H])>, Vec<[C::Base; H]>)> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let expected_len = 12 + NUM_WINDOWS * (8 + 2 * H * 32);
+    if bytes.len() != expected_len {
+        return Err(invalid("unexpected length of fixed-base tables"));
+    }
+    let (header, mut bytes) = bytes.split_at(12);
+    if header[0..4] != TABLES_MAGIC {
+        return Err(invalid("invalid magic bytes for fixed-base tables"));
+    }
+    if u32::from_le_bytes(header[4..8].try_into().unwrap()) != TABLES_VERSION {
+        return Err(invalid("unsupported version of fixed-base tables"));
+    }
+    if u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize != NUM_WINDOWS {
+        return Err(invalid("unexpected number of windows in fixed-base tables"));
+    }
+
+    // Reads the next `N` bytes. The total length was checked above.
+    fn read<const N: usize>(bytes: &mut &[u8]) -> [u8; N] {
+        let (head, tail) = bytes.split_at(N);
+        *bytes = tail;
+        head.try_into().unwrap()
+    }
+
+    let zs_and_us = (0..NUM_WINDOWS)
+        .map(|_| {
+            let z = u64::from_le_bytes(read(&mut bytes));
+            let mut us = [[0u8; 32]; H];
+            for u in us.iter_mut() {
+                *u = read(&mut bytes);
+            }
+            (z, us)
         })
+        .collect();
+
+    let lagrange_coeffs = (0..NUM_WINDOWS)
+        .map(|_| {
+            let mut coeffs = [C::Base::zero(); H];
+            for coeff in coeffs.iter_mut() {
+                *coeff = Option::from(C::Base::from_bytes(&read(&mut bytes)))
+                    .ok_or_else(|| invalid("non-canonical Lagrange coefficient"))?;
+            }
+            Ok(coeffs)
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok((zs_and_us, lagrange_coeffs))
+}
+
+/// The constants needed to use a fixed base in fixed-base scalar multiplication
+/// with a given number of windows.
+///
+/// Each field has one entry per window, and maps directly onto the methods of
+/// [`FixedPoints`](crate::ecc::FixedPoints).
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<C: CurveAffine> {
+    /// The Lagrange interpolation coefficients for each window, as returned by
+    /// [`compute_lagrange_coeffs`].
+    pub lagrange_coeffs: Vec<[C::Base; H]>,
+    /// The `z` for each window, as returned by [`find_zs_and_us`].
+    pub z: Vec<u64>,
+    /// The `u`s for each window, as returned by [`find_zs_and_us`].
+    pub u: Vec<[[u8; 32]; H]>,
+}
+
+impl<C: CurveAffine> FixedBaseTable<C> {
+    /// Computes the tables for `base` with `num_windows` windows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no valid `z` is found for some window.
+    pub fn generate(base: C, num_windows: usize) -> Self {
+        let lagrange_coeffs = compute_lagrange_coeffs(base, num_windows);
+        let (z, u) = find_zs_and_us(base, num_windows)
+            .expect("z and u values exist for every fixed base")
+            .into_iter()
+            .unzip();
+
+        FixedBaseTable {
+            lagrange_coeffs,
+            z,
+            u,
+        }
+    }
+}
+
+/// A fixed base given by a generator of the curve, with its tables computed by
+/// [`FixedBaseTable::generate`] when it is constructed.
+///
+/// This implements [`FixedPoints`] for any curve supported by the ECC chip,
+/// so fixed-base scalar multiplication by a generator does not need a
+/// hand-written implementation. Cloning is cheap, as the tables are shared.
+#[derive(Clone, Debug)]
+pub struct GeneratorFixedBase<C: CurveAffine> {
+    generator: C,
+    num_windows: usize,
+    table: Arc<FixedBaseTable<C>>,
+}
+
+impl<C: CurveAffine> GeneratorFixedBase<C> {
+    /// Computes the tables for `generator` with `num_windows` windows, i.e.
+    /// [`NUM_WINDOWS`] for full-width scalars or [`NUM_WINDOWS_SHORT`] for
+    /// short signed scalars.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no valid `z` is found for some window.
+    pub fn new(generator: C, num_windows: usize) -> Self {
+        GeneratorFixedBase {
+            generator,
+            num_windows,
+            table: Arc::new(FixedBaseTable::generate(generator, num_windows)),
+        }
+    }
+}
+
+impl GeneratorFixedBase<vesta::Affine> {
+    /// The standard Vesta generator, for full-width scalars.
+    pub fn vesta() -> Self {
+        Self::new(vesta::Point::generator().to_affine(), NUM_WINDOWS)
+    }
+}
+
+// The tables are determined by the generator and the number of windows.
+impl<C: CurveAffine> PartialEq for GeneratorFixedBase<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.generator == other.generator && self.num_windows == other.num_windows
+    }
+}
+
+impl<C: CurveAffine> Eq for GeneratorFixedBase<C> {}
+
+impl<C: CurveAffine> FixedPoints<C> for GeneratorFixedBase<C> {
+    fn generator(&self) -> C {
+        self.generator
+    }
+
+    fn u(&self) -> Vec<[[u8; 32]; H]> {
+        self.table.u.clone()
+    }
+
+    fn z(&self) -> Vec<u64> {
+        self.table.z.clone()
+    }
+
+    fn lagrange_coeffs(&self) -> Vec<[C::Base; H]> {
+        self.table.lagrange_coeffs.clone()
+    }
+}
+
+/// A cache of [`FixedBaseTable`]s, keyed by the compressed encoding of the
+/// fixed base and the number of windows.
+///
+/// Computing the tables for a fixed base is expensive. A single instance of this
+/// cache (e.g. in a `lazy_static`) can be shared by [`FixedPoints`] implementations
+/// so that the tables are only computed once per process.
+///
+/// [`FixedPoints`]: crate::ecc::FixedPoints
+#[derive(Debug)]
+pub struct FixedBaseTables<C: CurveAffine> {
+    tables: Mutex<HashMap<(Vec<u8>, usize), Arc<FixedBaseTable<C>>>>,
+}
+
+impl<C: CurveAffine> Default for FixedBaseTables<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: CurveAffine> FixedBaseTables<C> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        FixedBaseTables {
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a cache holding the tables for each of the given bases, for both
+    /// full-width and short signed scalars.
+    pub fn precompute(bases: &[C]) -> Self {
+        let tables = Self::new();
+        for base in bases {
+            tables.get(*base, NUM_WINDOWS);
+            tables.get(*base, NUM_WINDOWS_SHORT);
+        }
+        tables
+    }
+
+    /// Returns the tables for `base` with `num_windows` windows, computing and
+    /// caching them if they are not already cached.
+    pub fn get(&self, base: C, num_windows: usize) -> Arc<FixedBaseTable<C>> {
+        let key = (base.to_bytes().as_ref().to_vec(), num_windows);
+
+        if let Some(table) = self.tables.lock().unwrap().get(&key) {
+            return table.clone();
+        }
+
+        // Compute the tables without holding the lock. If another thread raced us,
+        // keep whichever entry was inserted first.
+        let table = Arc::new(FixedBaseTable::generate(base, num_windows));
+        self.tables
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(table)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_lagrange_coeffs, dump_tables, find_zs_and_us, find_zs_and_us_bounded, load_tables,
+        FixedBaseTable, FixedBaseTables,
     };
+    use crate::ecc::{
+        chip::{NUM_WINDOWS, NUM_WINDOWS_SHORT},
+        FixedPoints, H,
+    };
+    use group::{Curve, Group};
+    use pasta_curves::pallas;
+    use std::sync::Arc;
 
-    let window_table = compute_window_table(base, num_windows);
-    window_table
-        .iter()
-        .map(|window_points| find_z_and_us(window_points))
-        .collect()
+    #[test]
+    fn fixed_base_table_generate() {
+        use lazy_static::lazy_static;
+
+        lazy_static! {
+            static ref BASE: pallas::Affine = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            static ref TABLE: FixedBaseTable<pallas::Affine> =
+                FixedBaseTable::generate(*BASE, NUM_WINDOWS_SHORT);
+        }
+
+        // A `FixedPoints` implementation backed by a generated table.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        struct TableBase;
+
+        impl FixedPoints<pallas::Affine> for TableBase {
+            fn generator(&self) -> pallas::Affine {
+                *BASE
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                TABLE.u.clone()
+            }
+
+            fn z(&self) -> Vec<u64> {
+                TABLE.z.clone()
+            }
+
+            fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
+                TABLE.lagrange_coeffs.clone()
+            }
+        }
+
+        assert!(TableBase.validate().is_ok());
+        assert_eq!(TableBase.lagrange_coeffs().len(), NUM_WINDOWS_SHORT);
+        assert_eq!(
+            TableBase.lagrange_coeffs(),
+            compute_lagrange_coeffs(*BASE, NUM_WINDOWS_SHORT)
+        );
+        let zs_and_us = find_zs_and_us(*BASE, NUM_WINDOWS_SHORT).unwrap();
+        assert_eq!(
+            TableBase.z(),
+            zs_and_us.iter().map(|(z, _)| *z).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            TableBase.u(),
+            zs_and_us.iter().map(|(_, us)| *us).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn fixed_base_tables_cache() {
+        let base = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let tables = FixedBaseTables::new();
+
+        let table = tables.get(base, NUM_WINDOWS_SHORT);
+        assert_eq!(
+            table.lagrange_coeffs,
+            compute_lagrange_coeffs(base, NUM_WINDOWS_SHORT)
+        );
+        let zs_and_us = find_zs_and_us(base, NUM_WINDOWS_SHORT).unwrap();
+        assert_eq!(
+            table.z,
+            zs_and_us.iter().map(|(z, _)| *z).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            table.u,
+            zs_and_us.iter().map(|(_, us)| *us).collect::<Vec<_>>()
+        );
+
+        // A second lookup should return the cached tables.
+        assert!(Arc::ptr_eq(&table, &tables.get(base, NUM_WINDOWS_SHORT)));
+    }
+
+    #[test]
+    fn dump_and_load_tables() {
+        let base = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let bytes = dump_tables(base);
+
+        let (zs_and_us, lagrange_coeffs) = load_tables::<pallas::Affine>(&bytes).unwrap();
+        assert_eq!(zs_and_us, find_zs_and_us(base, NUM_WINDOWS).unwrap());
+        assert_eq!(lagrange_coeffs, compute_lagrange_coeffs(base, NUM_WINDOWS));
+
+        // Truncated encoding
+        assert!(load_tables::<pallas::Affine>(&bytes[..bytes.len() - 1]).is_err());
+
+        // Invalid magic bytes
+        {
+            let mut bytes = bytes.clone();
+            bytes[0] ^= 1;
+            assert!(load_tables::<pallas::Affine>(&bytes).is_err());
+        }
+
+        // Unexpected number of windows
+        {
+            let mut bytes = bytes;
+            bytes[8] ^= 1;
+            assert!(load_tables::<pallas::Affine>(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn find_zs_and_us_bounded_gives_up() {
+        let base = pallas::Point::random(rand::rngs::OsRng).to_affine();
+
+        // With no candidates to try, no window can succeed.
+        assert!(find_zs_and_us_bounded(base, NUM_WINDOWS_SHORT, 0).is_none());
+
+        // With enough candidates, the result matches the unbounded search.
+        let zs_and_us = find_zs_and_us(base, NUM_WINDOWS_SHORT).unwrap();
+        let max_z = zs_and_us.iter().map(|(z, _)| *z).max().unwrap();
+        assert_eq!(
+            find_zs_and_us_bounded(base, NUM_WINDOWS_SHORT, max_z + 1),
+            Some(zs_and_us)
+        );
+
+        // Stopping just short of the largest `z` fails.
+        assert!(find_zs_and_us_bounded(base, NUM_WINDOWS_SHORT, max_z).is_none());
+    }
+
+    #[cfg(feature = "multicore")]
+    #[test]
+    fn find_zs_and_us_parallel_matches_serial() {
+        use super::{compute_window_table, find_z_and_us, MAX_Z_TRIES};
+        use group::prime::PrimeCurveAffine;
+
+        let base = pallas::Affine::generator();
+
+        let serial: Option<Vec<_>> = compute_window_table(base, NUM_WINDOWS)
+            .iter()
+            .map(|window_points| find_z_and_us(window_points, MAX_Z_TRIES))
+            .collect();
+        assert_eq!(find_zs_and_us(base, NUM_WINDOWS), serial);
+    }
+
+    #[test]
+    fn check_tables_reports_window() {
+        use super::{check_tables, WindowError};
+        use crate::ecc::tests::FixedBase;
+        use ff::Field;
+        use halo2::plonk::Error;
+
+        // The full-width generator tables, with a single Lagrange coefficient
+        // corrupted in window 42.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        struct CorruptBase;
+
+        impl FixedPoints<pallas::Affine> for CorruptBase {
+            fn generator(&self) -> pallas::Affine {
+                FixedBase::FullWidth.generator()
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                FixedBase::FullWidth.u()
+            }
+
+            fn z(&self) -> Vec<u64> {
+                FixedBase::FullWidth.z()
+            }
+
+            fn lagrange_coeffs(&self) -> Vec<[pallas::Base; H]> {
+                let mut coeffs = FixedBase::FullWidth.lagrange_coeffs();
+                coeffs[42][0] += pallas::Base::one();
+                coeffs
+            }
+        }
+
+        assert_eq!(check_tables(&FixedBase::FullWidth), Ok(()));
+
+        // The table lengths are still consistent.
+        assert!(CorruptBase.validate().is_ok());
+
+        // The constant coefficient affects every window value, so k = 0 fails.
+        let err = check_tables(&CorruptBase).unwrap_err();
+        assert_eq!(err, WindowError::OffCurve { window: 42, k: 0 });
+        assert_eq!(err.to_string(), "off-curve at window 42 (k = 0)");
+
+        // Synthesis only sees a generic error.
+        assert!(matches!(Error::from(err), Error::SynthesisError));
+    }
 }
@@ -0,0 +1,105 @@
+use std::marker::PhantomData;
+
+use super::{copy, CellValue, EccConfig, EccCurve, NonIdentityEccPoint, SelectorUsage, Var};
+use halo2::{
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct Config<C: EccCurve> {
+    q_negate: Selector,
+    // x-coordinate of P in -P = R. This is shared with R.
+    pub x_p: Column<Advice>,
+    // y-coordinate of P in -P = R
+    pub y_p: Column<Advice>,
+    // y-coordinate of R in -P = R
+    pub y_r: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
+}
+
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
+        Self {
+            q_negate: ecc_config.q_negate,
+            x_p: ecc_config.advices[0],
+            y_p: ecc_config.advices[1],
+            y_r: ecc_config.advices[3],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("point negation", |meta| {
+            let q_negate = meta.query_selector(self.q_negate);
+            let y_p = meta.query_advice(self.y_p, Rotation::cur());
+            let y_r = meta.query_advice(self.y_r, Rotation::cur());
+
+            // y_r + y_p = 0
+            vec![("y_r", q_negate * (y_r + y_p))]
+        });
+    }
+
+    pub(super) fn assign_region(
+        &self,
+        p: &NonIdentityEccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        // Enable `q_negate` selector
+        self.usage.enable(&self.q_negate, region, offset)?;
+
+        // Copy point `p` into `x_p`, `y_p` columns. The copied x-coordinate
+        // is reused as the x-coordinate of the result.
+        let x_r = copy(region, || "x_p", self.x_p, offset, &p.x)?;
+        copy(region, || "y_p", self.y_p, offset, &p.y)?;
+
+        // Assign the negated y-coordinate
+        let y_r = p.y.value().map(|y_p| -y_p);
+        let y_r_var = region.assign_advice(
+            || "y_r",
+            self.y_r,
+            offset,
+            || y_r.ok_or(Error::SynthesisError),
+        )?;
+
+        Ok(NonIdentityEccPoint {
+            x: x_r,
+            y: CellValue::<C::Base>::new(y_r_var, y_r),
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use group::{Curve, Group};
+    use halo2::{circuit::Layouter, plonk::Error};
+    use pasta_curves::pallas;
+
+    use crate::ecc::{EccInstructions, NonIdentityPoint};
+
+    pub fn test_negate<EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug>(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        // Generate a random point P
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine(); // P
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+
+        // -P
+        {
+            let result = p.negate(layouter.namespace(|| "-P"))?;
+            let witnessed_result =
+                NonIdentityPoint::new(chip, layouter.namespace(|| "witnessed -P"), Some(-p_val))?;
+            result.constrain_equal(layouter.namespace(|| "constrain -P"), &witnessed_result)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use super::{CellValue, EccConfig, EccCurve, SelectorUsage, Var, L_PALLAS_BASE};
+use crate::{
+    primitives::sinsemilla,
+    utilities::{bool_check, copy, lookup_range_check::LookupRangeCheckConfig},
+};
+
+use ff::{Field, PrimeFieldBits};
+use halo2::{
+    circuit::Layouter,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::FieldExt;
+
+use std::array;
+
+/// Number of 10-bit words used to range-check `s` in the canonicity check.
+const CANONICITY_LOOKUP_WORDS: usize = 130 / sinsemilla::K;
+
+pub struct Config<C: EccCurve> {
+    // Selector for the bitwise running sum
+    q_scalar_bits: Selector,
+    // Selector for the canonicity check on the decomposition
+    q_scalar_bits_canon: Selector,
+    // 10-bit lookup table
+    lookup_config: LookupRangeCheckConfig<C::Base, { sinsemilla::K }>,
+    // Running sum z_i = 2⋅z_{i+1} + b_i
+    z: Column<Advice>,
+    // Bit b_i
+    bit: Column<Advice>,
+    // Advice columns used in the canonicity check
+    canon_advices: [Column<Advice>; 4],
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+}
+
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
+        Self {
+            q_scalar_bits: ecc_config.q_scalar_bits,
+            q_scalar_bits_canon: ecc_config.q_scalar_bits_canon,
+            lookup_config: ecc_config.lookup_config.clone(),
+            z: ecc_config.advices[0],
+            bit: ecc_config.advices[1],
+            canon_advices: [
+                ecc_config.advices[0],
+                ecc_config.advices[1],
+                ecc_config.advices[2],
+                ecc_config.advices[3],
+            ],
+            usage: ecc_config.usage.clone(),
+        }
+    }
+}
+
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("scalar bit decomposition", |meta| {
+            let q_scalar_bits = meta.query_selector(self.q_scalar_bits);
+            let z_cur = meta.query_advice(self.z, Rotation::cur());
+            let z_next = meta.query_advice(self.z, Rotation::next());
+            let bit = meta.query_advice(self.bit, Rotation::cur());
+
+            // b_i = z_i - 2⋅z_{i+1}
+            let word = z_cur - z_next * C::Base::from_u64(2);
+
+            array::IntoIter::new([("bit", bit.clone() - word), ("bool_check", bool_check(bit))])
+                .map(move |(name, poly)| (name, q_scalar_bits.clone() * poly))
+        });
+
+        meta.create_gate("scalar bit decomposition canonicity", |meta| {
+            let q_scalar_bits_canon = meta.query_selector(self.q_scalar_bits_canon);
+            let alpha = meta.query_advice(self.canon_advices[0], Rotation::cur());
+            let z_126 = meta.query_advice(self.canon_advices[1], Rotation::cur());
+            let b_254 = meta.query_advice(self.canon_advices[2], Rotation::cur());
+            let s = meta.query_advice(self.canon_advices[3], Rotation::cur());
+
+            // Constant expressions
+            let two_pow_126 = Expression::Constant(C::Base::from_u128(1 << 126));
+            let two_pow_128 = two_pow_126.clone() * C::Base::from_u64(1 << 2);
+            let two_pow_130 = two_pow_126.clone() * C::Base::from_u64(1 << 4);
+            let t_p = Expression::Constant(C::Base::from_u128(C::T_P));
+
+            // p = 2^254 + t_p is the base field modulus. If b_254 = 1, then
+            // alpha is canonical iff b_126..=b_253 are all zero and the low
+            // 126 bits are less than t_p.
+
+            // b_254 ⋅ (z_126 - 2^128) = 0
+            let hi_zero = b_254.clone() * (z_126.clone() - two_pow_128);
+
+            // b_254 ⋅ (alpha - 2^126⋅z_126 + 2^130 - t_p - s) = 0,
+            // where s is range-constrained to 130 bits.
+            let s_check = b_254 * (alpha - two_pow_126 * z_126 + two_pow_130 - t_p - s);
+
+            array::IntoIter::new([("hi_zero", hi_zero), ("s_check", s_check)])
+                .map(move |(name, poly)| (name, q_scalar_bits_canon.clone() * poly))
+        });
+    }
+
+    /// Decomposes `alpha` into its canonical little-endian bits, returning a
+    /// boolean cell for each bit.
+    pub(super) fn assign(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+    ) -> Result<Vec<CellValue<C::Base>>, Error> {
+        let bits: Option<Vec<bool>> = alpha.value().map(|alpha| {
+            alpha
+                .to_le_bits()
+                .iter()
+                .by_val()
+                .take(L_PALLAS_BASE)
+                .collect()
+        });
+
+        // Running sum [z_0, ..., z_255], where z_0 = alpha and z_255 = 0.
+        let (zs, bit_cells) = self.usage.assign_region(
+            &mut layouter,
+            || "scalar bit decomposition",
+            |mut region| {
+                let mut z = copy(&mut region, || "z_0 = alpha", self.z, 0, &alpha)?;
+                let mut zs = vec![z];
+                let mut bit_cells = Vec::with_capacity(L_PALLAS_BASE);
+
+                let two_inv = C::Base::from_u64(2).invert().unwrap();
+                for idx in 0..L_PALLAS_BASE {
+                    self.usage.enable(&self.q_scalar_bits, &mut region, idx)?;
+
+                    let bit = bits.as_ref().map(|bits| C::Base::from(bits[idx]));
+                    let bit_cell = region.assign_advice(
+                        || format!("b_{}", idx),
+                        self.bit,
+                        idx,
+                        || bit.ok_or(Error::SynthesisError),
+                    )?;
+                    bit_cells.push(CellValue::new(bit_cell, bit));
+
+                    // z_{i+1} = (z_i - b_i) / 2
+                    let z_next = if idx == L_PALLAS_BASE - 1 {
+                        // Constrain the final running sum output to be zero.
+                        let cell = region.assign_advice_from_constant(
+                            || format!("z_{}", idx + 1),
+                            self.z,
+                            idx + 1,
+                            C::Base::zero(),
+                        )?;
+                        CellValue::new(cell, Some(C::Base::zero()))
+                    } else {
+                        let z_next_val = z.value().zip(bit).map(|(z, bit)| (z - bit) * two_inv);
+                        let cell = region.assign_advice(
+                            || format!("z_{}", idx + 1),
+                            self.z,
+                            idx + 1,
+                            || z_next_val.ok_or(Error::SynthesisError),
+                        )?;
+                        CellValue::new(cell, z_next_val)
+                    };
+                    zs.push(z_next);
+                    z = z_next;
+                }
+
+                Ok((zs, bit_cells))
+            },
+        )?;
+
+        self.canonicity_check(layouter.namespace(|| "canonicity"), alpha, &zs, &bit_cells)?;
+
+        Ok(bit_cells)
+    }
+
+    /// Recombines little-endian `bits` into the field element they represent,
+    /// boolean-constraining each bit. This uses the same running sum gate as
+    /// [`Config::assign`], with `z_n = 0` and `z_0` the recombined value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are `L_PALLAS_BASE` or more bits, since the recombined
+    /// value could then wrap around the field modulus.
+    pub(super) fn recombine(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        bits: &[CellValue<C::Base>],
+    ) -> Result<CellValue<C::Base>, Error> {
+        assert!(bits.len() < L_PALLAS_BASE);
+        let num_bits = bits.len();
+
+        self.usage.assign_region(
+            &mut layouter,
+            || "scalar bit recombination",
+            |mut region| {
+                // Running sum [z_0, ..., z_n], where z_n = 0.
+                let mut z = {
+                    let cell = region.assign_advice_from_constant(
+                        || format!("z_{}", num_bits),
+                        self.z,
+                        num_bits,
+                        C::Base::zero(),
+                    )?;
+                    CellValue::new(cell, Some(C::Base::zero()))
+                };
+
+                for (idx, bit) in bits.iter().enumerate().rev() {
+                    self.usage.enable(&self.q_scalar_bits, &mut region, idx)?;
+
+                    let bit = copy(&mut region, || format!("b_{}", idx), self.bit, idx, bit)?;
+
+                    // z_i = 2⋅z_{i+1} + b_i
+                    let z_val = z.value().zip(bit.value()).map(|(z, bit)| z.double() + bit);
+                    let cell = region.assign_advice(
+                        || format!("z_{}", idx),
+                        self.z,
+                        idx,
+                        || z_val.ok_or(Error::SynthesisError),
+                    )?;
+                    z = CellValue::new(cell, z_val);
+                }
+
+                Ok(z)
+            },
+        )
+    }
+
+    fn canonicity_check(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        alpha: CellValue<C::Base>,
+        zs: &[CellValue<C::Base>],
+        bits: &[CellValue<C::Base>],
+    ) -> Result<(), Error> {
+        let z_126 = zs[126];
+        let b_254 = bits[254];
+
+        // s = alpha - 2^254 + 2^130 - t_p if b_254 = 1, and 0 otherwise.
+        let s_val = alpha.value().zip(b_254.value()).map(|(alpha, b_254)| {
+            if b_254 == C::Base::one() {
+                let two_pow_254 = C::Base::from_u128(1 << 127).square();
+                let two_pow_130 = C::Base::from_u128(1 << 65).square();
+                alpha - two_pow_254 + two_pow_130 - C::Base::from_u128(C::T_P)
+            } else {
+                C::Base::zero()
+            }
+        });
+
+        let s = self.usage.assign_region(
+            &mut layouter,
+            || "scalar bit decomposition canonicity",
+            |mut region| {
+                self.usage
+                    .enable(&self.q_scalar_bits_canon, &mut region, 0)?;
+
+                copy(&mut region, || "alpha", self.canon_advices[0], 0, &alpha)?;
+                copy(&mut region, || "z_126", self.canon_advices[1], 0, &z_126)?;
+                copy(&mut region, || "b_254", self.canon_advices[2], 0, &b_254)?;
+
+                let s_cell = region.assign_advice(
+                    || "s",
+                    self.canon_advices[3],
+                    0,
+                    || s_val.ok_or(Error::SynthesisError),
+                )?;
+                Ok(CellValue::new(s_cell, s_val))
+            },
+        )?;
+
+        // Range-constrain s to 130 bits.
+        self.lookup_config.copy_check(
+            layouter.namespace(|| "s < 2^130"),
+            s,
+            CANONICITY_LOOKUP_WORDS,
+            true,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! A self-test for [`EccChip`], for downstream crates to validate their own
+//! [`FixedPoints`] implementations.
+
+use super::{EccChip, EccCurve};
+use crate::{
+    ecc::{FixedPoint, FixedPoints, NonIdentityPoint, Point},
+    utilities::UtilitiesInstructions,
+};
+
+use group::{prime::PrimeCurveAffine, Curve};
+use halo2::{
+    circuit::{Chip, Layouter},
+    plonk::Error,
+};
+use pasta_curves::arithmetic::FieldExt;
+
+/// Runs the standard battery of ECC operations on `chip`: witnessing points,
+/// complete and incomplete addition, doubling, variable-base scalar
+/// multiplication, and fixed-base scalar multiplication by `base`.
+///
+/// Every result is constrained to equal its value computed outside the
+/// circuit, so a circuit calling this verifies if and only if the chip and
+/// the tables provided by `base` are correct. Scalars are sampled at random.
+pub fn ecc_chip_self_test<C: EccCurve, F: FixedPoints<C>>(
+    chip: EccChip<C, F>,
+    base: F,
+    mut layouter: impl Layouter<C::Base>,
+) -> Result<(), Error> {
+    let generator = C::generator();
+    let a_val = C::Scalar::rand();
+    let b_val = C::Scalar::rand();
+
+    // Witness P = [a]G and Q = [b]G
+    let p_val = (generator * a_val).to_affine();
+    let q_val = (generator * b_val).to_affine();
+    let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+    let q = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "Q"), Some(q_val))?;
+
+    // P + Q
+    {
+        let expected = Point::new(
+            chip.clone(),
+            layouter.namespace(|| "expected P + Q"),
+            Some((generator * (a_val + b_val)).to_affine()),
+        )?;
+
+        let complete = p.add(layouter.namespace(|| "P + Q"), &q)?;
+        complete.constrain_equal(layouter.namespace(|| "constrain P + Q"), &expected)?;
+
+        let incomplete = p.add_incomplete(layouter.namespace(|| "P + Q incomplete"), &q)?;
+        incomplete.constrain_equal(
+            layouter.namespace(|| "constrain P + Q incomplete"),
+            &expected,
+        )?;
+    }
+
+    // [2]P
+    {
+        let expected = NonIdentityPoint::new(
+            chip.clone(),
+            layouter.namespace(|| "expected [2]P"),
+            Some((generator * (a_val + a_val)).to_affine()),
+        )?;
+        let doubled = p.double(layouter.namespace(|| "[2]P"))?;
+        doubled.constrain_equal(layouter.namespace(|| "constrain [2]P"), &expected)?;
+    }
+
+    // [c]P, with c witnessed as a base field element
+    {
+        let c_val = rand::random::<u64>();
+        let column = chip.config().advices[0];
+        let c = chip.load_private(
+            layouter.namespace(|| "c"),
+            column,
+            Some(C::Base::from_u64(c_val)),
+        )?;
+        let expected = Point::new(
+            chip.clone(),
+            layouter.namespace(|| "expected [c]P"),
+            Some((p_val * C::Scalar::from_u64(c_val)).to_affine()),
+        )?;
+        let (result, _) = p.mul(layouter.namespace(|| "[c]P"), &c)?;
+        result.constrain_equal(layouter.namespace(|| "constrain [c]P"), &expected)?;
+    }
+
+    // [d]B
+    {
+        let d_val = C::Scalar::rand();
+        let expected = Point::new(
+            chip.clone(),
+            layouter.namespace(|| "expected [d]B"),
+            Some((base.generator() * d_val).to_affine()),
+        )?;
+        let fixed = FixedPoint::from_inner(chip, base);
+        let (result, _) = fixed.mul(layouter.namespace(|| "[d]B"), Some(d_val))?;
+        result.constrain_equal(layouter.namespace(|| "constrain [d]B"), &expected)?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,9 @@
-use super::{CellValue, EccConfig, EccPoint, NonIdentityEccPoint, Var};
+use super::{
+    copy, CellValue, EccConfig, EccCurve, EccPoint, NonIdentityEccPoint, SelectorUsage, Var,
+};
+use std::marker::PhantomData;
 
+use ff::Field;
 use group::prime::PrimeCurveAffine;
 
 use halo2::{
@@ -7,37 +11,50 @@ use halo2::{
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
     poly::Rotation,
 };
-use pasta_curves::{arithmetic::CurveAffine, pallas};
+use pasta_curves::arithmetic::CurveAffine;
 
 #[derive(Clone, Debug)]
-pub struct Config {
+pub struct Config<C: EccCurve> {
     q_point: Selector,
     q_point_non_id: Selector,
+    q_point_flag: Selector,
     // x-coordinate
     pub x: Column<Advice>,
     // y-coordinate
     pub y: Column<Advice>,
+    // Boolean flag that is 1 iff the point is the identity
+    pub is_identity: Column<Advice>,
+    // Inverse of the x-coordinate, or 0 if x = 0
+    pub x_inv: Column<Advice>,
+    // Rows on which the selectors are enabled
+    usage: SelectorUsage,
+    _marker: PhantomData<C>,
 }
 
-impl From<&EccConfig> for Config {
-    fn from(ecc_config: &EccConfig) -> Self {
+impl<C: EccCurve> From<&EccConfig<C>> for Config<C> {
+    fn from(ecc_config: &EccConfig<C>) -> Self {
         Self {
             q_point: ecc_config.q_point,
             q_point_non_id: ecc_config.q_point_non_id,
+            q_point_flag: ecc_config.q_point_flag,
             x: ecc_config.advices[0],
             y: ecc_config.advices[1],
+            is_identity: ecc_config.advices[2],
+            x_inv: ecc_config.advices[3],
+            usage: ecc_config.usage.clone(),
+            _marker: PhantomData,
         }
     }
 }
 
-impl Config {
-    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<pallas::Base>) {
-        let curve_eqn = |meta: &mut VirtualCells<pallas::Base>| {
+impl<C: EccCurve> Config<C> {
+    pub(super) fn create_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        let curve_eqn = |meta: &mut VirtualCells<C::Base>| {
             let x = meta.query_advice(self.x, Rotation::cur());
             let y = meta.query_advice(self.y, Rotation::cur());
 
             // y^2 = x^3 + b
-            y.square() - (x.clone().square() * x) - Expression::Constant(pallas::Affine::b())
+            y.square() - (x.clone().square() * x) - Expression::Constant(C::b())
         };
 
         meta.create_gate("witness point", |meta| {
@@ -65,12 +82,43 @@ impl Config {
         });
     }
 
+    pub(super) fn create_flag_gate(&self, meta: &mut ConstraintSystem<C::Base>) {
+        meta.create_gate("witness point identity flag", |meta| {
+            // This is used alongside `q_point`, which constrains (x, y) to be either
+            // (0, 0) or a valid curve point. No curve point has x = 0, so the point
+            // is the identity iff x = 0.
+
+            let q_point_flag = meta.query_selector(self.q_point_flag);
+            let x = meta.query_advice(self.x, Rotation::cur());
+            let y = meta.query_advice(self.y, Rotation::cur());
+            let is_identity = meta.query_advice(self.is_identity, Rotation::cur());
+            let x_inv = meta.query_advice(self.x_inv, Rotation::cur());
+
+            let one = Expression::Constant(C::Base::one());
+
+            vec![
+                // is_identity = 1 - x⋅x_inv
+                (
+                    "is_identity = 1 - x⋅x_inv",
+                    q_point_flag.clone() * (is_identity.clone() - (one - x.clone() * x_inv)),
+                ),
+                // x⋅is_identity = 0
+                (
+                    "x⋅is_identity = 0",
+                    q_point_flag.clone() * x * is_identity.clone(),
+                ),
+                // y⋅is_identity = 0
+                ("y⋅is_identity = 0", q_point_flag * y * is_identity),
+            ]
+        });
+    }
+
     fn assign_xy(
         &self,
-        value: Option<(pallas::Base, pallas::Base)>,
+        value: Option<(C::Base, C::Base)>,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<(CellValue<pallas::Base>, CellValue<pallas::Base>), Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<(CellValue<C::Base>, CellValue<C::Base>), Error> {
         // Assign `x` value
         let x_val = value.map(|value| value.0);
         let x_var = region.assign_advice(
@@ -90,25 +138,25 @@ impl Config {
         )?;
 
         Ok((
-            CellValue::<pallas::Base>::new(x_var, x_val),
-            CellValue::<pallas::Base>::new(y_var, y_val),
+            CellValue::<C::Base>::new(x_var, x_val),
+            CellValue::<C::Base>::new(y_var, y_val),
         ))
     }
 
     /// Assigns a point that can be the identity.
     pub(super) fn point(
         &self,
-        value: Option<pallas::Affine>,
+        value: Option<C>,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<EccPoint, Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<EccPoint<C>, Error> {
         // Enable `q_point` selector
-        self.q_point.enable(region, offset)?;
+        self.usage.enable(&self.q_point, region, offset)?;
 
         let value = value.map(|value| {
             // Map the identity to (0, 0).
-            if value == pallas::Affine::identity() {
-                (pallas::Base::zero(), pallas::Base::zero())
+            if value == C::identity() {
+                (C::Base::zero(), C::Base::zero())
             } else {
                 let value = value.coordinates().unwrap();
                 (*value.x(), *value.y())
@@ -119,19 +167,101 @@ impl Config {
             .map(|(x, y)| EccPoint { x, y })
     }
 
+    /// Assigns a constant point that can be the identity.
+    ///
+    /// The coordinates are loaded from the circuit's constants column, so no
+    /// on-curve check is needed.
+    pub(super) fn constant_point(
+        &self,
+        value: C,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<EccPoint<C>, Error> {
+        // Map the identity to (0, 0).
+        let (x, y) = if value == C::identity() {
+            (C::Base::zero(), C::Base::zero())
+        } else {
+            let value = value.coordinates().unwrap();
+            (*value.x(), *value.y())
+        };
+
+        let x_var = region.assign_advice_from_constant(|| "x", self.x, offset, x)?;
+        let y_var = region.assign_advice_from_constant(|| "y", self.y, offset, y)?;
+
+        Ok(EccPoint {
+            x: CellValue::new(x_var, Some(x)),
+            y: CellValue::new(y_var, Some(y)),
+        })
+    }
+
+    /// Assigns a point that can be the identity, along with a boolean flag that
+    /// is 1 iff the point is the identity.
+    pub(super) fn point_with_flag(
+        &self,
+        value: Option<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<(EccPoint<C>, CellValue<C::Base>), Error> {
+        // Enable `q_point_flag` selector
+        self.usage.enable(&self.q_point_flag, region, offset)?;
+
+        let point = self.point(value, offset, region)?;
+
+        // Assign `x_inv`, which is 0 if x = 0
+        let x_inv = point
+            .x
+            .value()
+            .map(|x| x.invert().unwrap_or(C::Base::zero()));
+        region.assign_advice(
+            || "x_inv",
+            self.x_inv,
+            offset,
+            || x_inv.ok_or(Error::SynthesisError),
+        )?;
+
+        // Assign `is_identity` flag
+        let is_identity = point.is_identity().map(C::Base::from);
+        let is_identity_var = region.assign_advice(
+            || "is_identity",
+            self.is_identity,
+            offset,
+            || is_identity.ok_or(Error::SynthesisError),
+        )?;
+
+        Ok((point, CellValue::new(is_identity_var, is_identity)))
+    }
+
+    /// Copies the given coordinates into this region, constraining them to
+    /// encode either the identity (0, 0) or a valid curve point.
+    pub(super) fn point_from_coords(
+        &self,
+        x: CellValue<C::Base>,
+        y: CellValue<C::Base>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<EccPoint<C>, Error> {
+        // Enable `q_point` selector
+        self.usage.enable(&self.q_point, region, offset)?;
+
+        let x = copy(region, || "x", self.x, offset, &x)?;
+        let y = copy(region, || "y", self.y, offset, &y)?;
+
+        Ok(EccPoint { x, y })
+    }
+
     /// Assigns a non-identity point.
     pub(super) fn point_non_id(
         &self,
-        value: Option<pallas::Affine>,
+        value: Option<C>,
         offset: usize,
-        region: &mut Region<'_, pallas::Base>,
-    ) -> Result<NonIdentityEccPoint, Error> {
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
         // Enable `q_point_non_id` selector
-        self.q_point_non_id.enable(region, offset)?;
+        self.usage.enable(&self.q_point_non_id, region, offset)?;
 
         if let Some(value) = value {
             // Return an error if the point is the identity.
-            if value == pallas::Affine::identity() {
+            if value == C::identity() {
                 return Err(Error::SynthesisError);
             }
         };
@@ -144,15 +274,37 @@ impl Config {
         self.assign_xy(value, offset, region)
             .map(|(x, y)| NonIdentityEccPoint { x, y })
     }
+
+    /// Copies an assigned point into this region, constraining it to be a
+    /// non-identity point.
+    pub(super) fn copy_non_id(
+        &self,
+        point: &EccPoint<C>,
+        offset: usize,
+        region: &mut Region<'_, C::Base>,
+    ) -> Result<NonIdentityEccPoint<C>, Error> {
+        // Enable `q_point_non_id` selector
+        self.usage.enable(&self.q_point_non_id, region, offset)?;
+
+        // Return an error if the point is the identity.
+        if point.is_identity() == Some(true) {
+            return Err(Error::SynthesisError);
+        }
+
+        let x = copy(region, || "x", self.x, offset, &point.x)?;
+        let y = copy(region, || "y", self.y, offset, &point.y)?;
+
+        Ok(NonIdentityEccPoint { x, y })
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use halo2::circuit::Layouter;
+    use group::prime::PrimeCurveAffine;
+    use halo2::{circuit::Layouter, plonk::Error};
     use pasta_curves::pallas;
 
-    use super::*;
-    use crate::ecc::{EccInstructions, NonIdentityPoint};
+    use crate::ecc::{EccInstructions, FixedPoints, NonIdentityPoint};
 
     pub fn test_witness_non_id<
         EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
@@ -170,4 +322,464 @@ pub mod tests {
 
         Ok(())
     }
+
+    pub fn test_witness_points<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::ecc::Point;
+        use group::{Curve, Group};
+
+        // Witness 16 points in a single region, including the identity.
+        let mut values: Vec<_> = (0..15)
+            .map(|_| Some(pallas::Point::random(rand::rngs::OsRng).to_affine()))
+            .collect();
+        values.push(Some(pallas::Affine::identity()));
+        let points = Point::new_batch(chip.clone(), layouter.namespace(|| "points"), &values)?;
+        assert_eq!(points.len(), values.len());
+
+        // The points must be returned in input order.
+        for idx in [0, 7, 15].iter() {
+            let expected = Point::new(
+                chip.clone(),
+                layouter.namespace(|| format!("expected point {}", idx)),
+                values[*idx],
+            )?;
+            points[*idx].constrain_equal(
+                layouter.namespace(|| format!("constrain point {}", idx)),
+                &expected,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_witness_with_flag<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::{ecc::Point, utilities::Var};
+        use ff::Field;
+        use group::{Curve, Group};
+
+        // The identity should have its flag set.
+        {
+            let (_, is_identity) = Point::new_with_flag(
+                chip.clone(),
+                layouter.namespace(|| "witness identity with flag"),
+                Some(pallas::Affine::identity()),
+            )?;
+            assert_eq!(is_identity.value(), Some(pallas::Base::one()));
+        }
+
+        // A random non-identity point should not have its flag set.
+        {
+            let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let (_, is_identity) = Point::new_with_flag(
+                chip,
+                layouter.namespace(|| "witness random point with flag"),
+                Some(p_val),
+            )?;
+            assert_eq!(is_identity.value(), Some(pallas::Base::zero()));
+        }
+
+        Ok(())
+    }
+
+    pub fn test_point_value<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::ecc::Point;
+        use group::{Curve, Group};
+
+        // A random point should be read back unchanged.
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = Point::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+        assert_eq!(p.value(), Some(p_val));
+
+        // The identity is read back from (0, 0).
+        let identity = Point::new(
+            chip,
+            layouter.namespace(|| "identity"),
+            Some(pallas::Affine::identity()),
+        )?;
+        assert_eq!(identity.value(), Some(pallas::Affine::identity()));
+
+        Ok(())
+    }
+
+    pub fn test_assert_non_identity<
+        EccChip: EccInstructions<pallas::Affine> + Clone + Eq + std::fmt::Debug,
+    >(
+        chip: EccChip,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::ecc::Point;
+        use group::{Curve, Group};
+
+        // Asserting the identity is non-identity should return an error.
+        {
+            let identity = Point::new(
+                chip.clone(),
+                layouter.namespace(|| "identity"),
+                Some(pallas::Affine::identity()),
+            )?;
+            identity
+                .assert_non_identity(layouter.namespace(|| "assert 𝒪 is non-identity"))
+                .expect_err("asserting 𝒪 is non-identity should return an error");
+        }
+
+        // A random point should be rewrapped as a non-identity point.
+        {
+            let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let p = Point::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+            let p_non_id =
+                p.assert_non_identity(layouter.namespace(|| "assert P is non-identity"))?;
+
+            let expected = NonIdentityPoint::new(
+                chip.clone(),
+                layouter.namespace(|| "expected P"),
+                Some(p_val),
+            )?;
+            p_non_id.constrain_equal(layouter.namespace(|| "P == expected"), &expected)?;
+        }
+
+        // A point converted by value can be used in incomplete addition.
+        {
+            let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let q_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let p = Point::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+            let q = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "Q"), Some(q_val))?;
+
+            let p = p.try_into_non_identity(layouter.namespace(|| "P into non-identity"))?;
+            let sum = p.add_incomplete(layouter.namespace(|| "P + Q"), &q)?;
+
+            let expected = NonIdentityPoint::new(
+                chip,
+                layouter.namespace(|| "expected P + Q"),
+                Some((p_val + q_val).to_affine()),
+            )?;
+            sum.constrain_equal(layouter.namespace(|| "P + Q == expected"), &expected)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn test_extract_y<F: FixedPoints<pallas::Affine>>(
+        chip: super::super::EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::utilities::{UtilitiesInstructions, Var};
+        use group::{Curve, Group};
+        use halo2::circuit::Chip;
+        use pasta_curves::arithmetic::CurveAffine;
+
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = NonIdentityPoint::new(chip.clone(), layouter.namespace(|| "P"), Some(p_val))?;
+
+        let y_val = *p_val.coordinates().unwrap().y();
+        let y = p.extract_y();
+        assert_eq!(y.value(), Some(y_val));
+
+        // The extracted cell should be usable in copy constraints.
+        let column = chip.config().advices[0];
+        let expected = chip.load_private(layouter.namespace(|| "y"), column, Some(y_val))?;
+        layouter.assign_region(
+            || "constrain y",
+            |mut region| region.constrain_equal(y.cell(), expected.cell()),
+        )
+    }
+
+    pub fn test_witness_point_strict<F: FixedPoints<pallas::Affine>>(
+        chip: super::super::EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use group::{Curve, Group};
+
+        // The identity is still accepted, but reported.
+        let (identity, is_identity) = chip.witness_point_strict(
+            &mut layouter.namespace(|| "identity"),
+            Some(pallas::Affine::identity()),
+        )?;
+        assert_eq!(is_identity, Some(true));
+        assert_eq!(identity.point(), Some(pallas::Affine::identity()));
+
+        // A random point is not reported.
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let (p, is_identity) =
+            chip.witness_point_strict(&mut layouter.namespace(|| "P"), Some(p_val))?;
+        assert_eq!(is_identity, Some(false));
+        assert_eq!(p.point(), Some(p_val));
+
+        Ok(())
+    }
+
+    pub fn test_witness_point_from_x_and_sign<F: FixedPoints<pallas::Affine>>(
+        chip: super::super::EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::utilities::Var;
+        use ff::Field;
+        use group::{Curve, Group};
+        use pasta_curves::arithmetic::{CurveAffine, FieldExt};
+
+        // A random point round-trips through its x-coordinate and the parity
+        // of its y-coordinate.
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let coords = p_val.coordinates().unwrap();
+        let y_is_odd = coords.y().to_bytes()[0] & 1 == 1;
+        let (p, parity) = chip.witness_point_from_x_and_sign(
+            &mut layouter.namespace(|| "P from (x, parity)"),
+            Some(*coords.x()),
+            Some(y_is_odd),
+        )?;
+        assert_eq!(p.point(), Some(p_val));
+        assert_eq!(parity.value(), Some(pallas::Base::from(y_is_odd)));
+
+        // The opposite parity recovers -P.
+        let (neg_p, parity) = chip.witness_point_from_x_and_sign(
+            &mut layouter.namespace(|| "-P from (x, parity)"),
+            Some(*coords.x()),
+            Some(!y_is_odd),
+        )?;
+        assert_eq!(neg_p.point(), Some(-p_val));
+        assert_eq!(parity.value(), Some(pallas::Base::from(!y_is_odd)));
+
+        // An x-coordinate for which x^3 + b is not a square is rejected.
+        let x = std::iter::repeat_with(pallas::Base::rand)
+            .find(|x| bool::from((x.square() * x + pallas::Affine::b()).sqrt().is_none()))
+            .unwrap();
+        chip.witness_point_from_x_and_sign(
+            &mut layouter.namespace(|| "x not on curve"),
+            Some(x),
+            Some(false),
+        )
+        .expect_err("x^3 + b is not a square");
+
+        Ok(())
+    }
+
+    pub fn test_identity<F: FixedPoints<pallas::Affine>>(
+        chip: super::super::EccChip<pallas::Affine, F>,
+        mut layouter: impl Layouter<pallas::Base>,
+    ) -> Result<(), Error> {
+        use crate::ecc::Point;
+        use group::{Curve, Group};
+
+        let identity = Point::identity(chip.clone(), layouter.namespace(|| "𝒪"))?;
+        assert_eq!(identity.inner().is_identity(), Some(true));
+
+        // P + 𝒪 = P
+        let p_val = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = Point::new(chip, layouter.namespace(|| "P"), Some(p_val))?;
+        let sum = p.add(layouter.namespace(|| "P + 𝒪"), &identity)?;
+        sum.constrain_equal(layouter.namespace(|| "P + 𝒪 = P"), &p)?;
+
+        // 𝒪 + 𝒪 = 𝒪
+        let sum = identity.add(layouter.namespace(|| "𝒪 + 𝒪"), &identity)?;
+        sum.constrain_equal(layouter.namespace(|| "𝒪 + 𝒪 = 𝒪"), &identity)?;
+        Ok(())
+    }
+
+    #[test]
+    fn witness_point_from_coords() {
+        use crate::{
+            ecc::{
+                chip::{EccChip, EccConfig},
+                tests::FixedBase,
+            },
+            utilities::UtilitiesInstructions,
+        };
+        use ff::Field;
+        use group::{Curve, Group};
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+        use pasta_curves::arithmetic::CurveAffine;
+
+        #[derive(Default)]
+        struct MyCircuit {
+            x: Option<pallas::Base>,
+            y: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                let column = config.advices[0];
+
+                let x = chip.load_private(layouter.namespace(|| "x"), column, self.x)?;
+                let y = chip.load_private(layouter.namespace(|| "y"), column, self.y)?;
+                chip.witness_point_from_coords(&mut layouter, x, y)?;
+
+                Ok(())
+            }
+        }
+
+        // A valid curve point should pass
+        {
+            let p = pallas::Point::random(rand::rngs::OsRng).to_affine();
+            let p = p.coordinates().unwrap();
+            let circuit = MyCircuit {
+                x: Some(*p.x()),
+                y: Some(*p.y()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // The identity encoded as (0, 0) should pass
+        {
+            let circuit = MyCircuit {
+                x: Some(pallas::Base::zero()),
+                y: Some(pallas::Base::zero()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Coordinates that are not on the curve should fail
+        {
+            let circuit = MyCircuit {
+                x: Some(pallas::Base::one()),
+                y: Some(pallas::Base::one()),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn assert_not_small_order() {
+        use crate::{
+            ecc::{
+                chip::{EccChip, EccConfig, EccPoint},
+                tests::FixedBase,
+                EccInstructions,
+            },
+            utilities::UtilitiesInstructions,
+        };
+        use ff::Field;
+        use group::{Curve, Group};
+        use halo2::{
+            circuit::SimpleFloorPlanner,
+            dev::MockProver,
+            plonk::{Circuit, ConstraintSystem},
+        };
+        use pasta_curves::arithmetic::CurveAffine;
+
+        #[derive(Default)]
+        struct MyCircuit {
+            x: Option<pallas::Base>,
+            y: Option<pallas::Base>,
+            // A mock cofactor; `None` uses the chip's `C::COFACTOR`.
+            cofactor: Option<u64>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self {
+                    cofactor: self.cofactor,
+                    ..Default::default()
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                let column = config.advices[0];
+
+                // Load the coordinates without constraining them to a curve point.
+                let x = chip.load_private(layouter.namespace(|| "x"), column, self.x)?;
+                let y = chip.load_private(layouter.namespace(|| "y"), column, self.y)?;
+                let point = EccPoint { x, y };
+
+                match self.cofactor {
+                    None => chip.assert_not_small_order(&mut layouter, &point),
+                    Some(cofactor) => {
+                        let cleared =
+                            chip.mul_by_cofactor_non_id(&mut layouter, &point, cofactor)?;
+                        if let (Some(x), Some(y)) = (self.x, self.y) {
+                            let p = pallas::Affine::from_xy(x, y).unwrap();
+                            let expected = (p * pallas::Scalar::from_u64(cofactor)).to_affine();
+                            assert_eq!(cleared.point(), Some(expected));
+                        }
+                        Ok(())
+                    }
+                }
+            }
+        }
+
+        // The identity is rejected at synthesis time by `copy_non_id`, so
+        // either proving or verification fails.
+        let fails = |circuit: MyCircuit| match MockProver::<pallas::Base>::run(11, &circuit, vec![])
+        {
+            Ok(prover) => prover.verify().is_err(),
+            Err(_) => true,
+        };
+
+        let p = pallas::Point::random(rand::rngs::OsRng).to_affine();
+        let p = p.coordinates().unwrap();
+
+        for cofactor in [None, Some(1), Some(6)].iter().copied() {
+            // A random curve point should pass
+            {
+                let circuit = MyCircuit {
+                    x: Some(*p.x()),
+                    y: Some(*p.y()),
+                    cofactor,
+                };
+                let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+                assert_eq!(prover.verify(), Ok(()));
+            }
+
+            // The identity should fail, whatever the cofactor
+            assert!(fails(MyCircuit {
+                x: Some(pallas::Base::zero()),
+                y: Some(pallas::Base::zero()),
+                cofactor,
+            }));
+        }
+
+        // Coordinates that are not on the curve should fail
+        assert!(fails(MyCircuit {
+            x: Some(pallas::Base::one()),
+            y: Some(pallas::Base::one()),
+            cofactor: None,
+        }));
+    }
 }
@@ -0,0 +1,194 @@
+//! A Pedersen hash over the fixed bases of the ECC chip.
+
+use halo2::{circuit::Layouter, plonk::Error};
+
+use super::{
+    chip::{EccChip, EccCurve, L_PALLAS_BASE},
+    EccInstructions, FixedPoint, FixedPoints, Point,
+};
+use crate::utilities::CellValue;
+
+/// A Pedersen hash of a base field element.
+///
+/// The input is decomposed into its canonical little-endian bits, which are
+/// split into chunks of `chunk_bits` bits. The hash is
+///
+/// $$\mathsf{PedersenHash}(m) = \sum_i [m_i] G_i,$$
+///
+/// where $m_i$ is the $i$-th chunk and $G_i$ the $i$-th fixed base.
+#[derive(Clone, Debug)]
+pub struct PedersenHash<C: EccCurve, F: FixedPoints<C>> {
+    chip: EccChip<C, F>,
+    chunk_bits: usize,
+}
+
+impl<C: EccCurve, F: FixedPoints<C>> PedersenHash<C, F> {
+    /// Constructs a Pedersen hash with chunks of `chunk_bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_bits` is zero, or if it is not less than the number of
+    /// bits in a base field element.
+    pub fn new(chip: EccChip<C, F>, chunk_bits: usize) -> Self {
+        assert!(chunk_bits > 0 && chunk_bits < L_PALLAS_BASE);
+        PedersenHash { chip, chunk_bits }
+    }
+
+    /// The number of chunks, and therefore of fixed bases, needed to hash a
+    /// base field element.
+    pub fn num_chunks(&self) -> usize {
+        (L_PALLAS_BASE + self.chunk_bits - 1) / self.chunk_bits
+    }
+
+    /// Hashes `input`, using `bases[i]` as the generator for the `i`-th chunk.
+    ///
+    /// Each chunk is multiplied with [`EccChip::mul_fixed_from_bits`], which
+    /// recombines it and passes it to
+    /// [`EccInstructions::mul_fixed_base_field_elem`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bases` does not contain exactly [`PedersenHash::num_chunks`]
+    /// bases.
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        input: CellValue<C::Base>,
+        bases: &[FixedPoint<C, EccChip<C, F>>],
+    ) -> Result<Point<C, EccChip<C, F>>, Error> {
+        assert_eq!(bases.len(), self.num_chunks());
+
+        let bits = self
+            .chip
+            .scalar_var_bits(&mut layouter.namespace(|| "input bits"), &input)?;
+
+        let mut acc: Option<Point<C, EccChip<C, F>>> = None;
+        for (idx, (chunk, base)) in bits.chunks(self.chunk_bits).zip(bases.iter()).enumerate() {
+            assert_eq!(base.chip, self.chip);
+            let term = self.chip.mul_fixed_from_bits(
+                layouter.namespace(|| format!("[m_{}] G_{}", idx, idx)),
+                chunk,
+                &base.inner,
+            )?;
+            let term = Point::from_inner(self.chip.clone(), term);
+
+            acc = Some(match acc {
+                Some(acc) => acc.add(
+                    layouter.namespace(|| format!("+ [m_{}] G_{}", idx, idx)),
+                    &term,
+                )?,
+                None => term,
+            });
+        }
+
+        Ok(acc.expect("there is at least one chunk"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::{Field, PrimeFieldBits};
+    use group::{Curve, Group};
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    use super::PedersenHash;
+    use crate::{
+        ecc::{
+            chip::{EccChip, EccConfig},
+            tests::FixedBase,
+            FixedPoint, FixedPoints, NonIdentityPoint,
+        },
+        utilities::UtilitiesInstructions,
+    };
+
+    #[test]
+    fn pedersen_hash() {
+        // Two 128-bit chunks cover the 255 bits of the input.
+        const CHUNK_BITS: usize = 128;
+        let bases = [FixedBase::FullWidth, FixedBase::FullWidthAlt];
+
+        struct MyCircuit {
+            input: Option<pallas::Base>,
+            expected: Option<pallas::Affine>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = EccConfig<pallas::Affine>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    input: None,
+                    expected: None,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                crate::ecc::tests::configure::<pallas::Affine, FixedBase>(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let chip = EccChip::<pallas::Affine, FixedBase>::construct(config.clone());
+                config.lookup_config.load(&mut layouter)?;
+
+                let hasher = PedersenHash::new(chip.clone(), CHUNK_BITS);
+                assert_eq!(hasher.num_chunks(), 2);
+
+                let bases = [
+                    FixedPoint::from_inner(chip.clone(), FixedBase::FullWidth),
+                    FixedPoint::from_inner(chip.clone(), FixedBase::FullWidthAlt),
+                ];
+                let input = chip.load_private(
+                    layouter.namespace(|| "input"),
+                    config.advices[0],
+                    self.input,
+                )?;
+                let hash = hasher.hash(layouter.namespace(|| "hash"), input, &bases)?;
+
+                let expected =
+                    NonIdentityPoint::new(chip, layouter.namespace(|| "expected"), self.expected)?;
+                hash.constrain_equal(layouter.namespace(|| "hash == expected"), &expected)?;
+                Ok(())
+            }
+        }
+
+        // Compute the hash out of circuit.
+        let input = pallas::Base::rand();
+        let expected = {
+            let bits: Vec<bool> = input.to_le_bits().iter().by_val().take(255).collect();
+            bits.chunks(CHUNK_BITS)
+                .zip(bases.iter())
+                .fold(pallas::Point::identity(), |acc, (chunk, base)| {
+                    let chunk = chunk.iter().rev().fold(pallas::Scalar::zero(), |acc, bit| {
+                        acc.double() + pallas::Scalar::from(*bit)
+                    });
+                    acc + base.generator() * chunk
+                })
+                .to_affine()
+        };
+
+        let circuit = MyCircuit {
+            input: Some(input),
+            expected: Some(expected),
+        };
+        let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A different expected point fails.
+        let circuit = MyCircuit {
+            input: Some(input),
+            expected: Some(pallas::Point::random(rand::rngs::OsRng).to_affine()),
+        };
+        let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
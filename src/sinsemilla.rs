@@ -1,17 +1,21 @@
 //! Gadget and chips for the Sinsemilla hash function.
 use crate::{
     ecc::{self, EccInstructions, FixedPoints},
+    primitives::sinsemilla::Q_PERSONALIZATION,
     utilities::Var,
 };
 use ff::PrimeField;
+use group::Curve;
 use halo2::{circuit::Layouter, plonk::Error};
-use pasta_curves::arithmetic::{CurveAffine, FieldExt};
+use pasta_curves::arithmetic::{CurveAffine, CurveExt, FieldExt};
 use std::{convert::TryInto, fmt::Debug};
 
 pub mod chip;
 pub mod merkle;
 mod message;
 
+pub use message::MessageError;
+
 /// The set of circuit instructions required to use the [`Sinsemilla`](https://zcash.github.io/halo2/design/gadgets/sinsemilla.html) gadget.
 /// This trait is bounded on two constant parameters: `K`, the number of bits
 /// in each word accepted by the Sinsemilla hash, and `MAX_WORDS`, the maximum
@@ -82,6 +86,20 @@ pub trait SinsemillaInstructions<C: CurveAffine, const K: usize, const MAX_WORDS
         message: Self::Message,
     ) -> Result<(Self::NonIdentityPoint, Vec<Self::RunningSum>), Error>;
 
+    /// Hashes a message to an ECC curve point, starting from a previously
+    /// assigned point `Q` instead of a domain's fixed `Q`.
+    ///
+    /// This allows hashes to be chained, using the output of one hash as the
+    /// starting point of the next.
+    #[allow(non_snake_case)]
+    #[allow(clippy::type_complexity)]
+    fn hash_to_point_from_point(
+        &self,
+        layouter: impl Layouter<C::Base>,
+        Q: &Self::NonIdentityPoint,
+        message: Self::Message,
+    ) -> Result<(Self::NonIdentityPoint, Vec<Self::RunningSum>), Error>;
+
     /// Extracts the x-coordinate of the output of a Sinsemilla hash.
     fn extract(point: &Self::NonIdentityPoint) -> Self::X;
 }
@@ -275,6 +293,23 @@ where
         }
     }
 
+    #[allow(non_snake_case)]
+    /// Constructs a new `HashDomain` whose `Q` is derived from the given
+    /// domain personalization string, as in
+    /// [`primitives::sinsemilla::HashDomain::new`](crate::primitives::sinsemilla::HashDomain::new).
+    pub fn from_personalization(
+        sinsemilla_chip: SinsemillaChip,
+        ecc_chip: EccChip,
+        personalization: &str,
+    ) -> Self {
+        let Q = C::CurveExt::hash_to_curve(Q_PERSONALIZATION)(personalization.as_bytes());
+        HashDomain {
+            sinsemilla_chip,
+            ecc_chip,
+            Q: Q.to_affine(),
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     /// $\mathsf{SinsemillaHashToPoint}$ from [§ 5.4.1.9][concretesinsemillahash].
     ///
@@ -290,6 +325,38 @@ where
             .map(|(point, zs)| (ecc::NonIdentityPoint::from_inner(self.ecc_chip.clone(), point), zs))
     }
 
+    /// Hashes each of the given messages in turn, using the output of each
+    /// hash as the starting point `Q` of the next, and returns the output of
+    /// the last hash.
+    ///
+    /// The first message is hashed from this domain's `Q`. This allows inputs
+    /// longer than `MAX_WORDS` words to be hashed in chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `messages` is empty.
+    pub fn hash_chained(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        messages: Vec<Message<C, SinsemillaChip, K, MAX_WORDS>>,
+    ) -> Result<ecc::NonIdentityPoint<C, EccChip>, Error> {
+        let mut messages = messages.into_iter();
+        let first = messages.next().expect("at least one message must be hashed");
+        let (mut acc, _) = self.hash_to_point(layouter.namespace(|| "hash message 0"), first)?;
+
+        for (idx, message) in messages.enumerate() {
+            assert_eq!(self.sinsemilla_chip, message.chip);
+            let (point, _) = self.sinsemilla_chip.hash_to_point_from_point(
+                layouter.namespace(|| format!("hash message {}", idx + 1)),
+                acc.inner(),
+                message.inner,
+            )?;
+            acc = ecc::NonIdentityPoint::from_inner(self.ecc_chip.clone(), point);
+        }
+
+        Ok(acc)
+    }
+
     /// $\mathsf{SinsemillaHash}$ from [§ 5.4.1.9][concretesinsemillahash].
     ///
     /// [concretesinsemillahash]: https://zips.z.cash/protocol/protocol.pdf#concretesinsemillahash
@@ -305,6 +372,66 @@ where
     }
 }
 
+/// A gadget hashing a [`Message`] to a non-identity curve point using
+/// $\mathsf{SinsemillaHashToPoint}$ in a given domain.
+///
+/// Unlike [`HashDomain::hash_to_point`], the output is additionally constrained
+/// by the ECC chip to be a valid non-identity point on the curve.
+pub struct HashToPoint<C: CurveAffine, SinsemillaChip, EccChip, const K: usize, const MAX_WORDS: usize>
+where
+    SinsemillaChip: SinsemillaInstructions<C, K, MAX_WORDS> + Clone + Debug + Eq,
+    EccChip: EccInstructions<
+            C,
+            NonIdentityPoint = <SinsemillaChip as SinsemillaInstructions<C, K, MAX_WORDS>>::NonIdentityPoint,
+            FixedPoints = <SinsemillaChip as SinsemillaInstructions<C, K, MAX_WORDS>>::FixedPoints,
+        > + Clone
+        + Debug
+        + Eq,
+{
+    domain: HashDomain<C, SinsemillaChip, EccChip, K, MAX_WORDS>,
+}
+
+impl<C: CurveAffine, SinsemillaChip, EccChip, const K: usize, const MAX_WORDS: usize>
+    HashToPoint<C, SinsemillaChip, EccChip, K, MAX_WORDS>
+where
+    SinsemillaChip: SinsemillaInstructions<C, K, MAX_WORDS> + Clone + Debug + Eq,
+    EccChip: EccInstructions<
+            C,
+            NonIdentityPoint = <SinsemillaChip as SinsemillaInstructions<C, K, MAX_WORDS>>::NonIdentityPoint,
+            FixedPoints = <SinsemillaChip as SinsemillaInstructions<C, K, MAX_WORDS>>::FixedPoints,
+        > + Clone
+        + Debug
+        + Eq,
+{
+    /// Constructs a new `HashToPoint` gadget for the given domain.
+    pub fn new(
+        sinsemilla_chip: SinsemillaChip,
+        ecc_chip: EccChip,
+        domain: &SinsemillaChip::HashDomains,
+    ) -> Self {
+        HashToPoint {
+            domain: HashDomain::new(sinsemilla_chip, ecc_chip, domain),
+        }
+    }
+
+    /// Hashes the given message to a non-identity curve point.
+    ///
+    /// This returns both the resulting point, as well as the message
+    /// decomposition in the form of intermediate values in a cumulative sum.
+    #[allow(clippy::type_complexity)]
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        message: Message<C, SinsemillaChip, K, MAX_WORDS>,
+    ) -> Result<(ecc::NonIdentityPoint<C, EccChip>, Vec<SinsemillaChip::RunningSum>), Error> {
+        let (point, zs) = self
+            .domain
+            .hash_to_point(layouter.namespace(|| "hash to point"), message)?;
+        point.constrain_non_identity(layouter.namespace(|| "constrain non-identity"))?;
+        Ok((point, zs))
+    }
+}
+
 /// Trait allowing circuit's Sinsemilla CommitDomains to be enumerated.
 pub trait CommitDomains<C: CurveAffine, F: FixedPoints<C>, H: HashDomains<C>>:
     Clone + Debug + Eq
@@ -372,6 +499,9 @@ where
     #[allow(clippy::type_complexity)]
     /// $\mathsf{SinsemillaCommit}$ from [§ 5.4.8.4][concretesinsemillacommit].
     ///
+    /// This returns the commitment, the blinding factor `r` as witnessed by the
+    /// fixed-base multiplication `[r] R`, and the message decomposition.
+    ///
     /// [concretesinsemillacommit]: https://zips.z.cash/protocol/nu5.pdf#concretesinsemillacommit
     pub fn commit(
         &self,
@@ -381,15 +511,16 @@ where
     ) -> Result<
         (
             ecc::Point<C, EccChip>,
+            ecc::ScalarFixed<C, EccChip>,
             Vec<SinsemillaChip::RunningSum>,
         ),
         Error,
     > {
         assert_eq!(self.M.sinsemilla_chip, message.chip);
-        let (blind, _) = self.R.mul(layouter.namespace(|| "[r] R"), r)?;
+        let (blind, r) = self.R.mul(layouter.namespace(|| "[r] R"), r)?;
         let (p, zs) = self.M.hash_to_point(layouter.namespace(|| "M"), message)?;
         let commitment = p.add(layouter.namespace(|| "M + [r] R"), &blind)?;
-        Ok((commitment, zs))
+        Ok((commitment, r, zs))
     }
 
     #[allow(clippy::type_complexity)]
@@ -403,7 +534,7 @@ where
         r: Option<C::Scalar>,
     ) -> Result<(ecc::X<C, EccChip>, Vec<SinsemillaChip::RunningSum>), Error> {
         assert_eq!(self.M.sinsemilla_chip, message.chip);
-        let (p, zs) = self.commit(layouter.namespace(|| "commit"), message, r)?;
+        let (p, _, zs) = self.commit(layouter.namespace(|| "commit"), message, r)?;
         Ok((p.extract_p(), zs))
     }
 }
@@ -413,16 +544,17 @@ pub mod tests {
     use crate::{
         ecc::{
             chip::{compute_lagrange_coeffs, find_zs_and_us, EccChip, EccConfig, NUM_WINDOWS},
-            FixedPoints, NonIdentityPoint, H,
+            FixedPoints, NonIdentityPoint, Point, H,
         },
         primitives::sinsemilla,
         sinsemilla::{
             chip::{SinsemillaChip, SinsemillaConfig},
-            CommitDomain, CommitDomains, HashDomain, HashDomains, Message,
+            CommitDomain, CommitDomains, HashDomain, HashDomains, HashToPoint, Message,
         },
-        utilities::lookup_range_check::LookupRangeCheckConfig,
+        utilities::{lookup_range_check::LookupRangeCheckConfig, Var},
     };
 
+    use ff::Field;
     use halo2::{
         circuit::{Layouter, SimpleFloorPlanner},
         plonk::{Circuit, ConstraintSystem, Error},
@@ -487,7 +619,7 @@ pub mod tests {
 
     impl Circuit<pallas::Base> for MyCircuit {
         type Config = (
-            EccConfig,
+            EccConfig<pallas::Affine>,
             SinsemillaConfig<Hash, Commit, FixedBase>,
             SinsemillaConfig<Hash, Commit, FixedBase>,
         );
@@ -537,7 +669,7 @@ pub mod tests {
 
             let range_check = LookupRangeCheckConfig::configure(meta, advices[9], table_idx);
 
-            let ecc_config = EccChip::<FixedBase>::configure(
+            let ecc_config = EccChip::<pallas::Affine, FixedBase>::configure(
                 meta,
                 advices,
                 lagrange_coeffs,
@@ -617,6 +749,130 @@ pub mod tests {
                 )?;
             }
 
+            // Test hash chaining: the output of each hash is the `Q` of the next.
+            {
+                let chip1 = SinsemillaChip::construct(config.1.clone());
+
+                let hash_domain = HashDomain::new(chip1.clone(), ecc_chip.clone(), &Hash);
+
+                let bitstrings: Vec<Vec<bool>> = [100, 200]
+                    .iter()
+                    .map(|len| (0..*len).map(|_| rand::random::<bool>()).collect())
+                    .collect();
+
+                let messages = bitstrings
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, bitstring)| {
+                        Message::from_bitstring(
+                            chip1.clone(),
+                            layouter.namespace(|| format!("witness chained message {}", idx)),
+                            bitstring.iter().map(|b| Some(*b)).collect(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let result =
+                    hash_domain.hash_chained(layouter.namespace(|| "hash chained"), messages)?;
+
+                // Reference implementation: chain the out-of-circuit hashes.
+                let expected_result = {
+                    let point = bitstrings.iter().fold(hash_domain.Q.to_curve(), |q, bits| {
+                        sinsemilla::HashDomain { Q: q }
+                            .hash_to_point(bits.iter().cloned())
+                            .unwrap()
+                    });
+
+                    NonIdentityPoint::new(
+                        ecc_chip.clone(),
+                        layouter.namespace(|| "Witness expected chained result"),
+                        Some(point.to_affine()),
+                    )?
+                };
+
+                result.constrain_equal(
+                    layouter.namespace(|| "chained result == expected result"),
+                    &expected_result,
+                )?;
+            }
+
+            // Test domain separation: the same message hashed under two domains.
+            {
+                let chip1 = SinsemillaChip::construct(config.1.clone());
+
+                let domain_a =
+                    HashDomain::from_personalization(chip1.clone(), ecc_chip.clone(), "domain-a");
+                let domain_b =
+                    HashDomain::from_personalization(chip1.clone(), ecc_chip.clone(), "domain-b");
+                assert_eq!(
+                    domain_a.Q,
+                    sinsemilla::HashDomain::new("domain-a").Q.to_affine()
+                );
+
+                let message: Vec<Option<bool>> =
+                    (0..100).map(|_| Some(rand::random::<bool>())).collect();
+
+                let results = [("domain-a", &domain_a), ("domain-b", &domain_b)]
+                    .iter()
+                    .map(|(name, domain)| {
+                        let message = Message::from_bitstring(
+                            chip1.clone(),
+                            layouter.namespace(|| format!("witness message for {}", name)),
+                            message.clone(),
+                        )?;
+                        domain
+                            .hash_to_point(
+                                layouter.namespace(|| format!("hash in {}", name)),
+                                message,
+                            )
+                            .map(|(point, _)| point)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let (result_a, result_b) = (results[0].clone(), &results[1]);
+                assert_ne!(result_a.inner().point(), result_b.inner().point());
+
+                let equal = Point::from(result_a)
+                    .equals(layouter.namespace(|| "domain-a == domain-b"), result_b)?;
+                assert_eq!(equal.value(), Some(pallas::Base::zero()));
+            }
+
+            // Test HashToPoint gadget with a fixed 40-bit message.
+            {
+                let chip1 = SinsemillaChip::construct(config.1.clone());
+
+                let hash_to_point = HashToPoint::new(chip1.clone(), ecc_chip.clone(), &Hash);
+
+                let message: Vec<Option<bool>> = (0..40)
+                    .map(|i| Some((0xab_cdef_1234u64 >> i) & 1 == 1))
+                    .collect();
+
+                let (result, _) = {
+                    let message = Message::from_bitstring(
+                        chip1,
+                        layouter.namespace(|| "witness 40-bit message"),
+                        message.clone(),
+                    )?;
+                    hash_to_point.hash(layouter.namespace(|| "hash 40-bit message"), message)?
+                };
+
+                let expected_result = {
+                    let message: Vec<bool> = message.into_iter().map(|b| b.unwrap()).collect();
+                    let point = sinsemilla::HashDomain { Q: Q.to_curve() }
+                        .hash_to_point(message.into_iter())
+                        .unwrap();
+
+                    NonIdentityPoint::new(
+                        ecc_chip.clone(),
+                        layouter.namespace(|| "Witness expected 40-bit hash"),
+                        Some(point.to_affine()),
+                    )?
+                };
+
+                result.constrain_equal(
+                    layouter.namespace(|| "40-bit hash == expected result"),
+                    &expected_result,
+                )?;
+            }
+
             // Test commit domain.
             {
                 let chip2 = SinsemillaChip::construct(config.2);
@@ -628,14 +884,15 @@ pub mod tests {
                 let message: Vec<Option<bool>> =
                     (0..500).map(|_| Some(rand::random::<bool>())).collect();
 
-                let (result, _) = {
+                let (result, r, _) = {
                     let message = Message::from_bitstring(
-                        chip2,
+                        chip2.clone(),
                         layouter.namespace(|| "witness message"),
                         message.clone(),
                     )?;
                     commit_domain.commit(layouter.namespace(|| "commit"), message, Some(r_val))?
                 };
+                assert_eq!(r.inner().value(), Some(r_val));
 
                 // Witness expected result.
                 let expected_result = {
@@ -655,7 +912,7 @@ pub mod tests {
                     };
 
                     NonIdentityPoint::new(
-                        ecc_chip,
+                        ecc_chip.clone(),
                         layouter.namespace(|| "Witness expected result"),
                         expected_result,
                     )?
@@ -665,6 +922,39 @@ pub mod tests {
                     layouter.namespace(|| "result == expected result"),
                     &expected_result,
                 )?;
+
+                // Commit to a fixed 40-bit message with a fixed blinding factor.
+                let r_val = pallas::Scalar::from_u64(0x0123_4567_89ab_cdef);
+                let message: Vec<bool> =
+                    (0..40).map(|i| (0xab_cdef_1234u64 >> i) & 1 == 1).collect();
+
+                let (result, r, _) = {
+                    let message = Message::from_bitstring(
+                        chip2,
+                        layouter.namespace(|| "witness 40-bit message"),
+                        message.iter().map(|b| Some(*b)).collect(),
+                    )?;
+                    commit_domain.commit(
+                        layouter.namespace(|| "commit to 40-bit message"),
+                        message,
+                        Some(r_val),
+                    )?
+                };
+                assert_eq!(r.inner().value(), Some(r_val));
+
+                let expected_result = {
+                    let point = COMMIT_DOMAIN.commit(message.into_iter(), &r_val).unwrap();
+                    NonIdentityPoint::new(
+                        ecc_chip,
+                        layouter.namespace(|| "Witness expected 40-bit commitment"),
+                        Some(point.to_affine()),
+                    )?
+                };
+
+                result.constrain_equal(
+                    layouter.namespace(|| "40-bit commitment == expected result"),
+                    &expected_result,
+                )?;
             }
 
             Ok(())
@@ -681,6 +971,116 @@ pub mod tests {
         assert_eq!(prover.verify(), Ok(()))
     }
 
+    #[test]
+    fn shared_range_check() {
+        use halo2::dev::MockProver;
+
+        struct MyCircuit;
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = (
+                EccConfig<pallas::Affine>,
+                SinsemillaConfig<Hash, Commit, FixedBase>,
+            );
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let advices = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+
+                // Shared fixed column for loading constants
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                let table_idx = meta.lookup_table_column();
+                let lagrange_coeffs = [
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                    meta.fixed_column(),
+                ];
+                let lookup = (
+                    table_idx,
+                    meta.lookup_table_column(),
+                    meta.lookup_table_column(),
+                );
+
+                // A single range check config, used by both chips.
+                let range_check = LookupRangeCheckConfig::configure(meta, advices[9], table_idx);
+
+                let ecc_config = EccChip::<pallas::Affine, FixedBase>::configure_shared(
+                    meta,
+                    advices,
+                    lagrange_coeffs,
+                    &range_check,
+                );
+                let sinsemilla_config = SinsemillaChip::configure(
+                    meta,
+                    advices[..5].try_into().unwrap(),
+                    advices[2],
+                    lagrange_coeffs[0],
+                    lookup,
+                    range_check,
+                );
+                (ecc_config, sinsemilla_config)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let ecc_chip = EccChip::construct(config.0);
+
+                // The table is loaded once, by the Sinsemilla chip.
+                SinsemillaChip::<Hash, Commit, FixedBase>::load(config.1.clone(), &mut layouter)?;
+                let sinsemilla_chip = SinsemillaChip::construct(config.1);
+
+                // The Sinsemilla chip range-checks the message pieces.
+                let hash_domain = HashDomain::new(sinsemilla_chip.clone(), ecc_chip.clone(), &Hash);
+                let message: Vec<Option<bool>> =
+                    (0..500).map(|_| Some(rand::random::<bool>())).collect();
+                let message = Message::from_bitstring(
+                    sinsemilla_chip,
+                    layouter.namespace(|| "witness message"),
+                    message,
+                )?;
+                let (hash, _) =
+                    hash_domain.hash_to_point(layouter.namespace(|| "hash"), message)?;
+
+                // The ECC chip range-checks the base field element used as a scalar.
+                let base = crate::ecc::FixedPoint::from_inner(ecc_chip, FixedBase);
+                base.mul_base_field(
+                    layouter.namespace(|| "[x(hash)] R"),
+                    *hash.extract_p().inner(),
+                )?;
+
+                Ok(())
+            }
+        }
+
+        let prover = MockProver::run(11, &MyCircuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_sinsemilla_chip() {
@@ -6,11 +6,12 @@ use crate::{
     primitives::sinsemilla,
     {
         ecc::{chip::NonIdentityEccPoint, FixedPoints},
-        utilities::{lookup_range_check::LookupRangeCheckConfig, CellValue, Var},
+        utilities::{copy, lookup_range_check::LookupRangeCheckConfig, CellValue, Var},
     },
 };
 use std::marker::PhantomData;
 
+use ff::{Field, PrimeField};
 use halo2::{
     circuit::{Chip, Layouter},
     plonk::{
@@ -43,6 +44,11 @@ where
     /// Simple selector used to constrain hash initialization to be consistent with
     /// the y-coordinate of the domain $Q$.
     q_sinsemilla4: Selector,
+    /// Simple selector used to accumulate a weighted sum of message pieces.
+    q_weighted_sum: Selector,
+    /// Simple selector used to constrain hash initialization to be consistent with
+    /// the y-coordinate of a starting point $Q$ assigned on the previous row.
+    q_sinsemilla_chain: Selector,
     /// Fixed column used to load the y-coordinate of the domain $Q$.
     fixed_y_q: Column<Fixed>,
     /// Advice column used to store the x-coordinate of the accumulator at each
@@ -156,6 +162,8 @@ where
             q_sinsemilla1: meta.complex_selector(),
             q_sinsemilla2: meta.fixed_column(),
             q_sinsemilla4: meta.selector(),
+            q_weighted_sum: meta.selector(),
+            q_sinsemilla_chain: meta.selector(),
             fixed_y_q,
             x_a: advices[0],
             x_p: advices[1],
@@ -260,8 +268,188 @@ where
             ]
         });
 
+        // Check that the accumulator is updated with the weighted term on this row.
+        meta.create_gate("Weighted sum", |meta| {
+            let q_weighted_sum = meta.query_selector(config.q_weighted_sum);
+            let acc_cur = meta.query_advice(config.x_a, Rotation::cur());
+            let acc_next = meta.query_advice(config.x_a, Rotation::next());
+            let term = meta.query_advice(config.x_p, Rotation::cur());
+            let weight = meta.query_advice(config.bits, Rotation::cur());
+
+            // acc_next = acc_cur + weight * term
+            vec![q_weighted_sum * (acc_next - acc_cur - weight * term)]
+        });
+
+        // Check that the initial x_A, x_P, lambda_1, lambda_2 are consistent with
+        // the y-coordinate of a starting point assigned to lambda_1 on the previous row.
+        meta.create_gate("Initial y_Q from point", |meta| {
+            let q_chain = meta.query_selector(config.q_sinsemilla_chain);
+            let y_q = meta.query_advice(config.lambda_1, Rotation::prev());
+
+            // Y_A = (lambda_1 + lambda_2) * (x_a - x_r)
+            let Y_A_cur = Y_A(meta, Rotation::cur());
+
+            // 2 * y_q - Y_{A,0} = 0
+            let init_y_q_check = y_q * two - Y_A_cur;
+
+            vec![q_chain * init_y_q_check]
+        });
+
         config
     }
+
+    /// Constrains `expected` to equal the field element obtained by
+    /// concatenating `pieces`, i.e. `sum(piece_i * 2^{offset_i})`, where
+    /// `offset_i` is `K` times the total number of words in the preceding
+    /// pieces.
+    ///
+    /// The weights are loaded as constants, so a fixed column must have been
+    /// passed to [`ConstraintSystem::enable_constant`] during circuit
+    /// configuration.
+    pub fn reconstruct(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        pieces: &[MessagePiece<pallas::Base, { sinsemilla::K }>],
+        expected: CellValue<pallas::Base>,
+    ) -> Result<(), Error> {
+        let mut offset = 0;
+        let terms: Vec<_> = pieces
+            .iter()
+            .map(|piece| {
+                let weight = pallas::Base::from_u64(2).pow(&[offset as u64, 0, 0, 0]);
+                offset += sinsemilla::K * piece.num_words();
+                (piece.cell_value(), weight)
+            })
+            .collect();
+
+        let sum =
+            self.weighted_sum(layouter.namespace(|| "sum(piece_i * 2^{offset_i})"), &terms)?;
+        layouter.assign_region(
+            || "reconstructed == expected",
+            |mut region| region.constrain_equal(sum.cell(), expected.cell()),
+        )
+    }
+
+    /// Builds a message piece from `K`-bit subpieces in little-endian order,
+    /// i.e. `piece = sum(subpiece_i * 2^{K * i})`.
+    ///
+    /// Each subpiece is range-checked to `K` bits using the lookup table, and
+    /// the returned piece is constrained to equal the weighted sum of the
+    /// subpieces. As with [`SinsemillaChip::reconstruct`], a fixed column must
+    /// have been passed to [`ConstraintSystem::enable_constant`].
+    pub fn message_piece_from_subpieces(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        subpieces: &[CellValue<pallas::Base>],
+    ) -> Result<MessagePiece<pallas::Base, { sinsemilla::K }>, Error> {
+        let terms = subpieces
+            .iter()
+            .enumerate()
+            .map(|(idx, subpiece)| {
+                self.config.lookup_config.copy_check(
+                    layouter.namespace(|| format!("subpiece {} < 2^K", idx)),
+                    *subpiece,
+                    1,
+                    true,
+                )?;
+                let weight =
+                    pallas::Base::from_u64(2).pow(&[(sinsemilla::K * idx) as u64, 0, 0, 0]);
+                Ok((*subpiece, weight))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let piece =
+            self.weighted_sum(layouter.namespace(|| "sum(subpiece_i * 2^{K * i})"), &terms)?;
+        Ok(MessagePiece::new(
+            piece.cell(),
+            piece.value(),
+            subpieces.len(),
+        ))
+    }
+
+    /// Concatenates two message pieces into one, with `a` in the low-order
+    /// words and `b` in the high-order words.
+    ///
+    /// The returned piece has `a.num_words() + b.num_words()` words and is
+    /// constrained to equal `a + b * 2^{K * a.num_words()}`. This allows
+    /// adjacent pieces to be hashed as a single piece.
+    ///
+    /// Hashing the concatenated piece only bounds the combined value, so `a`
+    /// and `b` are each range-checked to their number of words here. Otherwise
+    /// `(a + 2^{K * a.num_words()}, b - 1)` would concatenate to the same piece.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the concatenated piece would not fit within a base field
+    /// element.
+    pub fn concat_message_pieces(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &MessagePiece<pallas::Base, { sinsemilla::K }>,
+        b: &MessagePiece<pallas::Base, { sinsemilla::K }>,
+    ) -> Result<MessagePiece<pallas::Base, { sinsemilla::K }>, Error> {
+        let num_words = a.num_words() + b.num_words();
+        assert!(num_words * sinsemilla::K < pallas::Base::NUM_BITS as usize);
+
+        for (name, piece) in [("a", a), ("b", b)].iter() {
+            self.config.lookup_config.copy_check(
+                layouter.namespace(|| format!("{} < 2^{{K * len({})}}", name, name)),
+                piece.cell_value(),
+                piece.num_words(),
+                true,
+            )?;
+        }
+
+        let terms = [
+            (a.cell_value(), pallas::Base::one()),
+            (
+                b.cell_value(),
+                pallas::Base::from_u64(2).pow(&[a.num_bits() as u64, 0, 0, 0]),
+            ),
+        ];
+        let piece = self.weighted_sum(layouter.namespace(|| "a + b * 2^{K * len(a)}"), &terms)?;
+        Ok(MessagePiece::from_cell_value(piece, num_words))
+    }
+
+    /// Returns a cell constrained to `sum(term_i * weight_i)`, where each
+    /// weight is a constant.
+    fn weighted_sum(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        terms: &[(CellValue<pallas::Base>, pallas::Base)],
+    ) -> Result<CellValue<pallas::Base>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "weighted sum",
+            |mut region| {
+                // Initialize the accumulator to zero.
+                let mut acc = Some(pallas::Base::zero());
+                let mut acc_cell = region.assign_advice_from_constant(
+                    || "acc_0",
+                    config.x_a,
+                    0,
+                    pallas::Base::zero(),
+                )?;
+
+                for (row, (term, weight)) in terms.iter().enumerate() {
+                    config.q_weighted_sum.enable(&mut region, row)?;
+
+                    copy(&mut region, || "term", config.x_p, row, term)?;
+                    region.assign_advice_from_constant(|| "weight", config.bits, row, *weight)?;
+
+                    acc = acc.zip(term.value()).map(|(acc, term)| acc + term * weight);
+                    acc_cell = region.assign_advice(
+                        || format!("acc_{}", row + 1),
+                        config.x_a,
+                        row + 1,
+                        || acc.ok_or(Error::SynthesisError),
+                    )?;
+                }
+
+                Ok(CellValue::new(acc_cell, acc))
+            },
+        )
+    }
 }
 
 // Implement `SinsemillaInstructions` for `SinsemillaChip`
@@ -280,7 +468,7 @@ where
     type RunningSum = Vec<Self::CellValue>;
 
     type X = CellValue<pallas::Base>;
-    type NonIdentityPoint = NonIdentityEccPoint;
+    type NonIdentityPoint = NonIdentityEccPoint<pallas::Affine>;
     type FixedPoints = F;
 
     type HashDomains = Hash;
@@ -322,7 +510,318 @@ where
         )
     }
 
+    #[allow(non_snake_case)]
+    #[allow(clippy::type_complexity)]
+    fn hash_to_point_from_point(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        Q: &Self::NonIdentityPoint,
+        message: Self::Message,
+    ) -> Result<(Self::NonIdentityPoint, Vec<Self::RunningSum>), Error> {
+        layouter.assign_region(
+            || "hash_to_point from point",
+            |mut region| self.hash_message_from_point(&mut region, Q, &message),
+        )
+    }
+
     fn extract(point: &Self::NonIdentityPoint) -> Self::X {
         point.x()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SinsemillaChip, SinsemillaConfig};
+    use crate::{
+        primitives::sinsemilla::K,
+        sinsemilla::{
+            tests::{Commit, FixedBase, Hash},
+            SinsemillaInstructions,
+        },
+        utilities::{lookup_range_check::LookupRangeCheckConfig, UtilitiesInstructions},
+    };
+
+    use ff::{Field, PrimeFieldBits};
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    use std::convert::TryInto;
+
+    fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+    ) -> SinsemillaConfig<Hash, Commit, FixedBase> {
+        let advices = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+
+        // Shared fixed column for loading constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let table_idx = meta.lookup_table_column();
+        let lookup = (
+            table_idx,
+            meta.lookup_table_column(),
+            meta.lookup_table_column(),
+        );
+        let range_check = LookupRangeCheckConfig::configure(meta, advices[4], table_idx);
+
+        let fixed_y_q = meta.fixed_column();
+        SinsemillaChip::configure(
+            meta,
+            advices[..].try_into().unwrap(),
+            advices[2],
+            fixed_y_q,
+            lookup,
+            range_check,
+        )
+    }
+
+    #[test]
+    fn reconstruct() {
+        struct MyCircuit {
+            value: Option<pallas::Base>,
+            // Number of words in each piece, in little-endian order.
+            num_words: Vec<usize>,
+            // Whether to reverse the order of the pieces before reconstruction.
+            reverse: bool,
+        }
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = crate::utilities::CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = SinsemillaConfig<Hash, Commit, FixedBase>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    value: None,
+                    num_words: self.num_words.clone(),
+                    reverse: self.reverse,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                SinsemillaChip::load(config.clone(), &mut layouter)?;
+                let chip = SinsemillaChip::construct(config.clone());
+
+                // Split the value into pieces of the given numbers of words.
+                let mut offset = 0;
+                let mut pieces = self
+                    .num_words
+                    .iter()
+                    .map(|num_words| {
+                        let num_bits = K * num_words;
+                        let piece = self.value.map(|value| {
+                            value
+                                .to_le_bits()
+                                .iter()
+                                .by_val()
+                                .skip(offset)
+                                .take(num_bits)
+                                .rev()
+                                .fold(pallas::Base::zero(), |acc, bit| {
+                                    acc.double() + pallas::Base::from(bit)
+                                })
+                        });
+                        offset += num_bits;
+                        chip.witness_message_piece(
+                            layouter.namespace(|| "piece"),
+                            piece,
+                            *num_words,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                if self.reverse {
+                    pieces.reverse();
+                }
+
+                let expected =
+                    self.load_private(layouter.namespace(|| "expected"), config.bits, self.value)?;
+                chip.reconstruct(layouter.namespace(|| "reconstruct"), &pieces, expected)
+            }
+        }
+
+        // A 250-bit value split across three pieces.
+        let value = {
+            let mut bytes = pallas::Base::rand().to_bytes();
+            bytes[31] &= 0x03;
+            pallas::Base::from_bytes(&bytes).unwrap()
+        };
+
+        // Pieces in the correct order recombine to the value.
+        {
+            let circuit = MyCircuit {
+                value: Some(value),
+                num_words: vec![5, 10, 10],
+                reverse: false,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Mismatched pieces do not.
+        {
+            let circuit = MyCircuit {
+                value: Some(value),
+                num_words: vec![5, 10, 10],
+                reverse: true,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn message_piece_from_subpieces() {
+        struct MyCircuit(Vec<Option<u64>>);
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = crate::utilities::CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = SinsemillaConfig<Hash, Commit, FixedBase>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit(vec![None; self.0.len()])
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                SinsemillaChip::load(config.clone(), &mut layouter)?;
+                let chip = SinsemillaChip::construct(config.clone());
+
+                let subpieces = self
+                    .0
+                    .iter()
+                    .map(|subpiece| {
+                        self.load_private(
+                            layouter.namespace(|| "subpiece"),
+                            config.bits,
+                            subpiece.map(pallas::Base::from_u64),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let piece = chip.message_piece_from_subpieces(
+                    layouter.namespace(|| "from subpieces"),
+                    &subpieces,
+                )?;
+                assert_eq!(piece.num_words(), self.0.len());
+
+                let expected: Option<u64> = self.0.iter().rev().try_fold(0, |acc, subpiece| {
+                    subpiece.map(|subpiece| (acc << K) + subpiece)
+                });
+                assert_eq!(piece.field_elem(), expected.map(pallas::Base::from_u64));
+
+                Ok(())
+            }
+        }
+
+        // Subpieces within K bits
+        {
+            let circuit = MyCircuit(vec![Some(0), Some(1), Some((1 << K) - 1)]);
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // A subpiece exceeding K bits
+        {
+            let circuit = MyCircuit(vec![Some(0), Some(1 << K), Some(1)]);
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn concat_message_pieces() {
+        struct MyCircuit {
+            a: Option<pallas::Base>,
+            b: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = SinsemillaConfig<Hash, Commit, FixedBase>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { a: None, b: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                SinsemillaChip::load(config.clone(), &mut layouter)?;
+                let chip = SinsemillaChip::construct(config);
+
+                let a = chip.witness_message_piece(layouter.namespace(|| "a"), self.a, 3)?;
+                let b = chip.witness_message_piece(layouter.namespace(|| "b"), self.b, 21)?;
+
+                let piece = chip.concat_message_pieces(layouter.namespace(|| "a || b"), &a, &b)?;
+                assert_eq!(piece.num_words(), 24);
+
+                let expected = self
+                    .a
+                    .zip(self.b)
+                    .map(|(a, b)| a + b * pallas::Base::from_u64(2).pow(&[3 * K as u64, 0, 0, 0]));
+                assert_eq!(piece.field_elem(), expected);
+
+                Ok(())
+            }
+        }
+
+        // Pieces that fit in their number of words
+        {
+            let circuit = MyCircuit {
+                a: Some(pallas::Base::from_u64((1 << (3 * K)) - 1)),
+                b: Some(pallas::Base::from_u64(0x1234_5678_9abc_def0)),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // `a` exceeding its 3 words, with `b` adjusted so that the concatenated
+        // piece is unchanged
+        {
+            let circuit = MyCircuit {
+                a: Some(pallas::Base::from_u64((1 << (3 * K)) + 1)),
+                b: Some(pallas::Base::from_u64(0x1234_5678_9abc_def0 - 1)),
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use super::super::{CommitDomains, HashDomains, SinsemillaInstructions};
-use super::{CellValue, NonIdentityEccPoint, SinsemillaChip, Var};
+use super::{copy, CellValue, NonIdentityEccPoint, SinsemillaChip, Var};
 
 use crate::ecc::FixedPoints;
 use crate::primitives::sinsemilla::{self, lebs2ip_k, INV_TWO_POW_K, SINSEMILLA_S};
@@ -33,9 +33,15 @@ where
             { sinsemilla::K },
             { sinsemilla::C },
         >>::Message,
-    ) -> Result<(NonIdentityEccPoint, Vec<Vec<CellValue<pallas::Base>>>), Error> {
+    ) -> Result<
+        (
+            NonIdentityEccPoint<pallas::Affine>,
+            Vec<Vec<CellValue<pallas::Base>>>,
+        ),
+        Error,
+    > {
         let config = self.config().clone();
-        let mut offset = 0;
+        let offset = 0;
 
         // Get the `x`- and `y`-coordinates of the starting `Q` base.
         let x_q = *Q.coordinates().unwrap().x();
@@ -43,7 +49,7 @@ where
 
         // Constrain the initial x_a, lambda_1, lambda_2, x_p using the q_sinsemilla4
         // selector.
-        let mut y_a: Y<pallas::Base> = {
+        let y_a: Y<pallas::Base> = {
             // Enable `q_sinsemilla4` on the first row.
             config.q_sinsemilla4.enable(region, offset)?;
             region.assign_fixed(|| "fixed y_q", config.fixed_y_q, offset, || Ok(y_q))?;
@@ -52,7 +58,7 @@ where
         };
 
         // Constrain the initial x_q to equal the x-coordinate of the domain's `Q`.
-        let mut x_a: X<pallas::Base> = {
+        let x_a: X<pallas::Base> = {
             let x_a = {
                 let cell =
                     region.assign_advice_from_constant(|| "fixed x_q", config.x_a, offset, x_q)?;
@@ -62,6 +68,78 @@ where
             x_a.into()
         };
 
+        self.hash_message_inner(region, offset, Some(Q), x_a, y_a, message)
+    }
+
+    /// Hashes a message starting from a previously assigned point `Q`.
+    ///
+    /// The y-coordinate of `Q` is copied into `lambda_1` on the first row of
+    /// the region, and its x-coordinate into `x_a` on the next row, where the
+    /// hash starts.
+    #[allow(non_snake_case)]
+    #[allow(clippy::type_complexity)]
+    pub(super) fn hash_message_from_point(
+        &self,
+        region: &mut Region<'_, pallas::Base>,
+        Q: &NonIdentityEccPoint<pallas::Affine>,
+        message: &<Self as SinsemillaInstructions<
+            pallas::Affine,
+            { sinsemilla::K },
+            { sinsemilla::C },
+        >>::Message,
+    ) -> Result<
+        (
+            NonIdentityEccPoint<pallas::Affine>,
+            Vec<Vec<CellValue<pallas::Base>>>,
+        ),
+        Error,
+    > {
+        let config = self.config().clone();
+        let offset = 1;
+
+        // Copy y_q into lambda_1 on the row before the hash starts.
+        let y_q = copy(region, || "y_q", config.lambda_1, offset - 1, &Q.y())?;
+
+        // Constrain the initial x_a, lambda_1, lambda_2, x_p using the
+        // q_sinsemilla_chain selector.
+        config.q_sinsemilla_chain.enable(region, offset)?;
+        let y_a: Y<pallas::Base> = y_q.value().into();
+
+        // Copy the initial x_q into x_a.
+        let x_a: X<pallas::Base> = copy(region, || "x_q", config.x_a, offset, &Q.x())?.into();
+
+        self.hash_message_inner(region, offset, Q.point(), x_a, y_a, message)
+    }
+
+    /// Hashes a message from the accumulator `(x_a, y_a)`, whose x-coordinate
+    /// MUST have been already assigned within this region at `offset`.
+    ///
+    /// `Q` is the value of the starting point, and is only used to check the
+    /// result in tests.
+    #[allow(non_snake_case)]
+    #[allow(clippy::type_complexity)]
+    #[cfg_attr(not(test), allow(unused_variables))]
+    fn hash_message_inner(
+        &self,
+        region: &mut Region<'_, pallas::Base>,
+        mut offset: usize,
+        Q: Option<pallas::Affine>,
+        mut x_a: X<pallas::Base>,
+        mut y_a: Y<pallas::Base>,
+        message: &<Self as SinsemillaInstructions<
+            pallas::Affine,
+            { sinsemilla::K },
+            { sinsemilla::C },
+        >>::Message,
+    ) -> Result<
+        (
+            NonIdentityEccPoint<pallas::Affine>,
+            Vec<Vec<CellValue<pallas::Base>>>,
+        ),
+        Error,
+    > {
+        let config = self.config().clone();
+
         let mut zs_sum: Vec<Vec<CellValue<pallas::Base>>> = Vec::new();
 
         // Hash each piece in the message.
@@ -124,7 +202,11 @@ where
             let field_elems: Option<Vec<pallas::Base>> =
                 message.iter().map(|piece| piece.field_elem()).collect();
 
-            if field_elems.is_some() && x_a.value().is_some() && y_a.value().is_some() {
+            if field_elems.is_some()
+                && Q.is_some()
+                && x_a.value().is_some()
+                && y_a.value().is_some()
+            {
                 // Get message as a bitstring.
                 let bitstring: Vec<bool> = message
                     .iter()
@@ -147,7 +229,7 @@ where
                 // incomplete addition with negligible probability.
                 let expected_point = bitstring
                     .chunks(K)
-                    .fold(Q.to_curve(), |acc, chunk| (acc + S(chunk)) + acc);
+                    .fold(Q.unwrap().to_curve(), |acc, chunk| (acc + S(chunk)) + acc);
                 let actual_point =
                     pallas::Affine::from_xy(x_a.value().unwrap(), y_a.value().unwrap()).unwrap();
                 assert_eq!(expected_point.to_affine(), actual_point);
@@ -1,20 +1,118 @@
 //! Gadget and chips for the Sinsemilla hash function.
 use crate::utilities::{CellValue, Var};
 use ff::PrimeFieldBits;
-use halo2::{arithmetic::FieldExt, circuit::Cell};
-use std::fmt::Debug;
+use halo2::{arithmetic::FieldExt, circuit::Cell, plonk::Error};
+use std::fmt::{self, Debug};
 
 /// A [`Message`] composed of several [`MessagePiece`]s.
 #[derive(Clone, Debug)]
-pub struct Message<F: FieldExt, const K: usize, const MAX_WORDS: usize>(Vec<MessagePiece<F, K>>);
+pub struct Message<F: FieldExt, const K: usize, const MAX_WORDS: usize> {
+    pieces: Vec<MessagePiece<F, K>>,
+    /// The total number of K-bit words in this message.
+    num_words: usize,
+}
+
+/// An error returned when constructing a [`Message`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageError {
+    /// The pieces contain `num_words` words, but a message must contain fewer
+    /// than `max_words` words.
+    TooManyWords { num_words: usize, max_words: usize },
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::TooManyWords {
+                num_words,
+                max_words,
+            } => write!(
+                f,
+                "message has {} words, but must have fewer than {}",
+                num_words, max_words
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+impl From<MessageError> for Error {
+    fn from(_: MessageError) -> Self {
+        Error::SynthesisError
+    }
+}
 
 impl<F: FieldExt + PrimeFieldBits, const K: usize, const MAX_WORDS: usize>
     From<Vec<MessagePiece<F, K>>> for Message<F, K, MAX_WORDS>
 {
+    /// # Panics
+    ///
+    /// Panics if the pieces contain `MAX_WORDS` words or more. Use
+    /// [`Message::try_from_pieces`] to handle this case.
     fn from(pieces: Vec<MessagePiece<F, K>>) -> Self {
+        Self::try_from_pieces(pieces).expect("A message cannot contain more than `MAX_WORDS` words")
+    }
+}
+
+impl<F: FieldExt + PrimeFieldBits, const K: usize, const MAX_WORDS: usize>
+    Message<F, K, MAX_WORDS>
+{
+    /// Constructs a message from its pieces.
+    ///
+    /// Returns [`MessageError::TooManyWords`] if the pieces contain `MAX_WORDS`
+    /// words or more.
+    pub fn try_from_pieces(pieces: Vec<MessagePiece<F, K>>) -> Result<Self, MessageError> {
+        let num_words = pieces.iter().map(|piece| piece.num_words()).sum::<usize>();
         // A message cannot contain more than `MAX_WORDS` words.
-        assert!(pieces.iter().map(|piece| piece.num_words()).sum::<usize>() < MAX_WORDS);
-        Message(pieces)
+        if num_words >= MAX_WORDS {
+            return Err(MessageError::TooManyWords {
+                num_words,
+                max_words: MAX_WORDS,
+            });
+        }
+        Ok(Message { pieces, num_words })
+    }
+
+    /// Appends a piece to this message.
+    ///
+    /// Returns [`MessageError::TooManyWords`], leaving the message unchanged,
+    /// if the message would then contain `MAX_WORDS` words or more.
+    pub fn push(&mut self, piece: MessagePiece<F, K>) -> Result<(), MessageError> {
+        let num_words = self.num_words + piece.num_words();
+        if num_words >= MAX_WORDS {
+            return Err(MessageError::TooManyWords {
+                num_words,
+                max_words: MAX_WORDS,
+            });
+        }
+
+        self.pieces.push(piece);
+        self.num_words = num_words;
+        Ok(())
+    }
+
+    /// The total number of K-bit words in this message.
+    pub fn num_words(&self) -> usize {
+        self.num_words
+    }
+
+    /// The total bitlength of this message.
+    pub fn num_bits(&self) -> usize {
+        self.num_words * K
+    }
+
+    /// Iterates over the pieces of this message, along with the bit offset
+    /// at which each piece starts.
+    ///
+    /// The offset of each piece is `K` times the total number of words in the
+    /// preceding pieces.
+    pub fn pieces_with_offsets(&self) -> impl Iterator<Item = (usize, &MessagePiece<F, K>)> {
+        self.pieces.iter().scan(0, |offset, piece| {
+            let piece_offset = *offset;
+            *offset += piece.num_bits();
+            Some((piece_offset, piece))
+        })
     }
 }
 
@@ -24,7 +122,7 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize, const MAX_WORDS: usize> std::
     type Target = [MessagePiece<F, K>];
 
     fn deref(&self) -> &[MessagePiece<F, K>] {
-        &self.0
+        &self.pieces
     }
 }
 
@@ -41,18 +139,65 @@ pub struct MessagePiece<F: FieldExt, const K: usize> {
 
 impl<F: FieldExt + PrimeFieldBits, const K: usize> MessagePiece<F, K> {
     pub fn new(cell: Cell, field_elem: Option<F>, num_words: usize) -> Self {
+        Self::from_cell_value(CellValue::new(cell, field_elem), num_words)
+    }
+
+    /// Constructs a message piece from an assigned cell and its value.
+    ///
+    /// Unlike [`MessagePiece::new`], the cell and value cannot be mismatched,
+    /// since [`CellValue`] keeps them together.
+    pub fn from_cell_value(cell_value: CellValue<F>, num_words: usize) -> Self {
         assert!(num_words * K < F::NUM_BITS as usize);
-        let cell_value = CellValue::new(cell, field_elem);
         Self {
             cell_value,
             num_words,
         }
     }
 
+    /// Constructs a message piece from the little-endian encoding `bytes` of
+    /// the field element assigned to `cell`.
+    ///
+    /// The piece contains `ceil(8 * bytes.len() / K)` words.
+    pub fn from_bytes(cell: Cell, bytes: &[u8]) -> Self {
+        let num_bits = bytes.len() * 8;
+        assert!(num_bits <= F::NUM_BITS as usize);
+        let num_words = (num_bits + K - 1) / K;
+
+        let mut repr = [0u8; 32];
+        repr[..bytes.len()].copy_from_slice(bytes);
+        let field_elem = F::from_bytes(&repr).unwrap();
+
+        Self::new(cell, Some(field_elem), num_words)
+    }
+
+    /// Constructs a message piece declaring `target_words` words, regardless
+    /// of how many words are needed to encode `field_elem`.
+    ///
+    /// This allows a piece to be padded with zero words, keeping the layout of
+    /// a message independent of its contents.
+    pub fn padded(cell: Cell, field_elem: Option<F>, target_words: usize) -> Self {
+        // `field_elem` must fit into `target_words` words.
+        if let Some(field_elem) = field_elem {
+            assert!(field_elem
+                .to_le_bits()
+                .iter()
+                .by_val()
+                .skip(target_words * K)
+                .all(|bit| !bit));
+        }
+
+        Self::new(cell, field_elem, target_words)
+    }
+
     pub fn num_words(&self) -> usize {
         self.num_words
     }
 
+    /// The bitlength of this message piece.
+    pub fn num_bits(&self) -> usize {
+        self.num_words * K
+    }
+
     pub fn cell(&self) -> Cell {
         self.cell_value.cell()
     }
@@ -65,3 +210,216 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> MessagePiece<F, K> {
         self.cell_value
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, MessageError, MessagePiece};
+    use crate::{
+        primitives::sinsemilla::{C as MAX_WORDS, K},
+        utilities::{CellValue, Var},
+    };
+
+    use ff::{Field, PrimeField};
+    use halo2::{
+        circuit::{Cell, Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    /// A circuit that assigns a single cell and passes it to the test.
+    struct MyCircuit(fn(Cell) -> Result<(), Error>);
+
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = Column<Advice>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            MyCircuit(self.0)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            meta.advice_column()
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            let cell = layouter.assign_region(
+                || "message piece",
+                |mut region| {
+                    region.assign_advice(|| "piece", config, 0, || Ok(pallas::Base::zero()))
+                },
+            )?;
+
+            (self.0)(cell)
+        }
+    }
+
+    fn run(test: fn(Cell) -> Result<(), Error>) {
+        let prover = MockProver::run(3, &MyCircuit(test), vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn message_push() {
+        run(|cell| {
+            // The largest piece that fits into a base field element.
+            let piece = MessagePiece::<pallas::Base, K>::new(cell, Some(pallas::Base::zero()), 25);
+
+            let mut message = Message::<pallas::Base, K, MAX_WORDS>::from(vec![]);
+            let max_pieces = (MAX_WORDS - 1) / piece.num_words();
+            for _ in 0..max_pieces {
+                message.push(piece)?;
+            }
+            assert_eq!(message.len(), max_pieces);
+
+            // The next piece would overflow `MAX_WORDS`.
+            let num_words = (max_pieces + 1) * piece.num_words();
+            assert!(matches!(
+                message.push(piece),
+                Err(MessageError::TooManyWords { num_words: n, max_words: MAX_WORDS })
+                    if n == num_words
+            ));
+            assert_eq!(message.len(), max_pieces);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_try_from_pieces() {
+        run(|cell| {
+            let piece = MessagePiece::<pallas::Base, K>::new(cell, Some(pallas::Base::zero()), 25);
+            let max_pieces = (MAX_WORDS - 1) / piece.num_words();
+
+            let message =
+                Message::<pallas::Base, K, MAX_WORDS>::try_from_pieces(vec![piece; max_pieces])
+                    .unwrap();
+            assert_eq!(message.len(), max_pieces);
+
+            // One more piece overflows `MAX_WORDS`.
+            let num_words = (max_pieces + 1) * piece.num_words();
+            assert!(matches!(
+                Message::<pallas::Base, K, MAX_WORDS>::try_from_pieces(vec![piece; max_pieces + 1]),
+                Err(MessageError::TooManyWords { num_words: n, max_words: MAX_WORDS })
+                    if n == num_words
+            ));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_num_bits() {
+        run(|cell| {
+            let pieces: Vec<_> = [1, 5, 25]
+                .iter()
+                .map(|num_words| {
+                    MessagePiece::<pallas::Base, K>::new(
+                        cell,
+                        Some(pallas::Base::zero()),
+                        *num_words,
+                    )
+                })
+                .collect();
+            assert_eq!(pieces[1].num_bits(), 5 * K);
+
+            let message = Message::<pallas::Base, K, MAX_WORDS>::from(pieces);
+            assert_eq!(message.num_words(), 31);
+            assert_eq!(message.num_bits(), 31 * K);
+            assert_eq!(
+                message.num_bits(),
+                message.iter().map(|piece| piece.num_bits()).sum::<usize>()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_pieces_with_offsets() {
+        run(|cell| {
+            let pieces: Vec<_> = [3, 7, 2]
+                .iter()
+                .map(|num_words| {
+                    MessagePiece::<pallas::Base, K>::new(
+                        cell,
+                        Some(pallas::Base::zero()),
+                        *num_words,
+                    )
+                })
+                .collect();
+
+            let message = Message::<pallas::Base, K, MAX_WORDS>::from(pieces);
+            let offsets: Vec<_> = message
+                .pieces_with_offsets()
+                .map(|(offset, piece)| (offset, piece.num_words()))
+                .collect();
+            assert_eq!(offsets, vec![(0, 3), (3 * K, 7), ((3 + 7) * K, 2)]);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_piece_from_bytes() {
+        run(|cell| {
+            for bytes in [
+                vec![],
+                vec![0x12, 0x34, 0x56, 0x78, 0x9a],
+                (0..31).map(|i| 0xff - i).collect::<Vec<u8>>(),
+            ]
+            .iter()
+            {
+                let piece = MessagePiece::<pallas::Base, K>::from_bytes(cell, bytes);
+                assert_eq!(piece.num_words(), (bytes.len() * 8 + K - 1) / K);
+
+                // Recover the bytes from the field element.
+                let repr = piece.field_elem().unwrap().to_bytes();
+                assert_eq!(&repr[..bytes.len()], &bytes[..]);
+                assert!(repr[bytes.len()..].iter().all(|byte| *byte == 0));
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_piece_from_cell_value() {
+        run(|cell| {
+            let value = Some(pallas::Base::from_u64(0x1234));
+            let piece =
+                MessagePiece::<pallas::Base, K>::from_cell_value(CellValue::new(cell, value), 2);
+            // `Cell` does not implement `PartialEq`, so compare the debug output.
+            assert_eq!(format!("{:?}", piece.cell()), format!("{:?}", cell));
+            assert_eq!(piece.field_elem(), value);
+            assert_eq!(piece.num_words(), 2);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_piece_padded() {
+        run(|cell| {
+            let target_words = pallas::Base::NUM_BITS as usize / K;
+            for field_elem in [
+                None,
+                Some(pallas::Base::zero()),
+                Some(pallas::Base::from_u64(0x1234)),
+            ]
+            .iter()
+            {
+                let piece =
+                    MessagePiece::<pallas::Base, K>::padded(cell, *field_elem, target_words);
+                assert_eq!(piece.num_words(), target_words);
+                assert_eq!(piece.field_elem(), *field_elem);
+            }
+
+            Ok(())
+        });
+    }
+}
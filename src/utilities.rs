@@ -4,11 +4,23 @@ use halo2::{
     plonk::{Advice, Column, Error, Expression},
 };
 use pasta_curves::arithmetic::FieldExt;
-use std::{array, convert::TryInto, ops::Range};
+use std::{
+    array,
+    convert::TryInto,
+    ops::Range,
+    sync::atomic::{self, Ordering},
+};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
+pub mod arithmetic;
+pub mod boolean;
+pub mod canonical_scalar;
 pub mod cond_swap;
 pub mod decompose_running_sum;
+pub mod is_zero;
 pub mod lookup_range_check;
+pub mod range_check_small;
 
 /// A variable representing a field element.
 #[derive(Copy, Clone, Debug)]
@@ -37,6 +49,74 @@ impl<F: FieldExt> Var<F> for CellValue<F> {
     }
 }
 
+impl<F: FieldExt> CellValue<F> {
+    /// Compares the value of this cell to `other` in constant time.
+    ///
+    /// Returns `None` if the value of this cell is unknown. No constraints are
+    /// added.
+    pub fn value_eq(&self, other: F) -> Option<bool> {
+        self.value.map(|value| bool::from(value.ct_eq(&other)))
+    }
+}
+
+/// Compares two cells by value, ignoring their positions in the circuit.
+///
+/// As with [`CellValue::value_eq`], no constraints are added. A cell whose
+/// value is unknown is not equal to any cell, including itself.
+impl<F: FieldExt> PartialEq for CellValue<F> {
+    fn eq(&self, other: &Self) -> bool {
+        other
+            .value
+            .and_then(|other| self.value_eq(other))
+            .unwrap_or(false)
+    }
+}
+
+/// A secret scalar, such as key material, to be witnessed in a circuit.
+///
+/// The value is overwritten with zero when this is dropped. Copies returned by
+/// [`SecretScalar::value`], and any made while the scalar is being witnessed,
+/// are not covered; this only keeps the caller's copy from lingering.
+pub struct SecretScalar<F: FieldExt>(Option<F>);
+
+impl<F: FieldExt> SecretScalar<F> {
+    /// Wraps a secret scalar, which may be unknown (e.g. during keygen).
+    pub fn new(value: Option<F>) -> Self {
+        SecretScalar(value)
+    }
+
+    /// Returns a copy of the secret scalar.
+    pub fn value(&self) -> Option<F> {
+        self.0
+    }
+}
+
+impl<F: FieldExt> Zeroize for SecretScalar<F> {
+    fn zeroize(&mut self) {
+        if let Some(value) = self.0.as_mut() {
+            // Use a volatile write so that it is not elided for a value that is
+            // about to be dropped.
+            //
+            // Safety: `value` is a valid, aligned, exclusive reference.
+            unsafe { std::ptr::write_volatile(value, F::zero()) };
+        }
+        self.0 = None;
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<F: FieldExt> Drop for SecretScalar<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: FieldExt> std::fmt::Debug for SecretScalar<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretScalar(..)")
+    }
+}
+
 pub trait UtilitiesInstructions<F: FieldExt> {
     type Var: Var<F>;
 
@@ -59,6 +139,25 @@ pub trait UtilitiesInstructions<F: FieldExt> {
             },
         )
     }
+
+    /// Assigns a constant value to an advice cell at a specific offset within
+    /// the given region, and returns the assigned cell.
+    ///
+    /// The value is loaded from the fixed column passed to
+    /// [`ConstraintSystem::enable_constant`] during circuit configuration, so
+    /// the returned cell is fixed to `value` in every proof.
+    ///
+    /// [`ConstraintSystem::enable_constant`]: halo2::plonk::ConstraintSystem::enable_constant
+    fn assign_constant(
+        &self,
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+        value: F,
+    ) -> Result<Self::Var, Error> {
+        let cell = region.assign_advice_from_constant(|| "constant", column, offset, value)?;
+        Ok(Var::new(cell, Some(value)))
+    }
 }
 
 /// Assigns a cell at a specific offset within the given region, constraining it
@@ -129,12 +228,27 @@ pub fn bitrange_subset<F: FieldExt + PrimeFieldBits>(field_elem: F, bitrange: Ra
 
 /// Check that an expression is in the small range [0..range),
 /// i.e. 0 ≤ word < range.
+///
+/// `range` need not be a power of two. The returned expression is
+/// `word⋅(word - 1)⋅...⋅(word - (range - 1))`, which has degree `range` in
+/// `word`.
 pub fn range_check<F: FieldExt>(word: Expression<F>, range: usize) -> Expression<F> {
     (1..range).fold(word.clone(), |acc, i| {
         acc * (word.clone() - Expression::Constant(F::from_u64(i as u64)))
     })
 }
 
+/// Returns an expression that is zero iff `value` is in the range [0..n),
+/// for use in a custom gate.
+///
+/// # Panics
+///
+/// Panics if `n` is zero, since no value lies in an empty range.
+pub fn range_check_expr<F: FieldExt>(value: Expression<F>, n: usize) -> Expression<F> {
+    assert!(n > 0);
+    range_check(value, n)
+}
+
 /// Decompose a word `alpha` into `window_num_bits` bits (little-endian)
 /// For a window size of `w`, this returns [k_0, ..., k_n] where each `k_i`
 /// is a `w`-bit value, and `scalar = k_0 + k_1 * w + k_n * w^n`.
@@ -165,6 +279,24 @@ pub fn decompose_word<F: PrimeFieldBits>(
         .collect()
 }
 
+/// Compose `window_num_bits`-bit words (little-endian) into a field element.
+/// This is the inverse of [`decompose_word`]: for words [k_0, ..., k_n], this
+/// returns `k_0 + k_1 * 2^w + ... + k_n * 2^{wn}`.
+///
+/// # Panics
+///
+/// Panics if `window_num_bits > 8`, or if any word does not fit in
+/// `window_num_bits` bits.
+pub fn compose_word<F: FieldExt>(words: &[u8], window_num_bits: usize) -> F {
+    assert!(window_num_bits <= 8);
+
+    let two_pow_k = F::from_u64(1 << window_num_bits);
+    words.iter().rev().fold(F::zero(), |acc, word| {
+        assert!((*word as usize) < (1 << window_num_bits));
+        acc * two_pow_k + F::from_u64(*word as u64)
+    })
+}
+
 /// Takes in an FnMut closure and returns a constant-length array with elements of
 /// type `Output`.
 pub fn gen_const_array<Output: Copy + Default, const LEN: usize>(
@@ -277,6 +409,228 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_range_check_expr() {
+        #[derive(Default)]
+        struct MyCircuit(Option<pallas::Base>);
+
+        #[derive(Clone)]
+        struct Config {
+            selector: Selector,
+            advice: Column<Advice>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let selector = meta.selector();
+                let advice = meta.advice_column();
+
+                // Constrain the value to one of 5 values.
+                let n = 5;
+                meta.create_gate("range check 5", |meta| {
+                    let selector = meta.query_selector(selector);
+                    let advice = meta.query_advice(advice, Rotation::cur());
+
+                    vec![selector * range_check_expr(advice, n)]
+                });
+
+                Config { selector, advice }
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                layouter.assign_region(
+                    || "range constrain",
+                    |mut region| {
+                        config.selector.enable(&mut region, 0)?;
+                        region.assign_advice(
+                            || "value",
+                            config.advice,
+                            0,
+                            || self.0.ok_or(Error::SynthesisError),
+                        )?;
+
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        for i in 0..5 {
+            let circuit = MyCircuit(Some(pallas::Base::from_u64(i)));
+            let prover = MockProver::<pallas::Base>::run(3, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        for value in [
+            pallas::Base::from_u64(5),
+            pallas::Base::from_u64(6),
+            -pallas::Base::one(),
+        ]
+        .iter()
+        {
+            let circuit = MyCircuit(Some(*value));
+            let prover = MockProver::<pallas::Base>::run(3, &circuit, vec![]).unwrap();
+            assert_eq!(
+                prover.verify(),
+                Err(vec![VerifyFailure::ConstraintNotSatisfied {
+                    constraint: ((0, "range check 5").into(), 0, "").into(),
+                    row: 0
+                }])
+            );
+        }
+    }
+
+    #[test]
+    fn test_cell_value_eq() {
+        struct MyCircuit;
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = Column<Advice>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                meta.advice_column()
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let (a, b) = layouter.assign_region(
+                    || "cells",
+                    |mut region| {
+                        let a = region.assign_advice(
+                            || "a",
+                            config,
+                            0,
+                            || Ok(pallas::Base::from_u64(3)),
+                        )?;
+                        let b = region.assign_advice(
+                            || "b",
+                            config,
+                            1,
+                            || Ok(pallas::Base::from_u64(3)),
+                        )?;
+                        Ok((a, b))
+                    },
+                )?;
+
+                let three = pallas::Base::from_u64(3);
+                let known_a = CellValue::new(a, Some(three));
+                let known_b = CellValue::new(b, Some(three));
+                let other = CellValue::new(b, Some(pallas::Base::from_u64(4)));
+                let unknown = CellValue::<pallas::Base>::new(a, None);
+
+                // A known value is compared.
+                assert_eq!(known_a.value_eq(three), Some(true));
+                assert_eq!(other.value_eq(three), Some(false));
+                // An unknown value cannot be compared.
+                assert_eq!(unknown.value_eq(three), None);
+
+                // Cells are compared by value, regardless of position.
+                assert_eq!(known_a, known_b);
+                assert_ne!(known_a, other);
+                assert_ne!(known_a, unknown);
+                // Unknown values are never equal, even for the same cell.
+                assert_ne!(unknown, CellValue::new(a, None));
+
+                Ok(())
+            }
+        }
+
+        let prover = MockProver::<pallas::Base>::run(3, &MyCircuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_assign_constant() {
+        struct MyCircuit {
+            constant: pallas::Base,
+            witness: Option<pallas::Base>,
+        }
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = Column<Advice>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    constant: self.constant,
+                    witness: None,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let advice = meta.advice_column();
+                meta.enable_equality(advice.into());
+
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                advice
+            }
+
+            fn synthesize(
+                &self,
+                advice: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let witness =
+                    self.load_private(layouter.namespace(|| "witness"), advice, self.witness)?;
+
+                layouter.assign_region(
+                    || "constant",
+                    |mut region| {
+                        let constant =
+                            self.assign_constant(&mut region, advice, 0, self.constant)?;
+                        assert_eq!(constant.value(), Some(self.constant));
+                        region.constrain_equal(constant.cell(), witness.cell())
+                    },
+                )
+            }
+        }
+
+        // The witnessed cell matches the constant.
+        {
+            let circuit = MyCircuit {
+                constant: pallas::Base::from_u64(42),
+                witness: Some(pallas::Base::from_u64(42)),
+            };
+            let prover = MockProver::<pallas::Base>::run(3, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // The witnessed cell does not match the constant.
+        {
+            let circuit = MyCircuit {
+                constant: pallas::Base::from_u64(42),
+                witness: Some(pallas::Base::from_u64(43)),
+            };
+            let prover = MockProver::<pallas::Base>::run(3, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
     #[test]
     fn test_bitrange_subset() {
         // Subset full range.
@@ -349,6 +703,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_secret_scalar_zeroize() {
+        use std::mem::ManuallyDrop;
+
+        let value = pallas::Scalar::rand();
+
+        // Reads the bytes at which the scalar was stored. The zero scalar is
+        // represented by zero bytes.
+        let stored_bytes = |ptr: *const pallas::Scalar| -> [u8; 32] {
+            unsafe { std::ptr::read_volatile(ptr as *const [u8; 32]) }
+        };
+
+        // Explicit zeroization clears the value and overwrites its bytes.
+        let mut secret = SecretScalar::new(Some(value));
+        assert_eq!(secret.value(), Some(value));
+        let stored: *const pallas::Scalar = secret.0.as_ref().unwrap();
+        assert_ne!(stored_bytes(stored), [0; 32]);
+        secret.zeroize();
+        assert_eq!(secret.value(), None);
+        assert_eq!(stored_bytes(stored), [0; 32]);
+
+        // Dropping zeroizes the value in place. `ManuallyDrop` keeps the memory
+        // around so we can inspect it after running the destructor.
+        let mut secret = ManuallyDrop::new(SecretScalar::new(Some(value)));
+        let inner: *const Option<pallas::Scalar> = &secret.0;
+        let stored: *const pallas::Scalar = secret.0.as_ref().unwrap();
+        unsafe { ManuallyDrop::drop(&mut secret) };
+        assert_eq!(unsafe { *inner }, None);
+        assert_eq!(stored_bytes(stored), [0; 32]);
+
+        // The value is not exposed through `Debug`.
+        let secret = SecretScalar::new(Some(value));
+        assert_eq!(format!("{:?}", secret), "SecretScalar(..)");
+    }
+
     prop_compose! {
         fn arb_scalar()(bytes in prop::array::uniform32(0u8..)) -> pallas::Scalar {
             // Instead of rejecting out-of-range bytes, let's reduce them.
@@ -383,5 +772,15 @@ mod tests {
             // Check that original scalar is recovered from decomposition
             assert_eq!(scalar, pallas::Scalar::from_bytes(&bytes.try_into().unwrap()).unwrap());
         }
+
+        #[test]
+        fn test_compose_word(
+            scalar in arb_scalar(),
+            window_num_bits in 1u8..9
+        ) {
+            // Composing the decomposition recovers the original scalar
+            let decomposed = decompose_word(scalar, pallas::Scalar::NUM_BITS as usize, window_num_bits as usize);
+            assert_eq!(scalar, compose_word::<pallas::Scalar>(&decomposed, window_num_bits as usize));
+        }
     }
 }
@@ -0,0 +1,216 @@
+//! Gates for constrained field arithmetic on assigned cells.
+
+use super::{copy, CellValue, Var};
+use halo2::{
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::FieldExt;
+use std::{array, marker::PhantomData};
+
+/// A binary operation supported by [`ArithmeticConfig`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BinOp {
+    /// c = a + b
+    Add,
+    /// c = a - b
+    Sub,
+    /// c = a⋅b
+    Mul,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArithmeticConfig<F: FieldExt> {
+    q_add: Selector,
+    q_sub: Selector,
+    q_mul: Selector,
+    // Left operand
+    pub a: Column<Advice>,
+    // Right operand
+    pub b: Column<Advice>,
+    // Output
+    pub c: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> ArithmeticConfig<F> {
+    /// Configures the arithmetic gates on the given columns.
+    ///
+    /// # Side-effects
+    ///
+    /// All columns in `advices` will be equality-enabled.
+    pub fn configure(meta: &mut ConstraintSystem<F>, advices: [Column<Advice>; 3]) -> Self {
+        for advice in advices.iter() {
+            meta.enable_equality((*advice).into());
+        }
+
+        let config = Self {
+            q_add: meta.selector(),
+            q_sub: meta.selector(),
+            q_mul: meta.selector(),
+            a: advices[0],
+            b: advices[1],
+            c: advices[2],
+            _marker: PhantomData,
+        };
+
+        meta.create_gate("arithmetic", |meta| {
+            let q_add = meta.query_selector(config.q_add);
+            let q_sub = meta.query_selector(config.q_sub);
+            let q_mul = meta.query_selector(config.q_mul);
+            let a = meta.query_advice(config.a, Rotation::cur());
+            let b = meta.query_advice(config.b, Rotation::cur());
+            let c = meta.query_advice(config.c, Rotation::cur());
+
+            array::IntoIter::new([
+                ("add", q_add * (a.clone() + b.clone() - c.clone())),
+                ("sub", q_sub * (a.clone() - b.clone() - c.clone())),
+                ("mul", q_mul * (a * b - c)),
+            ])
+        });
+
+        config
+    }
+
+    /// Copies `a` and `b` into the given row, and returns a cell constrained
+    /// to `a op b`.
+    pub fn assign_binop(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        op: BinOp,
+        a: &CellValue<F>,
+        b: &CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        let (selector, f): (_, fn(F, F) -> F) = match op {
+            BinOp::Add => (self.q_add, |a, b| a + b),
+            BinOp::Sub => (self.q_sub, |a, b| a - b),
+            BinOp::Mul => (self.q_mul, |a, b| a * b),
+        };
+        selector.enable(region, offset)?;
+
+        let a = copy(region, || "a", self.a, offset, a)?;
+        let b = copy(region, || "b", self.b, offset, b)?;
+
+        let c = a.value().zip(b.value()).map(|(a, b)| f(a, b));
+        let cell =
+            region.assign_advice(|| "c", self.c, offset, || c.ok_or(Error::SynthesisError))?;
+
+        Ok(CellValue::new(cell, c))
+    }
+
+    /// Returns a cell constrained to `a + b`.
+    pub fn add(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &CellValue<F>,
+        b: &CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        self.assign_binop(region, offset, BinOp::Add, a, b)
+    }
+
+    /// Returns a cell constrained to `a - b`.
+    pub fn sub(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &CellValue<F>,
+        b: &CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        self.assign_binop(region, offset, BinOp::Sub, a, b)
+    }
+
+    /// Returns a cell constrained to `a⋅b`.
+    pub fn mul(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &CellValue<F>,
+        b: &CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        self.assign_binop(region, offset, BinOp::Mul, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArithmeticConfig;
+    use crate::utilities::{CellValue, UtilitiesInstructions, Var};
+
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    #[test]
+    fn arithmetic() {
+        #[derive(Default)]
+        struct MyCircuit {
+            a: Option<pallas::Base>,
+            b: Option<pallas::Base>,
+            c: Option<pallas::Base>,
+        }
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = ArithmeticConfig<pallas::Base>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let advices = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+                ArithmeticConfig::configure(meta, advices)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let a = self.load_private(layouter.namespace(|| "a"), config.a, self.a)?;
+                let b = self.load_private(layouter.namespace(|| "b"), config.a, self.b)?;
+                let c = self.load_private(layouter.namespace(|| "c"), config.a, self.c)?;
+
+                // (a + b) * c, and (a + b) * c - a
+                let (result, diff) = layouter.assign_region(
+                    || "(a + b) * c",
+                    |mut region| {
+                        let sum = config.add(&mut region, 0, &a, &b)?;
+                        let result = config.mul(&mut region, 1, &sum, &c)?;
+                        let diff = config.sub(&mut region, 2, &result, &a)?;
+                        Ok((result, diff))
+                    },
+                )?;
+
+                if let (Some(a), Some(b), Some(c)) = (self.a, self.b, self.c) {
+                    assert_eq!(result.value(), Some((a + b) * c));
+                    assert_eq!(diff.value(), Some((a + b) * c - a));
+                }
+
+                Ok(())
+            }
+        }
+
+        let circuit = MyCircuit {
+            a: Some(pallas::Base::rand()),
+            b: Some(pallas::Base::rand()),
+            c: Some(pallas::Base::from_u64(7)),
+        };
+        let prover = MockProver::<pallas::Base>::run(4, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
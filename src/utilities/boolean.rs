@@ -0,0 +1,147 @@
+//! Witness a boolean value, constraining it to be either 0 or 1.
+
+use super::{bool_check, CellValue, Var};
+use halo2::{
+    circuit::Layouter,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::FieldExt;
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BooleanConstraintConfig<F: FieldExt> {
+    pub q_bool: Selector,
+    pub value: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BooleanConstraintConfig<F> {
+    /// Configures a gate constraining cells in `value` to be boolean.
+    ///
+    /// # Side-effects
+    ///
+    /// `value` will be equality-enabled.
+    pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+        meta.enable_equality(value.into());
+
+        let q_bool = meta.selector();
+        let config = Self {
+            q_bool,
+            value,
+            _marker: PhantomData,
+        };
+
+        meta.create_gate("boolean constraint", |meta| {
+            let q_bool = meta.query_selector(config.q_bool);
+            let value = meta.query_advice(config.value, Rotation::cur());
+
+            vec![q_bool * bool_check(value)]
+        });
+
+        config
+    }
+
+    /// Witnesses a boolean value, returning the assigned cell.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Option<bool>,
+    ) -> Result<CellValue<F>, Error> {
+        layouter.assign_region(
+            || "witness boolean",
+            |mut region| {
+                self.q_bool.enable(&mut region, 0)?;
+
+                let value = value.map(|value| F::from_u64(value as u64));
+                let cell = region.assign_advice(
+                    || "boolean",
+                    self.value,
+                    0,
+                    || value.ok_or(Error::SynthesisError),
+                )?;
+                Ok(CellValue::new(cell, value))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BooleanConstraintConfig;
+    use crate::utilities::Var;
+
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    #[test]
+    fn boolean_constraint() {
+        enum MyCircuit {
+            // Witness a boolean using the gadget.
+            Bool(bool),
+            // Assign an arbitrary value with the boolean constraint enabled.
+            Raw(u64),
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = BooleanConstraintConfig<pallas::Base>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit::Raw(0)
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let value = meta.advice_column();
+                BooleanConstraintConfig::configure(meta, value)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                match self {
+                    MyCircuit::Bool(value) => {
+                        let cell = config.assign(layouter.namespace(|| "bool"), Some(*value))?;
+                        assert_eq!(cell.value(), Some(pallas::Base::from_u64(*value as u64)));
+                        Ok(())
+                    }
+                    MyCircuit::Raw(value) => layouter.assign_region(
+                        || "raw",
+                        |mut region| {
+                            config.q_bool.enable(&mut region, 0)?;
+                            region.assign_advice(
+                                || "raw",
+                                config.value,
+                                0,
+                                || Ok(pallas::Base::from_u64(*value)),
+                            )?;
+                            Ok(())
+                        },
+                    ),
+                }
+            }
+        }
+
+        for circuit in [MyCircuit::Bool(false), MyCircuit::Bool(true)].iter() {
+            let prover = MockProver::<pallas::Base>::run(3, circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        {
+            let prover = MockProver::<pallas::Base>::run(3, &MyCircuit::Raw(2), vec![]).unwrap();
+            assert_eq!(
+                prover.verify(),
+                Err(vec![VerifyFailure::ConstraintNotSatisfied {
+                    constraint: ((0, "boolean constraint").into(), 0, "").into(),
+                    row: 0
+                }])
+            );
+        }
+    }
+}
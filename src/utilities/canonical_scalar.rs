@@ -0,0 +1,292 @@
+//! Constrain a little-endian bit decomposition to encode an integer below a
+//! fixed modulus.
+
+use super::{bool_check, copy, CellValue, Var};
+use bigint::U256;
+use halo2::{
+    circuit::Layouter,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::FieldExt;
+use std::{array, marker::PhantomData};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CanonicalScalarConfig<F: FieldExt> {
+    // Selector for a row comparing a bit against a 1-bit of the modulus.
+    pub q_modulus_one: Selector,
+    // Selector for a row comparing a bit against a 0-bit of the modulus.
+    pub q_modulus_zero: Selector,
+    // The bits of the scalar, most significant first.
+    pub bit: Column<Advice>,
+    // 1 iff the bits processed so far are equal to those of the modulus.
+    pub eq: Column<Advice>,
+    // 1 iff the bits processed so far are less than those of the modulus.
+    pub lt: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> CanonicalScalarConfig<F> {
+    /// Configures a gate comparing a scalar against a modulus, one bit at a
+    /// time from the most significant bit.
+    ///
+    /// Each row holds the `eq` and `lt` flags for the bits processed in the
+    /// previous rows, and the next row holds the flags updated with the bit
+    /// `b` on this row. If the bit of the modulus on this row is 1:
+    ///
+    /// $$
+    /// \begin{array}{l}
+    /// \mathsf{eq}_{next} = \mathsf{eq} \cdot b \\
+    /// \mathsf{lt}_{next} = \mathsf{lt} + \mathsf{eq} \cdot (1 - b)
+    /// \end{array}
+    /// $$
+    ///
+    /// and if it is 0:
+    ///
+    /// $$
+    /// \begin{array}{l}
+    /// \mathsf{eq}_{next} = \mathsf{eq} \cdot (1 - b) \\
+    /// \mathsf{lt}_{next} = \mathsf{lt}
+    /// \end{array}
+    /// $$
+    ///
+    /// # Side-effects
+    ///
+    /// `bit`, `eq` and `lt` will be equality-enabled.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        eq: Column<Advice>,
+        lt: Column<Advice>,
+    ) -> Self {
+        meta.enable_equality(bit.into());
+        meta.enable_equality(eq.into());
+        meta.enable_equality(lt.into());
+
+        let q_modulus_one = meta.selector();
+        let q_modulus_zero = meta.selector();
+        let config = Self {
+            q_modulus_one,
+            q_modulus_zero,
+            bit,
+            eq,
+            lt,
+            _marker: PhantomData,
+        };
+
+        meta.create_gate("canonical scalar", |meta| {
+            let q_modulus_one = meta.query_selector(config.q_modulus_one);
+            let q_modulus_zero = meta.query_selector(config.q_modulus_zero);
+            let bit = meta.query_advice(config.bit, Rotation::cur());
+            let eq_cur = meta.query_advice(config.eq, Rotation::cur());
+            let eq_next = meta.query_advice(config.eq, Rotation::next());
+            let lt_cur = meta.query_advice(config.lt, Rotation::cur());
+            let lt_next = meta.query_advice(config.lt, Rotation::next());
+
+            let one = Expression::Constant(F::one());
+            let not_bit = one - bit.clone();
+
+            let bit_check =
+                (q_modulus_one.clone() + q_modulus_zero.clone()) * bool_check(bit.clone());
+
+            let one_checks = array::IntoIter::new([
+                ("eq, modulus bit 1", eq_next.clone() - eq_cur.clone() * bit),
+                (
+                    "lt, modulus bit 1",
+                    lt_next.clone() - lt_cur.clone() - eq_cur.clone() * not_bit.clone(),
+                ),
+            ])
+            .map(move |(name, poly)| (name, q_modulus_one.clone() * poly));
+
+            let zero_checks = array::IntoIter::new([
+                ("eq, modulus bit 0", eq_next - eq_cur * not_bit),
+                ("lt, modulus bit 0", lt_next - lt_cur),
+            ])
+            .map(move |(name, poly)| (name, q_modulus_zero.clone() * poly));
+
+            std::iter::once(("bool_check", bit_check))
+                .chain(one_checks)
+                .chain(zero_checks)
+        });
+
+        config
+    }
+
+    /// Constrains `scalar_bits`, a little-endian bit decomposition, to encode
+    /// an integer strictly less than `q`.
+    ///
+    /// Each bit is constrained to be boolean. This uses `scalar_bits.len() + 1`
+    /// rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scalar_bits` is empty, or if `q` does not fit in
+    /// `scalar_bits.len()` bits.
+    pub fn assert_canonical_scalar(
+        &self,
+        mut layouter: impl Layouter<F>,
+        scalar_bits: &[CellValue<F>],
+        q: U256,
+    ) -> Result<(), Error> {
+        let num_bits = scalar_bits.len();
+        assert!(num_bits > 0 && q.bits() <= num_bits);
+
+        layouter.assign_region(
+            || "assert canonical scalar",
+            |mut region| {
+                // Nothing has been compared yet.
+                let mut eq = Some(true);
+                let mut lt = Some(false);
+                region.assign_advice_from_constant(|| "eq_init", self.eq, 0, F::one())?;
+                region.assign_advice_from_constant(|| "lt_init", self.lt, 0, F::zero())?;
+
+                for (row, (idx, bit)) in scalar_bits.iter().enumerate().rev().enumerate() {
+                    let modulus_bit = q.bit(idx);
+                    if modulus_bit {
+                        self.q_modulus_one.enable(&mut region, row)?;
+                    } else {
+                        self.q_modulus_zero.enable(&mut region, row)?;
+                    }
+
+                    // Copy in the bit
+                    copy(&mut region, || format!("bit {}", idx), self.bit, row, bit)?;
+
+                    let bit = bit.value().map(|bit| bit == F::one());
+                    let (eq_next, lt_next) = match (eq, lt, bit) {
+                        (Some(eq), Some(lt), Some(bit)) => {
+                            if modulus_bit {
+                                (Some(eq && bit), Some(lt || (eq && !bit)))
+                            } else {
+                                (Some(eq && !bit), Some(lt))
+                            }
+                        }
+                        _ => (None, None),
+                    };
+                    eq = eq_next;
+                    lt = lt_next;
+
+                    region.assign_advice(
+                        || format!("eq after bit {}", idx),
+                        self.eq,
+                        row + 1,
+                        || eq.map(F::from).ok_or(Error::SynthesisError),
+                    )?;
+                    let lt_cell = region.assign_advice(
+                        || format!("lt after bit {}", idx),
+                        self.lt,
+                        row + 1,
+                        || lt.map(F::from).ok_or(Error::SynthesisError),
+                    )?;
+
+                    // The scalar must be strictly less than `q`.
+                    if row + 1 == num_bits {
+                        region.constrain_constant(lt_cell, F::one())?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CanonicalScalarConfig;
+    use crate::utilities::{CellValue, UtilitiesInstructions};
+
+    use bigint::U256;
+    use ff::{Field, PrimeField};
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+    use pasta_curves::pallas;
+
+    #[test]
+    fn assert_canonical_scalar() {
+        struct MyCircuit {
+            bits: Vec<Option<bool>>,
+            q: U256,
+        }
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = (CanonicalScalarConfig<pallas::Base>, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    bits: vec![None; self.bits.len()],
+                    q: self.q,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let bit = meta.advice_column();
+                let eq = meta.advice_column();
+                let lt = meta.advice_column();
+
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                (CanonicalScalarConfig::configure(meta, bit, eq, lt), bit)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let bits = self
+                    .bits
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, bit)| {
+                        self.load_private(
+                            layouter.namespace(|| format!("bit {}", idx)),
+                            config.1,
+                            bit.map(pallas::Base::from),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                config.0.assert_canonical_scalar(
+                    layouter.namespace(|| "assert canonical"),
+                    &bits,
+                    self.q,
+                )
+            }
+        }
+
+        // The order of the Pallas scalar field.
+        let q = U256::from_little_endian(&(-pallas::Scalar::one()).to_repr()) + U256::one();
+        let num_bits = pallas::Scalar::NUM_BITS as usize;
+        let to_bits = |value: U256| (0..num_bits).map(|i| Some(value.bit(i))).collect();
+
+        // Accepts scalars below the modulus.
+        for value in [U256::zero(), U256::one(), q - U256::one()].iter() {
+            let circuit = MyCircuit {
+                bits: to_bits(*value),
+                q,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Rejects the modulus itself, and anything above it.
+        let max = (U256::one() << num_bits) - U256::one();
+        for value in [q, q + U256::one(), max].iter() {
+            let circuit = MyCircuit {
+                bits: to_bits(*value),
+                q,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
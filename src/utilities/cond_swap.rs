@@ -1,6 +1,6 @@
 use super::{copy, CellValue, UtilitiesInstructions, Var};
 use halo2::{
-    circuit::{Chip, Layouter},
+    circuit::{Chip, Layouter, Region},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
@@ -69,9 +69,6 @@ impl<F: FieldExt> CondSwapInstructions<F> for CondSwapChip<F> {
         layouter.assign_region(
             || "swap",
             |mut region| {
-                // Enable `q_swap` selector
-                config.q_swap.enable(&mut region, 0)?;
-
                 // Copy in `a` value
                 let a = copy(&mut region, || "copy a", config.a, 0, &pair.0)?;
 
@@ -86,58 +83,99 @@ impl<F: FieldExt> CondSwapInstructions<F> for CondSwapChip<F> {
                     CellValue::new(cell, pair.1)
                 };
 
-                // Witness `swap` value
-                let swap_val = swap.map(|swap| F::from_u64(swap as u64));
-                region.assign_advice(
-                    || "swap",
-                    config.swap,
-                    0,
-                    || swap_val.ok_or(Error::SynthesisError),
-                )?;
-
-                // Conditionally swap a
-                let a_swapped = {
-                    let a_swapped = a
-                        .value
-                        .zip(b.value)
-                        .zip(swap)
-                        .map(|((a, b), swap)| if swap { b } else { a });
-                    let a_swapped_cell = region.assign_advice(
-                        || "a_swapped",
-                        config.a_swapped,
-                        0,
-                        || a_swapped.ok_or(Error::SynthesisError),
-                    )?;
-                    CellValue {
-                        cell: a_swapped_cell,
-                        value: a_swapped,
-                    }
-                };
+                config.assign_swapped(&mut region, a, b, swap)
+            },
+        )
+    }
+}
 
-                // Conditionally swap b
-                let b_swapped = {
-                    let b_swapped = a
-                        .value
-                        .zip(b.value)
-                        .zip(swap)
-                        .map(|((a, b), swap)| if swap { a } else { b });
-                    let b_swapped_cell = region.assign_advice(
-                        || "b_swapped",
-                        config.b_swapped,
-                        0,
-                        || b_swapped.ok_or(Error::SynthesisError),
-                    )?;
-                    CellValue {
-                        cell: b_swapped_cell,
-                        value: b_swapped,
-                    }
-                };
+impl CondSwapConfig {
+    /// Given an input pair (a,b) of existing cells and a `swap` boolean flag,
+    /// returns (b,a) if `swap` is set, else (a,b) if `swap` is not set.
+    ///
+    /// Both elements of the pair are copied into this helper.
+    #[allow(clippy::type_complexity)]
+    pub fn assign<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pair: (CellValue<F>, CellValue<F>),
+        swap: Option<bool>,
+    ) -> Result<(CellValue<F>, CellValue<F>), Error> {
+        layouter.assign_region(
+            || "swap",
+            |mut region| {
+                // Copy in `a` and `b` values
+                let a = copy(&mut region, || "copy a", self.a, 0, &pair.0)?;
+                let b = copy(&mut region, || "copy b", self.b, 0, &pair.1)?;
 
-                // Return swapped pair
-                Ok((a_swapped, b_swapped))
+                self.assign_swapped(&mut region, a, b, swap)
             },
         )
     }
+
+    /// Witnesses `swap` and the swapped pair, given `a` and `b` assigned at
+    /// offset 0 of the region.
+    #[allow(clippy::type_complexity)]
+    fn assign_swapped<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        a: CellValue<F>,
+        b: CellValue<F>,
+        swap: Option<bool>,
+    ) -> Result<(CellValue<F>, CellValue<F>), Error> {
+        // Enable `q_swap` selector
+        self.q_swap.enable(region, 0)?;
+
+        // Witness `swap` value
+        let swap_val = swap.map(|swap| F::from_u64(swap as u64));
+        region.assign_advice(
+            || "swap",
+            self.swap,
+            0,
+            || swap_val.ok_or(Error::SynthesisError),
+        )?;
+
+        // Conditionally swap a
+        let a_swapped = {
+            let a_swapped = a
+                .value
+                .zip(b.value)
+                .zip(swap)
+                .map(|((a, b), swap)| if swap { b } else { a });
+            let a_swapped_cell = region.assign_advice(
+                || "a_swapped",
+                self.a_swapped,
+                0,
+                || a_swapped.ok_or(Error::SynthesisError),
+            )?;
+            CellValue {
+                cell: a_swapped_cell,
+                value: a_swapped,
+            }
+        };
+
+        // Conditionally swap b
+        let b_swapped = {
+            let b_swapped = a
+                .value
+                .zip(b.value)
+                .zip(swap)
+                .map(|((a, b), swap)| if swap { a } else { b });
+            let b_swapped_cell = region.assign_advice(
+                || "b_swapped",
+                self.b_swapped,
+                0,
+                || b_swapped.ok_or(Error::SynthesisError),
+            )?;
+            CellValue {
+                cell: b_swapped_cell,
+                value: b_swapped,
+            }
+        };
+
+        // Return swapped pair
+        Ok((a_swapped, b_swapped))
+    }
 }
 
 impl<F: FieldExt> CondSwapChip<F> {
@@ -145,14 +183,15 @@ impl<F: FieldExt> CondSwapChip<F> {
     ///
     /// # Side-effects
     ///
-    /// `advices[0]` will be equality-enabled.
+    /// `advices[0]` and `advices[1]` will be equality-enabled.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advices: [Column<Advice>; 5],
     ) -> CondSwapConfig {
         let a = advices[0];
-        // Only column a is used in an equality constraint directly by this chip.
+        // Only columns a and b are used in equality constraints directly by this chip.
         meta.enable_equality(a.into());
+        meta.enable_equality(advices[1].into());
 
         let q_swap = meta.selector();
 
@@ -298,4 +337,69 @@ mod tests {
             assert_eq!(prover.verify(), Ok(()));
         }
     }
+
+    #[test]
+    fn cond_swap_config_assign() {
+        #[derive(Default)]
+        struct MyCircuit<F: FieldExt> {
+            a: Option<F>,
+            b: Option<F>,
+            swap: Option<bool>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+            type Config = CondSwapConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self::default()
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let advices = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+
+                CondSwapChip::<F>::configure(meta, advices)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let chip = CondSwapChip::<F>::construct(config.clone());
+
+                // Load the pair into the circuit.
+                let a = chip.load_private(layouter.namespace(|| "a"), config.a, self.a)?;
+                let b = chip.load_private(layouter.namespace(|| "b"), config.b, self.b)?;
+
+                // Return the swapped pair.
+                let swapped_pair =
+                    config.assign(layouter.namespace(|| "swap"), (a, b), self.swap)?;
+
+                if let Some(swap) = self.swap {
+                    let expected = if swap { (b, a) } else { (a, b) };
+                    assert_eq!(swapped_pair.0.value.unwrap(), expected.0.value.unwrap());
+                    assert_eq!(swapped_pair.1.value.unwrap(), expected.1.value.unwrap());
+                }
+
+                Ok(())
+            }
+        }
+
+        for swap in [true, false].iter() {
+            let circuit: MyCircuit<Base> = MyCircuit {
+                a: Some(Base::rand()),
+                b: Some(Base::rand()),
+                swap: Some(*swap),
+            };
+            let prover = MockProver::<Base>::run(3, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
 }
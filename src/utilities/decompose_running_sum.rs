@@ -24,7 +24,7 @@
 
 use ff::PrimeFieldBits;
 use halo2::{
-    circuit::Region,
+    circuit::{Layouter, Region},
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
@@ -133,6 +133,95 @@ impl<F: FieldExt + PrimeFieldBits, const WINDOW_NUM_BITS: usize>
         self.decompose(region, offset, z_0, strict, word_num_bits, num_windows)
     }
 
+    /// Decompose an existing variable `value` in its own region, returning the
+    /// running sum $[z_0, ..., z_W]$.
+    ///
+    /// Each window $k_i = z_i - 2^K z_{i+1}$ is range-checked to `WINDOW_NUM_BITS`
+    /// bits, and the final running sum $z_W$ is constrained to be zero.
+    pub fn decompose_running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+        word_num_bits: usize,
+        num_windows: usize,
+    ) -> Result<Vec<CellValue<F>>, Error> {
+        layouter.assign_region(
+            || format!("decompose {:?}-bit value", word_num_bits),
+            |mut region| {
+                self.copy_decompose(&mut region, 0, value, true, word_num_bits, num_windows)
+                    .map(|zs| zs.0)
+            },
+        )
+    }
+
+    /// Compose the given `WINDOW_NUM_BITS`-bit words (little-endian) into a
+    /// field element, returning the running sum $[z_0, ..., z_W]$, where $z_0$
+    /// is the composed value.
+    ///
+    /// This is the inverse of [`RunningSumConfig::witness_decompose`]. The
+    /// running sum is assigned from $z_W = 0$ down to $z_0$, with
+    /// $z_i = k_i + 2^K z_{i+1}$, and each window $k_i = z_i - 2^K z_{i+1}$ is
+    /// range-checked to `WINDOW_NUM_BITS` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any known word does not fit in `WINDOW_NUM_BITS` bits.
+    pub fn compose_running_sum(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        words: &[Option<u8>],
+    ) -> Result<RunningSum<F>, Error> {
+        let num_windows = words.len();
+
+        // Enable selectors
+        for idx in 0..num_windows {
+            self.q_range_check.enable(region, offset + idx)?;
+        }
+
+        // Constrain the final running sum z_W to be zero.
+        let z_last = {
+            let cell = region.assign_advice_from_constant(
+                || format!("z_{:?}", num_windows),
+                self.z,
+                offset + num_windows,
+                F::zero(),
+            )?;
+            CellValue::new(cell, Some(F::zero()))
+        };
+
+        // Assign running sum `z_i` = k_i + (2^K) z_{i+1} for i = n-1..=0.
+        let two_pow_k = F::from_u64(1 << WINDOW_NUM_BITS as u64);
+        let mut zs: Vec<CellValue<F>> = vec![z_last];
+        let mut z = z_last;
+        for (i, word) in words.iter().enumerate().rev() {
+            if let Some(word) = word {
+                assert!((*word as usize) < (1 << WINDOW_NUM_BITS));
+            }
+
+            let z_cur = {
+                let word = word.map(|word| F::from_u64(word as u64));
+                let z_cur_val = z
+                    .value()
+                    .zip(word)
+                    .map(|(z_next_val, word)| word + z_next_val * two_pow_k);
+                let cell = region.assign_advice(
+                    || format!("z_{:?}", i),
+                    self.z,
+                    offset + i,
+                    || z_cur_val.ok_or(Error::SynthesisError),
+                )?;
+                CellValue::new(cell, z_cur_val)
+            };
+
+            z = z_cur;
+            zs.push(z);
+        }
+        zs.reverse();
+
+        Ok(RunningSum(zs))
+    }
+
     /// `z_0` must be the cell at `(self.z, offset)` in `region`.
     ///
     /// # Panics
@@ -390,4 +479,150 @@ mod tests {
             assert_eq!(prover.verify(), Ok(()));
         }
     }
+
+    #[test]
+    fn test_decompose_running_sum() {
+        // 0b101_110_011_000_111 in 3-bit windows, little-endian
+        const VALUE: u64 = 0b101_110_011_000_111;
+        const WORD_NUM_BITS: usize = 15;
+        const NUM_WINDOWS: usize = 5;
+
+        struct MyCircuit {
+            value: Option<pallas::Base>,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = RunningSumConfig<pallas::Base, FIXED_BASE_WINDOW_SIZE>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self { value: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let z = meta.advice_column();
+                let q_range_check = meta.selector();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                RunningSumConfig::configure(meta, q_range_check, z)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let value = layouter.assign_region(
+                    || "witness value",
+                    |mut region| {
+                        let cell = region.assign_advice(
+                            || "value",
+                            config.z,
+                            0,
+                            || self.value.ok_or(Error::SynthesisError),
+                        )?;
+                        Ok(CellValue::new(cell, self.value))
+                    },
+                )?;
+
+                let zs = config.decompose_running_sum(
+                    layouter.namespace(|| "decompose"),
+                    value,
+                    WORD_NUM_BITS,
+                    NUM_WINDOWS,
+                )?;
+                assert_eq!(zs.len(), NUM_WINDOWS + 1);
+
+                // Check each window k_i = z_i - 2^K z_{i+1}
+                if self.value.is_some() {
+                    let two_pow_k = pallas::Base::from_u64(1 << FIXED_BASE_WINDOW_SIZE);
+                    let expected_windows = [0b111, 0b000, 0b011, 0b110, 0b101];
+                    for (i, expected) in expected_windows.iter().enumerate() {
+                        let window =
+                            zs[i].value().unwrap() - zs[i + 1].value().unwrap() * two_pow_k;
+                        assert_eq!(window, pallas::Base::from_u64(*expected));
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        let circuit = MyCircuit {
+            value: Some(pallas::Base::from_u64(VALUE)),
+        };
+        let prover = MockProver::<pallas::Base>::run(5, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_compose_running_sum() {
+        // 0b101_110_011_000_111 in 3-bit windows, little-endian
+        const VALUE: u64 = 0b101_110_011_000_111;
+        const WORD_NUM_BITS: usize = 15;
+        const NUM_WINDOWS: usize = 5;
+
+        struct MyCircuit {
+            words: [Option<u8>; NUM_WINDOWS],
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = RunningSumConfig<pallas::Base, FIXED_BASE_WINDOW_SIZE>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self {
+                    words: [None; NUM_WINDOWS],
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let z = meta.advice_column();
+                let q_range_check = meta.selector();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                RunningSumConfig::configure(meta, q_range_check, z)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let zs = layouter.assign_region(
+                    || "compose",
+                    |mut region| config.compose_running_sum(&mut region, 0, &self.words),
+                )?;
+                assert_eq!(zs.len(), NUM_WINDOWS + 1);
+
+                // The composed value decomposes back into the same words.
+                let value = zs[0];
+                if self.words.iter().all(|word| word.is_some()) {
+                    assert_eq!(value.value(), Some(pallas::Base::from_u64(VALUE)));
+                }
+                config.decompose_running_sum(
+                    layouter.namespace(|| "decompose"),
+                    value,
+                    WORD_NUM_BITS,
+                    NUM_WINDOWS,
+                )?;
+
+                Ok(())
+            }
+        }
+
+        let circuit = MyCircuit {
+            words: [
+                Some(0b111),
+                Some(0b000),
+                Some(0b011),
+                Some(0b110),
+                Some(0b101),
+            ],
+        };
+        let prover = MockProver::<pallas::Base>::run(5, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
 }
@@ -0,0 +1,302 @@
+//! Constrain a boolean cell to be 1 if and only if a field element is zero.
+
+use super::{bool_check, copy, CellValue, Var};
+use halo2::{
+    circuit::Layouter,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::arithmetic::FieldExt;
+use std::{array, marker::PhantomData};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IsZeroConfig<F: FieldExt> {
+    pub q_is_zero: Selector,
+    pub q_equals_const: Selector,
+    // The value being checked
+    pub value: Column<Advice>,
+    // The inverse of `value`, or 0 if `value` is 0
+    pub inv: Column<Advice>,
+    // Boolean output that is 1 iff `value` is 0
+    pub is_zero: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> IsZeroConfig<F> {
+    /// Configures a gate constraining `is_zero` to be 1 if `value` is zero,
+    /// and 0 otherwise.
+    ///
+    /// A second gate constrains `is_zero` to equal `value - inv`, where `inv`
+    /// is loaded from a fixed column, for use in [`IsZeroConfig::equals_const`].
+    ///
+    /// # Side-effects
+    ///
+    /// `value`, `inv` and `is_zero` will be equality-enabled.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        inv: Column<Advice>,
+        is_zero: Column<Advice>,
+    ) -> Self {
+        meta.enable_equality(value.into());
+        meta.enable_equality(inv.into());
+        meta.enable_equality(is_zero.into());
+
+        let q_is_zero = meta.selector();
+        let q_equals_const = meta.selector();
+        let config = Self {
+            q_is_zero,
+            q_equals_const,
+            value,
+            inv,
+            is_zero,
+            _marker: PhantomData,
+        };
+
+        meta.create_gate("is zero", |meta| {
+            let q_is_zero = meta.query_selector(config.q_is_zero);
+            let value = meta.query_advice(config.value, Rotation::cur());
+            let inv = meta.query_advice(config.inv, Rotation::cur());
+            let is_zero = meta.query_advice(config.is_zero, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+
+            // is_zero = 1 - value⋅inv
+            let is_zero_check = is_zero.clone() - (one - value.clone() * inv);
+
+            // value⋅is_zero = 0
+            let value_check = value * is_zero.clone();
+
+            array::IntoIter::new([
+                ("is_zero", is_zero_check),
+                ("value", value_check),
+                ("bool_check", bool_check(is_zero)),
+            ])
+            .map(move |(name, poly)| (name, q_is_zero.clone() * poly))
+        });
+
+        meta.create_gate("equals constant", |meta| {
+            let q_equals_const = meta.query_selector(config.q_equals_const);
+            let value = meta.query_advice(config.value, Rotation::cur());
+            let constant = meta.query_advice(config.inv, Rotation::cur());
+            let diff = meta.query_advice(config.is_zero, Rotation::cur());
+
+            // diff = value - constant
+            vec![("diff", q_equals_const * (value - constant - diff))]
+        });
+
+        config
+    }
+
+    /// Returns a boolean cell that is 1 if `value` is zero, and 0 otherwise.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                self.q_is_zero.enable(&mut region, 0)?;
+
+                // Copy in `value`
+                copy(&mut region, || "value", self.value, 0, &value)?;
+
+                // Witness the inverse of `value`, or 0 if `value` is 0
+                let inv = value
+                    .value()
+                    .map(|value| value.invert().unwrap_or(F::zero()));
+                region.assign_advice(|| "inv", self.inv, 0, || inv.ok_or(Error::SynthesisError))?;
+
+                // Witness the boolean output
+                let is_zero = value.value().map(|value| F::from(value == F::zero()));
+                let cell = region.assign_advice(
+                    || "is_zero",
+                    self.is_zero,
+                    0,
+                    || is_zero.ok_or(Error::SynthesisError),
+                )?;
+
+                Ok(CellValue::new(cell, is_zero))
+            },
+        )
+    }
+
+    /// Returns a boolean cell that is 1 if `value` equals `constant`, and 0
+    /// otherwise.
+    ///
+    /// `constant` is loaded from the fixed column passed to
+    /// [`ConstraintSystem::enable_constant`] during circuit configuration.
+    pub fn equals_const(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+        constant: F,
+    ) -> Result<CellValue<F>, Error> {
+        let diff = layouter.assign_region(
+            || "value - constant",
+            |mut region| {
+                self.q_equals_const.enable(&mut region, 0)?;
+
+                // Copy in `value`
+                copy(&mut region, || "value", self.value, 0, &value)?;
+
+                // Load `constant`
+                region.assign_advice_from_constant(|| "constant", self.inv, 0, constant)?;
+
+                // Witness `value - constant`
+                let diff = value.value().map(|value| value - constant);
+                let cell = region.assign_advice(
+                    || "value - constant",
+                    self.is_zero,
+                    0,
+                    || diff.ok_or(Error::SynthesisError),
+                )?;
+
+                Ok(CellValue::new(cell, diff))
+            },
+        )?;
+
+        self.assign(layouter.namespace(|| "is zero"), diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsZeroConfig;
+    use crate::utilities::{CellValue, UtilitiesInstructions, Var};
+
+    use ff::Field;
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    #[test]
+    fn is_zero() {
+        struct MyCircuit(Option<pallas::Base>);
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = IsZeroConfig<pallas::Base>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit(None)
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let value = meta.advice_column();
+                let inv = meta.advice_column();
+                let is_zero = meta.advice_column();
+                IsZeroConfig::configure(meta, value, inv, is_zero)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let value =
+                    self.load_private(layouter.namespace(|| "value"), config.value, self.0)?;
+                let is_zero = config.assign(layouter.namespace(|| "is zero"), value)?;
+
+                if let Some(value) = self.0 {
+                    assert_eq!(
+                        is_zero.value(),
+                        Some(pallas::Base::from(value == pallas::Base::zero()))
+                    );
+                }
+
+                Ok(())
+            }
+        }
+
+        for value in [
+            pallas::Base::zero(),
+            pallas::Base::one(),
+            -pallas::Base::one(),
+            pallas::Base::from_u64(0xdeadbeef),
+            pallas::Base::rand(),
+        ]
+        .iter()
+        {
+            let prover =
+                MockProver::<pallas::Base>::run(3, &MyCircuit(Some(*value)), vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn equals_const() {
+        struct MyCircuit {
+            value: Option<pallas::Base>,
+            constant: pallas::Base,
+        }
+
+        impl UtilitiesInstructions<pallas::Base> for MyCircuit {
+            type Var = CellValue<pallas::Base>;
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = IsZeroConfig<pallas::Base>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    value: None,
+                    constant: self.constant,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let value = meta.advice_column();
+                let inv = meta.advice_column();
+                let is_zero = meta.advice_column();
+
+                // Shared fixed column for loading constants
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                IsZeroConfig::configure(meta, value, inv, is_zero)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                let value =
+                    self.load_private(layouter.namespace(|| "value"), config.value, self.value)?;
+                let equals = config.equals_const(
+                    layouter.namespace(|| "equals constant"),
+                    value,
+                    self.constant,
+                )?;
+
+                if let Some(value) = self.value {
+                    assert_eq!(
+                        equals.value(),
+                        Some(pallas::Base::from(value == self.constant))
+                    );
+                }
+
+                Ok(())
+            }
+        }
+
+        for (value, constant) in [(7, 7), (7, 8), (0, 0), (0, 1), (0xdead, 0xbeef)].iter() {
+            let circuit = MyCircuit {
+                value: Some(pallas::Base::from_u64(*value)),
+                constant: pallas::Base::from_u64(*constant),
+            };
+            let prover = MockProver::<pallas::Base>::run(4, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
+}
@@ -34,11 +34,30 @@ impl<F: FieldExt + PrimeFieldBits> std::ops::Deref for RunningSum<F> {
     }
 }
 
+/// Records which lookup tables have been loaded during a single synthesis.
+///
+/// Create a fresh one at the start of each call to `Circuit::synthesize`, and
+/// pass it to [`LookupRangeCheckConfig::ensure_loaded`]. It must not outlive
+/// the synthesis, since every synthesis needs to load its tables again.
+#[derive(Debug, Default)]
+pub struct TableLoadState {
+    loaded: Vec<TableColumn>,
+}
+
+impl TableLoadState {
+    /// Returns `true` if `table_idx` has been loaded during this synthesis.
+    pub fn is_loaded(&self, table_idx: TableColumn) -> bool {
+        self.loaded.contains(&table_idx)
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub struct LookupRangeCheckConfig<F: FieldExt + PrimeFieldBits, const K: usize> {
     pub q_lookup: Selector,
     pub q_running: Selector,
     pub q_bitshift: Selector,
+    pub q_less_than: Selector,
+    pub q_limb: Selector,
     pub running_sum: Column<Advice>,
     table_idx: TableColumn,
     _marker: PhantomData<F>,
@@ -66,10 +85,14 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
         let q_lookup = meta.complex_selector();
         let q_running = meta.complex_selector();
         let q_bitshift = meta.selector();
+        let q_less_than = meta.selector();
+        let q_limb = meta.selector();
         let config = LookupRangeCheckConfig {
             q_lookup,
             q_running,
             q_bitshift,
+            q_less_than,
+            q_limb,
             running_sum,
             table_idx,
             _marker: PhantomData,
@@ -122,14 +145,45 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
             vec![q_bitshift * (word * two_pow_k * inv_two_pow_s - shifted_word)]
         });
 
+        // For bounded checks, check that the difference to the bound is correctly derived.
+        meta.create_gate("Less than bound", |meta| {
+            let q_less_than = meta.query_selector(config.q_less_than);
+            let value = meta.query_advice(config.running_sum, Rotation::cur());
+            let bound_minus_one = meta.query_advice(config.running_sum, Rotation::next());
+            let diff = meta.query_advice(config.running_sum, Rotation(2));
+
+            // diff = (bound - 1) - value
+            vec![q_less_than * (bound_minus_one - value - diff)]
+        });
+
+        // For limb extraction, check that each limb is the difference of the
+        // running sums it sits between.
+        meta.create_gate("Running sum limb", |meta| {
+            let q_limb = meta.query_selector(config.q_limb);
+            let z_cur = meta.query_advice(config.running_sum, Rotation::cur());
+            let limb = meta.query_advice(config.running_sum, Rotation::next());
+            let z_next = meta.query_advice(config.running_sum, Rotation(2));
+
+            // a_i = z_i - 2^{K}⋅z_{i + 1}
+            vec![q_limb * (z_cur - z_next * F::from_u64(1 << K) - limb)]
+        });
+
         config
     }
 
-    #[cfg(test)]
-    // Loads the values [0..2^K) into `table_idx`. This is only used in testing
-    // for now, since the Sinsemilla chip provides a pre-loaded table in the
-    // Orchard context.
+    /// Loads the values [0..2^K) into `table_idx`.
+    ///
+    /// In the Orchard context, the Sinsemilla chip provides a pre-loaded table
+    /// and this does not need to be called.
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        Self::load_into(layouter, self.table_idx)
+    }
+
+    /// Loads the values [0..2^K) into the given table column.
+    ///
+    /// Configs that share a table column only need it to be loaded once. Configs
+    /// that use distinct table columns each need their own table loaded.
+    pub fn load_into(layouter: &mut impl Layouter<F>, table_idx: TableColumn) -> Result<(), Error> {
         layouter.assign_table(
             || "table_idx",
             |mut table| {
@@ -137,7 +191,7 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
                 for index in 0..(1 << K) {
                     table.assign_cell(
                         || "table_idx",
-                        self.table_idx,
+                        table_idx,
                         index,
                         || Ok(F::from_u64(index as u64)),
                     )?;
@@ -147,6 +201,28 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
         )
     }
 
+    /// Loads the values [0..2^K) into `table_idx`, unless `state` records that
+    /// it was already loaded during this synthesis.
+    ///
+    /// Unlike [`LookupRangeCheckConfig::load`], this is safe to call from
+    /// every gadget that needs the table.
+    pub fn ensure_loaded(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &mut TableLoadState,
+    ) -> Result<(), Error> {
+        if !state.is_loaded(self.table_idx) {
+            self.load(layouter)?;
+            state.loaded.push(self.table_idx);
+        }
+        Ok(())
+    }
+
+    /// Returns the table column this config looks up into.
+    pub fn table_idx(&self) -> TableColumn {
+        self.table_idx
+    }
+
     /// Range check on an existing cell that is copied into this helper.
     ///
     /// Returns an error if `element` is not in a column that was passed to
@@ -193,6 +269,126 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
         )
     }
 
+    /// Constrains an existing cell to be less than `bound`, which need not be a
+    /// power of two.
+    ///
+    /// Both `value` and `bound - 1 - value` are range-checked to the number of
+    /// `K`-bit words needed to represent `bound - 1`. Since neither can wrap
+    /// around the field modulus, this enforces `value < bound`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn witness_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+        bound: u64,
+    ) -> Result<(), Error> {
+        assert!(bound > 0);
+        let num_bits = 64 - (bound - 1).leading_zeros() as usize;
+        let num_words = (num_bits + K - 1) / K;
+
+        // Range check `value`.
+        self.copy_check(layouter.namespace(|| "value"), value, num_words, true)?;
+
+        // Witness `bound - 1 - value`.
+        let diff = layouter.assign_region(
+            || format!("{:?} - 1 - value", bound),
+            |mut region| {
+                self.q_less_than.enable(&mut region, 0)?;
+
+                copy(&mut region, || "value", self.running_sum, 0, &value)?;
+
+                let bound_minus_one = F::from_u64(bound - 1);
+                region.assign_advice_from_constant(
+                    || "bound - 1",
+                    self.running_sum,
+                    1,
+                    bound_minus_one,
+                )?;
+
+                let diff = value.value().map(|value| bound_minus_one - value);
+                let cell = region.assign_advice(
+                    || "bound - 1 - value",
+                    self.running_sum,
+                    2,
+                    || diff.ok_or(Error::SynthesisError),
+                )?;
+                Ok(CellValue::new(cell, diff))
+            },
+        )?;
+
+        // Range check `bound - 1 - value`.
+        self.copy_check(
+            layouter.namespace(|| "bound - 1 - value"),
+            diff,
+            num_words,
+            true,
+        )?;
+
+        Ok(())
+    }
+
+    /// Range check on an existing cell that is copied into this helper, in
+    /// strict mode, additionally returning the `K`-bit limbs of the value.
+    ///
+    /// Returns the checked value together with the limbs $[a_0, ..., a_{W-1}]$
+    /// in little-endian order, where `W = num_words`. Each limb is constrained
+    /// to equal $z_i - 2^K \cdot z_{i + 1}$ for the running sum produced by the
+    /// range check, so it is known to lie in $[0, 2^K)$.
+    pub fn copy_check_with_limbs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+        num_words: usize,
+    ) -> Result<(CellValue<F>, Vec<CellValue<F>>), Error> {
+        // Range check `value`.
+        let zs = self.copy_check(layouter.namespace(|| "value"), value, num_words, true)?;
+
+        // Extract the limbs from adjacent running sums.
+        let limbs = layouter.assign_region(
+            || format!("{:?} limbs", num_words),
+            |mut region| {
+                let two_pow_k = F::from_u64(1 << K);
+
+                // Rows alternate between running sums and limbs:
+                //   z_0, a_0, z_1, a_1, ..., a_{W-1}, z_W
+                copy(&mut region, || "z_0", self.running_sum, 0, &zs[0])?;
+
+                let mut limbs = Vec::with_capacity(num_words);
+                for (idx, z) in zs.windows(2).enumerate() {
+                    let offset = 2 * idx;
+                    self.q_limb.enable(&mut region, offset)?;
+
+                    let limb = z[0]
+                        .value()
+                        .zip(z[1].value())
+                        .map(|(z_cur, z_next)| z_cur - z_next * two_pow_k);
+                    let cell = region.assign_advice(
+                        || format!("a_{:?}", idx),
+                        self.running_sum,
+                        offset + 1,
+                        || limb.ok_or(Error::SynthesisError),
+                    )?;
+                    limbs.push(CellValue::new(cell, limb));
+
+                    copy(
+                        &mut region,
+                        || format!("z_{:?}", idx + 1),
+                        self.running_sum,
+                        offset + 2,
+                        &z[1],
+                    )?;
+                }
+
+                Ok(limbs)
+            },
+        )?;
+
+        Ok((zs[0], limbs))
+    }
+
     /// If `strict` is set to "true", the field element must fit into
     /// `num_words * K` bits. In other words, the the final cumulative sum `z_{num_words}`
     /// must be zero.
@@ -343,10 +539,10 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
         element: CellValue<F>,
         num_bits: usize,
     ) -> Result<(), Error> {
-        // Enable lookup for `element`, to constrain it to 10 bits.
+        // Enable lookup for `element`, to constrain it to K bits.
         self.q_lookup.enable(region, 0)?;
 
-        // Enable lookup for shifted element, to constrain it to 10 bits.
+        // Enable lookup for shifted element, to constrain it to K bits.
         self.q_lookup.enable(region, 1)?;
 
         // Check element has been shifted by the correct number of bits.
@@ -380,8 +576,8 @@ impl<F: FieldExt + PrimeFieldBits, const K: usize> LookupRangeCheckConfig<F, K>
 
 #[cfg(test)]
 mod tests {
-    use super::super::Var;
-    use super::{lebs2ip, LookupRangeCheckConfig};
+    use super::super::{CellValue, Var};
+    use super::{lebs2ip, LookupRangeCheckConfig, TableLoadState};
 
     use crate::primitives::sinsemilla::{INV_TWO_POW_K, K};
     use ff::{Field, PrimeFieldBits};
@@ -394,6 +590,72 @@ mod tests {
 
     use std::{convert::TryInto, marker::PhantomData};
 
+    #[test]
+    fn ensure_loaded() {
+        #[derive(Clone, Copy)]
+        struct MyCircuit {
+            // Load the table through `ensure_loaded` rather than `load`.
+            ensure: bool,
+        }
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = LookupRangeCheckConfig<pallas::Base, K>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                *self
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let running_sum = meta.advice_column();
+                let table_idx = meta.lookup_table_column();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                LookupRangeCheckConfig::<pallas::Base, K>::configure(meta, running_sum, table_idx)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                // Two gadgets that each need the table.
+                if self.ensure {
+                    let mut state = TableLoadState::default();
+                    assert!(!state.is_loaded(config.table_idx()));
+                    config.ensure_loaded(&mut layouter, &mut state)?;
+                    assert!(state.is_loaded(config.table_idx()));
+                    config.ensure_loaded(&mut layouter, &mut state)?;
+                } else {
+                    config.load(&mut layouter)?;
+                    config.load(&mut layouter)?;
+                }
+
+                config.witness_check(
+                    layouter.namespace(|| "witness_check"),
+                    Some(pallas::Base::from_u64(1 << K)),
+                    2,
+                    true,
+                )?;
+                Ok(())
+            }
+        }
+
+        // The floor planner rejects a table column that is filled twice, so a
+        // successful run means the second call did not fill it again.
+        {
+            let circuit = MyCircuit { ensure: true };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        {
+            let circuit = MyCircuit { ensure: false };
+            assert!(MockProver::<pallas::Base>::run(11, &circuit, vec![]).is_err());
+        }
+    }
+
     #[test]
     fn lookup_range_check() {
         #[derive(Clone, Copy)]
@@ -636,4 +898,320 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn lookup_range_check_8_bits() {
+        const K: usize = 8;
+
+        struct MyCircuit<F: FieldExt + PrimeFieldBits> {
+            element: Option<F>,
+        }
+
+        impl<F: FieldExt + PrimeFieldBits> Circuit<F> for MyCircuit<F> {
+            type Config = LookupRangeCheckConfig<F, K>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { element: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let running_sum = meta.advice_column();
+                let table_idx = meta.lookup_table_column();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                LookupRangeCheckConfig::<F, K>::configure(meta, running_sum, table_idx)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                // Load the 8-bit table
+                config.load(&mut layouter)?;
+
+                // Constrain element to be no longer than 3 * 8 = 24 bits.
+                config.witness_check(
+                    layouter.namespace(|| "Lookup 24 bits"),
+                    self.element,
+                    3,
+                    true,
+                )?;
+
+                Ok(())
+            }
+        }
+
+        // Element within 24 bits
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64((1 << 24) - 1)),
+            };
+            let prover = MockProver::<pallas::Base>::run(9, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Element just over 24 bits
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64(1 << 24)),
+            };
+            let prover = MockProver::<pallas::Base>::run(9, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn independent_tables() {
+        struct MyCircuit<F: FieldExt + PrimeFieldBits> {
+            // Checked against an 8-bit table
+            wide: Option<F>,
+            // Checked against a 4-bit table
+            narrow: Option<F>,
+        }
+
+        impl<F: FieldExt + PrimeFieldBits> Circuit<F> for MyCircuit<F> {
+            type Config = (LookupRangeCheckConfig<F, 8>, LookupRangeCheckConfig<F, 4>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    wide: None,
+                    narrow: None,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                let wide = {
+                    let running_sum = meta.advice_column();
+                    let table_idx = meta.lookup_table_column();
+                    LookupRangeCheckConfig::<F, 8>::configure(meta, running_sum, table_idx)
+                };
+                let narrow = {
+                    let running_sum = meta.advice_column();
+                    let table_idx = meta.lookup_table_column();
+                    LookupRangeCheckConfig::<F, 4>::configure(meta, running_sum, table_idx)
+                };
+                (wide, narrow)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (wide, narrow) = config;
+
+                // Load each table into its own column.
+                LookupRangeCheckConfig::<F, 8>::load_into(&mut layouter, wide.table_idx())?;
+                LookupRangeCheckConfig::<F, 4>::load_into(&mut layouter, narrow.table_idx())?;
+
+                wide.witness_check(layouter.namespace(|| "8 bits"), self.wide, 1, true)?;
+                narrow.witness_check(layouter.namespace(|| "4 bits"), self.narrow, 1, true)?;
+
+                Ok(())
+            }
+        }
+
+        let run = |wide: u64, narrow: u64| {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                wide: Some(pallas::Base::from_u64(wide)),
+                narrow: Some(pallas::Base::from_u64(narrow)),
+            };
+            MockProver::<pallas::Base>::run(9, &circuit, vec![])
+                .unwrap()
+                .verify()
+        };
+
+        // Both values are within the range of their own tables.
+        assert_eq!(run(255, 15), Ok(()));
+
+        // 255 is in the 8-bit table, but the 4-bit check only looks up its own table.
+        assert!(run(15, 255).is_err());
+
+        // 256 is not in the 8-bit table.
+        assert!(run(256, 15).is_err());
+    }
+
+    #[test]
+    fn less_than_check() {
+        struct MyCircuit<F: FieldExt + PrimeFieldBits> {
+            element: Option<F>,
+            bound: u64,
+        }
+
+        impl<F: FieldExt + PrimeFieldBits> Circuit<F> for MyCircuit<F> {
+            type Config = LookupRangeCheckConfig<F, K>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    element: None,
+                    bound: self.bound,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let running_sum = meta.advice_column();
+                let table_idx = meta.lookup_table_column();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                LookupRangeCheckConfig::<F, K>::configure(meta, running_sum, table_idx)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                // Load table_idx
+                config.load(&mut layouter)?;
+
+                let element = layouter.assign_region(
+                    || "Witness element",
+                    |mut region| {
+                        let cell = region.assign_advice(
+                            || "Witness element",
+                            config.running_sum,
+                            0,
+                            || self.element.ok_or(Error::SynthesisError),
+                        )?;
+                        Ok(CellValue::new(cell, self.element))
+                    },
+                )?;
+
+                config.witness_less_than(
+                    layouter.namespace(|| format!("Less than {:?}", self.bound)),
+                    element,
+                    self.bound,
+                )
+            }
+        }
+
+        // Element less than the bound
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64(999)),
+                bound: 1000,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Element equal to the bound
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64(1000)),
+                bound: 1000,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn copy_check_with_limbs() {
+        struct MyCircuit<F: FieldExt + PrimeFieldBits> {
+            element: Option<F>,
+            num_words: usize,
+        }
+
+        impl<F: FieldExt + PrimeFieldBits> Circuit<F> for MyCircuit<F> {
+            type Config = LookupRangeCheckConfig<F, K>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    element: None,
+                    num_words: self.num_words,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let running_sum = meta.advice_column();
+                let table_idx = meta.lookup_table_column();
+                let constants = meta.fixed_column();
+                meta.enable_constant(constants);
+
+                LookupRangeCheckConfig::<F, K>::configure(meta, running_sum, table_idx)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                // Load table_idx
+                config.load(&mut layouter)?;
+
+                let element = layouter.assign_region(
+                    || "Witness element",
+                    |mut region| {
+                        let cell = region.assign_advice(
+                            || "Witness element",
+                            config.running_sum,
+                            0,
+                            || self.element.ok_or(Error::SynthesisError),
+                        )?;
+                        Ok(CellValue::new(cell, self.element))
+                    },
+                )?;
+
+                let (checked, limbs) = config.copy_check_with_limbs(
+                    layouter.namespace(|| "Limbs"),
+                    element,
+                    self.num_words,
+                )?;
+                assert_eq!(limbs.len(), self.num_words);
+
+                // Reconstruct the value from its limbs.
+                let limbs: Option<Vec<F>> = limbs.iter().map(|limb| limb.value()).collect();
+                if let (Some(element), Some(checked), Some(limbs)) =
+                    (self.element, checked.value(), limbs)
+                {
+                    let recomposed = limbs
+                        .iter()
+                        .rev()
+                        .fold(F::zero(), |acc, limb| acc * F::from_u64(1 << K) + limb);
+                    assert_eq!(checked, element);
+                    if element
+                        .to_le_bits()
+                        .iter()
+                        .skip(self.num_words * K)
+                        .all(|b| !*b)
+                    {
+                        assert_eq!(recomposed, element);
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        // Element within num_words * K bits
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64(0x2345_6789)),
+                num_words: 3,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // Element just over num_words * K bits
+        {
+            let circuit: MyCircuit<pallas::Base> = MyCircuit {
+                element: Some(pallas::Base::from_u64(1 << (3 * K))),
+                num_words: 3,
+            };
+            let prover = MockProver::<pallas::Base>::run(11, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
 }
@@ -0,0 +1,167 @@
+//! Range check a field element to a small number of bits using a single
+//! custom gate, without a lookup table.
+
+use halo2::{
+    circuit::{Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use super::*;
+
+/// The maximum number of bits that can be range-checked. The degree of the
+/// gate grows as `2^num_bits`, so this is kept small.
+pub const MAX_NUM_BITS: usize = 5;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct RangeCheckSmallConfig<F: FieldExt> {
+    pub q_range_check: Selector,
+    pub value: Column<Advice>,
+    num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> RangeCheckSmallConfig<F> {
+    /// The `value` advice column contains the field element being constrained
+    /// to `num_bits` bits, such that
+    ///     value⋅(value - 1)⋅...⋅(value - (2^num_bits - 1)) = 0.
+    ///
+    /// # Side-effects
+    ///
+    /// `value` will be equality-enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` is larger than [`MAX_NUM_BITS`].
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        num_bits: usize,
+    ) -> Self {
+        assert!(num_bits <= MAX_NUM_BITS);
+
+        meta.enable_equality(value.into());
+
+        let q_range_check = meta.selector();
+        let config = Self {
+            q_range_check,
+            value,
+            num_bits,
+            _marker: PhantomData,
+        };
+
+        meta.create_gate("small range check", |meta| {
+            let q_range_check = meta.query_selector(config.q_range_check);
+            let value = meta.query_advice(config.value, Rotation::cur());
+
+            vec![q_range_check * range_check(value, 1 << num_bits)]
+        });
+
+        config
+    }
+
+    /// Range check on an existing cell that is copied into this helper.
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        layouter.assign_region(
+            || format!("Range check {:?} bits", self.num_bits),
+            |mut region| {
+                let value = copy(&mut region, || "value", self.value, 0, &value)?;
+                self.range_check(&mut region, value)
+            },
+        )
+    }
+
+    /// Range check on a value that is witnessed in this helper.
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Option<F>,
+    ) -> Result<CellValue<F>, Error> {
+        layouter.assign_region(
+            || format!("Range check {:?} bits", self.num_bits),
+            |mut region| {
+                let cell = region.assign_advice(
+                    || "value",
+                    self.value,
+                    0,
+                    || value.ok_or(Error::SynthesisError),
+                )?;
+                self.range_check(&mut region, CellValue::new(cell, value))
+            },
+        )
+    }
+
+    /// `value` must have been assigned to `self.value` at offset 0.
+    fn range_check(
+        &self,
+        region: &mut Region<'_, F>,
+        value: CellValue<F>,
+    ) -> Result<CellValue<F>, Error> {
+        self.q_range_check.enable(region, 0)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeCheckSmallConfig;
+
+    use halo2::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+
+    #[test]
+    fn range_check_small() {
+        struct MyCircuit(u64);
+
+        impl Circuit<pallas::Base> for MyCircuit {
+            type Config = RangeCheckSmallConfig<pallas::Base>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit(self.0)
+            }
+
+            fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+                let value = meta.advice_column();
+                RangeCheckSmallConfig::configure(meta, value, 3)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<pallas::Base>,
+            ) -> Result<(), Error> {
+                config.witness_check(
+                    layouter.namespace(|| "range check 3 bits"),
+                    Some(pallas::Base::from_u64(self.0)),
+                )?;
+                Ok(())
+            }
+        }
+
+        for i in 0..8 {
+            let prover = MockProver::<pallas::Base>::run(4, &MyCircuit(i), vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        {
+            let prover = MockProver::<pallas::Base>::run(4, &MyCircuit(8), vec![]).unwrap();
+            assert_eq!(
+                prover.verify(),
+                Err(vec![VerifyFailure::ConstraintNotSatisfied {
+                    constraint: ((0, "small range check").into(), 0, "").into(),
+                    row: 0
+                }])
+            );
+        }
+    }
+}